@@ -0,0 +1,393 @@
+//! Export to the formats of Bible software: [OSIS](https://crosswire.org/osis/) and
+//! [USFM](https://ubsicap.github.io/usfm/).
+//!
+//! Both formats are keyed by verse, not by page. The text is split at the verse anchors of a
+//! single versification scheme (see [`contents`](crate::contents)), and every verse is rendered
+//! as plain text with the same [`RenderRules`] as ground truth, where dropping text drops the
+//! whole verse. Text before the first anchor of the scheme is not exported.
+
+use crate::{
+    contents::VerseId,
+    ground_truth::RenderRules,
+    streamed::{self, Block},
+};
+
+/// The OSIS abbreviations of the books of the protestant canon with their USFM book codes.
+const BOOK_CODES: [(&str, &str); 66] = [
+    ("Gen", "GEN"),
+    ("Exod", "EXO"),
+    ("Lev", "LEV"),
+    ("Num", "NUM"),
+    ("Deut", "DEU"),
+    ("Josh", "JOS"),
+    ("Judg", "JDG"),
+    ("Ruth", "RUT"),
+    ("1Sam", "1SA"),
+    ("2Sam", "2SA"),
+    ("1Kgs", "1KI"),
+    ("2Kgs", "2KI"),
+    ("1Chr", "1CH"),
+    ("2Chr", "2CH"),
+    ("Ezra", "EZR"),
+    ("Neh", "NEH"),
+    ("Esth", "EST"),
+    ("Job", "JOB"),
+    ("Ps", "PSA"),
+    ("Prov", "PRO"),
+    ("Eccl", "ECC"),
+    ("Song", "SNG"),
+    ("Isa", "ISA"),
+    ("Jer", "JER"),
+    ("Lam", "LAM"),
+    ("Ezek", "EZK"),
+    ("Dan", "DAN"),
+    ("Hos", "HOS"),
+    ("Joel", "JOL"),
+    ("Amos", "AMO"),
+    ("Obad", "OBA"),
+    ("Jonah", "JON"),
+    ("Mic", "MIC"),
+    ("Nah", "NAM"),
+    ("Hab", "HAB"),
+    ("Zeph", "ZEP"),
+    ("Hag", "HAG"),
+    ("Zech", "ZEC"),
+    ("Mal", "MAL"),
+    ("Matt", "MAT"),
+    ("Mark", "MRK"),
+    ("Luke", "LUK"),
+    ("John", "JHN"),
+    ("Acts", "ACT"),
+    ("Rom", "ROM"),
+    ("1Cor", "1CO"),
+    ("2Cor", "2CO"),
+    ("Gal", "GAL"),
+    ("Eph", "EPH"),
+    ("Phil", "PHP"),
+    ("Col", "COL"),
+    ("1Thess", "1TH"),
+    ("2Thess", "2TH"),
+    ("1Tim", "1TI"),
+    ("2Tim", "2TI"),
+    ("Titus", "TIT"),
+    ("Phlm", "PHM"),
+    ("Heb", "HEB"),
+    ("Jas", "JAS"),
+    ("1Pet", "1PE"),
+    ("2Pet", "2PE"),
+    ("1John", "1JN"),
+    ("2John", "2JN"),
+    ("3John", "3JN"),
+    ("Jude", "JUD"),
+    ("Rev", "REV"),
+];
+
+/// The USFM book code of the book with the OSIS abbreviation `osis_book`.
+#[must_use]
+pub fn usfm_book_code(osis_book: &str) -> Option<&'static str> {
+    BOOK_CODES
+        .iter()
+        .find(|(osis, _)| *osis == osis_book)
+        .map(|(_, usfm)| *usfm)
+}
+
+/// The OSIS namespace.
+pub const OSIS_NAMESPACE: &str = "http://www.bibletechnologies.net/2003/OSIS/namespace";
+
+/// The text of a single verse.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Verse {
+    pub id: VerseId,
+    /// The text from this verse anchor to the next, rendered with [`RenderRules`]
+    pub text: String,
+}
+
+/// The verses in `blocks`, split at the anchors whose `@type` is `scheme`.
+///
+/// Verses dropped by `rules` are left out.
+#[must_use]
+pub fn verses(blocks: &[Block], scheme: &str, rules: &RenderRules) -> Vec<Verse> {
+    let starts = blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, block)| match block {
+            Block::Anchor(anchor) if anchor.anchor_type == scheme => {
+                VerseId::from_anchor_id(&anchor.anchor_id).map(|(_, id)| (idx, id))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (start, id))| {
+            let end = starts.get(i + 1).map_or(blocks.len(), |(end, _)| *end);
+            rules.render(&blocks[start + 1..end]).map(|text| Verse {
+                id: id.clone(),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// The problems that can occur when exporting to USFM.
+#[derive(Debug, PartialEq, Eq)]
+pub enum UsfmError {
+    /// There is no USFM book code for this OSIS book
+    UnknownBook(String),
+}
+impl core::fmt::Display for UsfmError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::UnknownBook(x) => {
+                write!(f, "The book \"{x}\" has no USFM book code.")
+            }
+        }
+    }
+}
+impl core::error::Error for UsfmError {}
+
+/// Export the verses of `ms` in the scheme `scheme` as USFM.
+///
+/// USFM expects one file per book, so this returns the book code and content of one file for
+/// each book, in the order the books first appear. USFM cannot escape the `\` that starts a
+/// marker, so backslashes in the title and the verses are left out.
+///
+/// # Errors
+/// If a book has no USFM book code.
+pub fn to_usfm(
+    ms: &streamed::Manuscript,
+    scheme: &str,
+    rules: &RenderRules,
+) -> Result<Vec<(String, String)>, UsfmError> {
+    let mut files = Vec::<(String, String)>::new();
+    let mut chapter = None;
+    for verse in verses(&ms.content, scheme, rules) {
+        let code = usfm_book_code(&verse.id.book)
+            .ok_or_else(|| UsfmError::UnknownBook(verse.id.book.clone()))?;
+        let idx = match files.iter().position(|(c, _)| c == code) {
+            Some(idx) => idx,
+            None => {
+                files.push((
+                    code.to_string(),
+                    format!(
+                        "\\id {code} {}\n\\usfm 3.0\n",
+                        without_markers(&ms.meta.title)
+                    ),
+                ));
+                chapter = None;
+                files.len() - 1
+            }
+        };
+        let content = &mut files[idx].1;
+        if chapter != Some((idx, verse.id.chapter)) {
+            chapter = Some((idx, verse.id.chapter));
+            content.push_str(&format!("\\c {}\n\\p\n", verse.id.chapter));
+        }
+        content.push_str(&format!(
+            "\\v {} {}\n",
+            verse.id.verse,
+            without_markers(&verse.text)
+        ));
+    }
+    Ok(files)
+}
+
+/// `text` without the backslashes that would start USFM markers.
+fn without_markers(text: &str) -> String {
+    text.replace('\\', "")
+}
+
+/// Export the verses of `ms` in the scheme `scheme` as an OSIS document for the work `work`.
+#[must_use]
+pub fn to_osis(ms: &streamed::Manuscript, scheme: &str, work: &str, rules: &RenderRules) -> String {
+    let escape = |s: &str| quick_xml::escape::escape(s).into_owned();
+    let mut res = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<osis xmlns=\"{OSIS_NAMESPACE}\">\n<osisText osisIDWork=\"{work}\" osisRefWork=\"Bible\">\n<header><work osisWork=\"{work}\"><title>{title}</title><refSystem>Bible</refSystem></work></header>\n",
+        work = escape(work),
+        title = escape(&ms.meta.title),
+    );
+    let mut current: Option<(String, u32)> = None;
+    for verse in verses(&ms.content, scheme, rules) {
+        let VerseId {
+            book,
+            chapter,
+            verse: n,
+        } = &verse.id;
+        let same_book = current.as_ref().is_some_and(|(b, _)| b == book);
+        if current.as_ref() != Some(&(book.clone(), *chapter)) {
+            if current.is_some() {
+                res.push_str("</chapter>\n");
+                if !same_book {
+                    res.push_str("</div>\n");
+                }
+            }
+            if !same_book {
+                res.push_str(&format!(
+                    "<div type=\"book\" osisID=\"{}\">\n",
+                    escape(book)
+                ));
+            }
+            res.push_str(&format!(
+                "<chapter osisID=\"{}.{chapter}\">\n",
+                escape(book)
+            ));
+            current = Some((book.clone(), *chapter));
+        }
+        res.push_str(&format!(
+            "<verse osisID=\"{book}.{chapter}.{n}\">{}</verse>\n",
+            escape(&verse.text),
+            book = escape(book),
+        ));
+    }
+    if current.is_some() {
+        res.push_str("</chapter>\n</div>\n");
+    }
+    res.push_str("</osisText>\n</osis>\n");
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ground_truth::{AbbreviationRule, CorrectionRule, LacunaRule};
+
+    /// The text as it is visible on the page, with a `.` for each missing character.
+    fn visible() -> RenderRules {
+        RenderRules {
+            lacuna: LacunaRule::Mark('.'),
+            ..Default::default()
+        }
+    }
+
+    fn wlc_sample() -> streamed::Manuscript {
+        let xml = std::fs::File::open("examples/wlc_sample.xml").unwrap();
+        crate::wlc::from_wlc(std::io::BufReader::new(xml)).unwrap()
+    }
+
+    #[test]
+    fn usfm() {
+        let rules = RenderRules {
+            abbreviation: AbbreviationRule::Expansion,
+            ..visible()
+        };
+        let files = to_usfm(&wlc_sample(), "Masoretic", &rules).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "GEN");
+        assert_eq!(
+            files[0].1,
+            concat!(
+                "\\id GEN Westminster Leningrad Codex\n",
+                "\\usfm 3.0\n",
+                "\\c 1\n",
+                "\\p\n",
+                "\\v 1 בְּרֵאשִׁ֖ית בָּרָ֣א אֱלֹהִ֑ים אֵ֥ת הַשָּׁמַ֖יִם וְאֵ֥ת הָאָֽרֶץ׃\n",
+                "\\v 2 וְהָאָ֗רֶץ הָיְתָ֥ה תֹ֨הוּ֙ וָבֹ֔הוּ וְחֹ֖שֶׁךְ עַל־פְּנֵ֣י תְה֑וֹם וְר֣וּחַ אֱלֹהִ֔ים מְרַחֶ֖פֶת עַל־פְּנֵ֥י הַמָּֽיִם׃\n",
+                "\\c 8\n",
+                "\\p\n",
+                "\\v 17 כָּל־הַחַיָּ֨ה אֲשֶֽׁר־אִתְּךָ֜ הַיְצֵ֣א אִתָּ֑ךְ\n",
+            )
+        );
+        // other schemes are not exported
+        assert_eq!(to_usfm(&wlc_sample(), "Septuagint", &rules), Ok(vec![]));
+    }
+
+    /// Backslashes in the text cannot start markers.
+    #[test]
+    fn usfm_markers_in_text() {
+        let mut ms = wlc_sample();
+        ms.meta.title = "WLC \\toc1 Genesis".to_string();
+        if let Some(Block::Text(p)) = ms.content.iter_mut().find(|b| matches!(b, Block::Text(_))) {
+            p.content = "in \\v 99 principio\\".to_string();
+        }
+        let files = to_usfm(&ms, "Masoretic", &visible()).unwrap();
+        assert!(files[0].1.starts_with("\\id GEN WLC toc1 Genesis\n"));
+        assert!(files[0].1.contains("\\v 1 in v 99 principio\n"));
+        assert!(!files[0].1.contains("\\v 99"));
+    }
+
+    #[test]
+    fn osis() {
+        let mut ms = wlc_sample();
+        ms.meta.title = "WLC & co".to_string();
+        let osis = to_osis(&ms, "Masoretic", "WLC", &visible());
+        assert!(osis.contains("<title>WLC &amp; co</title>"));
+        assert!(osis.contains(
+            "<div type=\"book\" osisID=\"Gen\">\n<chapter osisID=\"Gen.1\">\n<verse osisID=\"Gen.1.1\">"
+        ));
+        assert!(osis.contains(
+            "</verse>\n</chapter>\n<chapter osisID=\"Gen.8\">\n<verse osisID=\"Gen.8.17\">כָּל־הַחַיָּ֨ה אֲשֶֽׁר־אִתְּךָ֜ הוצא אִתָּ֑ךְ</verse>\n</chapter>\n</div>\n</osisText>"
+        ));
+        // the output is well-formed
+        let mut reader = quick_xml::Reader::from_str(&osis);
+        while !matches!(reader.read_event().unwrap(), quick_xml::events::Event::Eof) {}
+    }
+
+    #[test]
+    fn lacunae_and_corrections() {
+        let anchor = |id: &str| {
+            Block::Anchor(streamed::Anchor {
+                anchor_id: id.to_string(),
+                anchor_type: "Common".to_string(),
+            })
+        };
+        let text = |content: &str| {
+            Block::Text(streamed::Paragraph {
+                lang: "grc".to_string(),
+                content: content.to_string(),
+            })
+        };
+        let blocks = vec![
+            Block::Break(streamed::BreakType::Page("1r".to_string(), None)),
+            anchor("A_V_C_John-1-1"),
+            text("εν αρχη"),
+            Block::Lacuna(streamed::Lacuna {
+                reason: "lost".to_string(),
+                unit: streamed::ExtentUnit::Character,
+                n: 3,
+                cert: None,
+            }),
+            Block::Break(streamed::BreakType::Line),
+            anchor("A_V_C_John-1-2"),
+            Block::Correction(streamed::Correction {
+                versions: vec![
+                    streamed::Version {
+                        lang: "grc".to_string(),
+                        hand: None,
+                        content: "ουτο".to_string(),
+                    },
+                    streamed::Version {
+                        lang: "grc".to_string(),
+                        hand: Some("corrector".to_string()),
+                        content: "ουτος".to_string(),
+                    },
+                ],
+            }),
+        ];
+        let texts = |rules: RenderRules| {
+            verses(&blocks, "Common", &rules)
+                .into_iter()
+                .map(|v| (v.id.verse, v.text))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(visible()),
+            vec![(1, "εν αρχη ...".to_string()), (2, "ουτος".to_string())]
+        );
+        assert_eq!(
+            texts(RenderRules {
+                correction: CorrectionRule::FirstVersion,
+                ..Default::default()
+            }),
+            vec![(2, "ουτο".to_string())]
+        );
+        assert_eq!(
+            texts(RenderRules {
+                lacuna: LacunaRule::Skip,
+                correction: CorrectionRule::Drop,
+                ..Default::default()
+            }),
+            vec![(1, "εν αρχη".to_string())]
+        );
+    }
+}
@@ -0,0 +1,803 @@
+//! A compact binary cache format for parsed manuscripts.
+//!
+//! Parsing the XML of a manuscript is slow compared to reading it back from this format. A cache
+//! entry is self-contained and versioned:
+//!
+//! | bytes | content                                                     |
+//! |-------|-------------------------------------------------------------|
+//! | 4     | the magic bytes `CRFC`                                      |
+//! | 2     | the [`CACHE_VERSION`], little endian                        |
+//! | 1     | the kind of manuscript, [`streamed`] or [`normalized`]      |
+//! | 32    | the [`ContentHash`] of the XML the manuscript was parsed from |
+//! | rest  | the manuscript                                              |
+//!
+//! The manuscript is written field by field in declaration order. Integers are LEB128 varints
+//! (signed ones zigzag encoded), strings and lists are prefixed with their length, and options and
+//! enums with a one-byte tag.
+//!
+//! Use [`cached_source`] to check whether an entry is still valid without decoding the
+//! manuscript.
+
+use crate::{normalized, schema, streamed};
+
+/// The version of the format written by this crate.
+///
+/// Any change to the encoding of any type has to bump this version.
+pub const CACHE_VERSION: u16 = 2;
+
+/// The magic bytes every cache entry starts with.
+const MAGIC: &[u8; 4] = b"CRFC";
+
+/// The length of the header before the manuscript.
+const HEADER_LEN: usize = MAGIC.len() + 2 + 1 + 32;
+
+/// The problems that can occur when reading a cache entry.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CacheError {
+    /// The input ended in the middle of a value
+    Truncated,
+    /// The input does not start with the magic bytes
+    NotACacheEntry,
+    /// The entry was written by a different version of the format
+    UnsupportedVersion(u16),
+    /// The entry contains a different kind of manuscript than requested
+    WrongKind { expected: u8, found: u8 },
+    /// A value could not be decoded
+    InvalidValue(&'static str),
+    /// There are bytes left after the manuscript
+    TrailingBytes(usize),
+}
+impl core::fmt::Display for CacheError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Truncated => {
+                write!(f, "The cache entry ended unexpectedly.")
+            }
+            Self::NotACacheEntry => {
+                write!(f, "The input is not a cache entry.")
+            }
+            Self::UnsupportedVersion(x) => {
+                write!(
+                    f,
+                    "The cache entry has version {x}, but only version {CACHE_VERSION} is supported."
+                )
+            }
+            Self::WrongKind { expected, found } => {
+                write!(
+                    f,
+                    "The cache entry contains a manuscript of kind {found}, expected kind {expected}."
+                )
+            }
+            Self::InvalidValue(x) => {
+                write!(f, "The cache entry contains an invalid {x}.")
+            }
+            Self::TrailingBytes(x) => {
+                write!(f, "The cache entry has {x} bytes after the manuscript.")
+            }
+        }
+    }
+}
+impl core::error::Error for CacheError {}
+
+/// The SHA-256 hash of the XML a manuscript was parsed from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct ContentHash(pub [u8; 32]);
+impl ContentHash {
+    /// The hash of `source`.
+    #[must_use]
+    pub fn of(source: &[u8]) -> Self {
+        Self(sha256(source))
+    }
+}
+/// Lowercase hex, as printed by `sha256sum`.
+impl core::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The round constants of SHA-256.
+#[rustfmt::skip]
+const SHA256_K: [u32; 64] = [
+    0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5, 0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+    0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3, 0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+    0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc, 0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+    0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7, 0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+    0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13, 0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+    0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3, 0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+    0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5, 0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+    0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208, 0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+];
+
+/// The SHA-256 hash of `data` (FIPS 180-4).
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09_e667,
+        0xbb67_ae85,
+        0x3c6e_f372,
+        0xa54f_f53a,
+        0x510e_527f,
+        0x9b05_688c,
+        0x1f83_d9ab,
+        0x5be0_cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_be_bytes());
+
+    for chunk in message.chunks_exact(64) {
+        let mut w = [0_u32; 64];
+        for (i, word) in chunk.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (s, x) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(x);
+        }
+    }
+
+    let mut res = [0_u8; 32];
+    for (out, word) in res.chunks_exact_mut(4).zip(state) {
+        out.copy_from_slice(&word.to_be_bytes());
+    }
+    res
+}
+
+mod private {
+    use super::CacheError;
+
+    /// Reads values from a cache entry.
+    pub struct Reader<'a> {
+        pub bytes: &'a [u8],
+    }
+    impl<'a> Reader<'a> {
+        pub fn take(&mut self, n: usize) -> Result<&'a [u8], CacheError> {
+            if self.bytes.len() < n {
+                return Err(CacheError::Truncated);
+            }
+            let (res, rest) = self.bytes.split_at(n);
+            self.bytes = rest;
+            Ok(res)
+        }
+
+        pub fn byte(&mut self) -> Result<u8, CacheError> {
+            Ok(self.take(1)?[0])
+        }
+
+        pub fn varint(&mut self) -> Result<u64, CacheError> {
+            let mut res = 0_u64;
+            for shift in (0..64).step_by(7) {
+                let byte = self.byte()?;
+                res |= u64::from(byte & 0x7f) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(res);
+                }
+            }
+            Err(CacheError::InvalidValue("integer"))
+        }
+
+        /// The length of a string or list, which cannot be longer than the rest of the input.
+        pub fn len(&mut self) -> Result<usize, CacheError> {
+            let len = usize::try_from(self.varint()?).map_err(|_| CacheError::Truncated)?;
+            if len > self.bytes.len() {
+                return Err(CacheError::Truncated);
+            }
+            Ok(len)
+        }
+    }
+
+    /// Types that can be written to and read from a cache entry.
+    pub trait Codec: Sized {
+        fn encode(&self, out: &mut Vec<u8>);
+        fn decode(reader: &mut Reader) -> Result<Self, CacheError>;
+    }
+}
+use private::{Codec, Reader};
+
+/// A manuscript that can be cached.
+///
+/// This is implemented for [`streamed::Manuscript`] and [`normalized::Manuscript`].
+pub trait Cacheable: Codec {
+    /// The kind of manuscript stored in the header
+    const KIND: u8;
+}
+impl Cacheable for streamed::Manuscript {
+    const KIND: u8 = 1;
+}
+impl Cacheable for normalized::Manuscript {
+    const KIND: u8 = 2;
+}
+
+/// Write `ms`, parsed from the XML with the hash `source`, as a cache entry.
+#[must_use]
+pub fn to_cache<T: Cacheable>(ms: &T, source: &ContentHash) -> Vec<u8> {
+    let mut res = Vec::with_capacity(4096);
+    res.extend_from_slice(MAGIC);
+    res.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    res.push(T::KIND);
+    res.extend_from_slice(&source.0);
+    ms.encode(&mut res);
+    res
+}
+
+/// Read the header of a cache entry, returning the kind of manuscript and the hash of its source.
+fn read_header(reader: &mut Reader) -> Result<(u8, ContentHash), CacheError> {
+    if reader.bytes.len() < HEADER_LEN {
+        return Err(if reader.bytes.starts_with(MAGIC) {
+            CacheError::Truncated
+        } else {
+            CacheError::NotACacheEntry
+        });
+    }
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(CacheError::NotACacheEntry);
+    }
+    let version = reader.take(2)?;
+    let version = u16::from_le_bytes([version[0], version[1]]);
+    if version != CACHE_VERSION {
+        return Err(CacheError::UnsupportedVersion(version));
+    }
+    let kind = reader.byte()?;
+    let mut hash = [0_u8; 32];
+    hash.copy_from_slice(reader.take(32)?);
+    Ok((kind, ContentHash(hash)))
+}
+
+/// The hash of the XML the manuscript in the cache entry `bytes` was parsed from.
+///
+/// Only the header is read, so this is cheap even for large manuscripts.
+///
+/// # Errors
+/// If `bytes` does not start with the header of a supported version of the format.
+pub fn cached_source(bytes: &[u8]) -> Result<ContentHash, CacheError> {
+    read_header(&mut Reader { bytes }).map(|(_, hash)| hash)
+}
+
+/// Read a manuscript from the cache entry `bytes`, together with the hash of its source.
+///
+/// # Errors
+/// If `bytes` is not a complete cache entry of a supported version containing a `T`.
+pub fn from_cache<T: Cacheable>(bytes: &[u8]) -> Result<(ContentHash, T), CacheError> {
+    let mut reader = Reader { bytes };
+    let (kind, hash) = read_header(&mut reader)?;
+    if kind != T::KIND {
+        return Err(CacheError::WrongKind {
+            expected: T::KIND,
+            found: kind,
+        });
+    }
+    let ms = T::decode(&mut reader)?;
+    if !reader.bytes.is_empty() {
+        return Err(CacheError::TrailingBytes(reader.bytes.len()));
+    }
+    Ok((hash, ms))
+}
+
+/// Read a manuscript from the cache entry `bytes` if it was parsed from `source`.
+///
+/// Returns `Ok(None)` if the entry is stale.
+///
+/// # Errors
+/// If `bytes` is not a complete cache entry of a supported version containing a `T`.
+pub fn from_cache_if_fresh<T: Cacheable>(
+    bytes: &[u8],
+    source: &[u8],
+) -> Result<Option<T>, CacheError> {
+    if cached_source(bytes)? != ContentHash::of(source) {
+        return Ok(None);
+    }
+    from_cache(bytes).map(|(_, ms)| Some(ms))
+}
+
+// primitives
+
+fn encode_varint(mut x: u64, out: &mut Vec<u8>) {
+    while x >= 0x80 {
+        out.push((x as u8) | 0x80);
+        x >>= 7;
+    }
+    out.push(x as u8);
+}
+
+impl Codec for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        reader.byte()
+    }
+}
+
+impl Codec for u32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_varint(u64::from(*self), out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        Self::try_from(reader.varint()?).map_err(|_| CacheError::InvalidValue("integer"))
+    }
+}
+
+impl Codec for i32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // zigzag, so small negative numbers stay short
+        encode_varint(u64::from(((self << 1) ^ (self >> 31)).cast_unsigned()), out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        let x = u32::try_from(reader.varint()?).map_err(|_| CacheError::InvalidValue("integer"))?;
+        Ok((x >> 1).cast_signed() ^ -((x & 1).cast_signed()))
+    }
+}
+
+impl Codec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_varint(self.len() as u64, out);
+        out.extend_from_slice(self.as_bytes());
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        let len = reader.len()?;
+        let bytes = reader.take(len)?;
+        Self::from_utf8(bytes.to_vec()).map_err(|_| CacheError::InvalidValue("string"))
+    }
+}
+
+impl<T: Codec> Codec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            None => out.push(0),
+            Some(x) => {
+                out.push(1);
+                x.encode(out);
+            }
+        }
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        match reader.byte()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(reader)?)),
+            _ => Err(CacheError::InvalidValue("option")),
+        }
+    }
+}
+
+impl<T: Codec> Codec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_varint(self.len() as u64, out);
+        for x in self {
+            x.encode(out);
+        }
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        // every element takes at least one byte, so this bounds the allocation
+        let len = reader.len()?;
+        let mut res = Self::with_capacity(len);
+        for _ in 0..len {
+            res.push(T::decode(reader)?);
+        }
+        Ok(res)
+    }
+}
+
+/// Implement [`Codec`] for structs by encoding their fields in order.
+macro_rules! codec_struct {
+    ($($ty:path { $($field:ident),* $(,)? })*) => {
+        $(
+            impl Codec for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    $( self.$field.encode(out); )*
+                }
+                fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+                    Ok(Self {
+                        $( $field: Codec::decode(reader)?, )*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+/// Implement [`Codec`] for enums whose variants have at most one field, tagged with their index.
+macro_rules! codec_enum {
+    ($($ty:ident $name:literal { $($tag:literal => $variant:ident $(($inner:ident))?),* $(,)? })*) => {
+        $(
+            impl Codec for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    match self {
+                        $(
+                            Self::$variant $(($inner))? => {
+                                out.push($tag);
+                                $( $inner.encode(out); )?
+                            }
+                        )*
+                    }
+                }
+                fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+                    match reader.byte()? {
+                        $( $tag => Ok(Self::$variant $(({
+                            let $inner = Codec::decode(reader)?;
+                            $inner
+                        }))?), )*
+                        _ => Err(CacheError::InvalidValue($name)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// header
+
+codec_struct! {
+    normalized::Meta {
+        title,
+        country,
+        settlement,
+        institution,
+        repository,
+        collection,
+        shelfmark,
+        hand_desc,
+        hands,
+        script_desc,
+        alt_identifier,
+        orig_date,
+        orig_date_desc,
+        orig_place,
+        provenance,
+        material,
+        support,
+        extent,
+        layouts,
+        contents,
+        changes,
+    }
+    normalized::Hand { id, description, scribe, script, not_before, not_after }
+    normalized::Date { year, month, day }
+    normalized::DateRange { not_before, not_after }
+    normalized::Identifier { id_type, idno }
+    normalized::Change { who, when, status, page, description }
+    schema::Extent { measure, dimensions }
+    schema::Measure { unit, quantity, description }
+    schema::Dimensions { dim_type, unit, height, width }
+    schema::Layout { columns, written_lines, description }
+    schema::MsItem { locus, title }
+    schema::Locus { from, to }
+    schema::MsItemTitle { title_type, name }
+}
+
+impl Codec for schema::Count {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.min.encode(out);
+        self.max.encode(out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        let min = u32::decode(reader)?;
+        let max = u32::decode(reader)?;
+        if min > max {
+            return Err(CacheError::InvalidValue("count"));
+        }
+        Ok(Self { min, max })
+    }
+}
+
+impl Codec for schema::Material {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().to_string().encode(out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        Ok(String::decode(reader)?.into())
+    }
+}
+
+impl Codec for schema::IdNoType {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_str().to_string().encode(out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        Ok(String::decode(reader)?.into())
+    }
+}
+
+// facsimile
+
+codec_struct! {
+    schema::Facsimile { surface }
+    schema::Surface { xml_id, graphic, zone }
+    schema::Graphic { url, width, height }
+    schema::Zone { xml_id, ulx, uly, lrx, lry, points }
+    schema::Point { x, y }
+}
+
+impl Codec for schema::Points {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        Ok(Self(Vec::decode(reader)?))
+    }
+}
+
+// shared blocks
+
+use schema::{Certainty, ExtentUnit, PageStatus};
+
+codec_struct! {
+    schema::Gap { reason, unit, n, cert }
+    schema::Space { quantity, unit }
+    normalized::Anchor { anchor_id, anchor_type }
+}
+
+codec_enum! {
+    ExtentUnit "extent unit" { 0 => Character, 1 => Line, 2 => Column }
+    PageStatus "page status" { 0 => Draft, 1 => Transcribed, 2 => Reviewed, 3 => Approved }
+}
+
+impl Codec for Certainty {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::High => out.push(0),
+            Self::Medium => out.push(1),
+            Self::Low => out.push(2),
+            Self::Unknown => out.push(3),
+            Self::Probability(p) => {
+                out.push(4);
+                out.extend_from_slice(&p.value().to_le_bytes());
+            }
+        }
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        match reader.byte()? {
+            0 => Ok(Self::High),
+            1 => Ok(Self::Medium),
+            2 => Ok(Self::Low),
+            3 => Ok(Self::Unknown),
+            4 => {
+                let mut bytes = [0_u8; 8];
+                bytes.copy_from_slice(reader.take(8)?);
+                schema::Probability::new(f64::from_le_bytes(bytes))
+                    .map(Self::Probability)
+                    .ok_or(CacheError::InvalidValue("certainty"))
+            }
+            _ => Err(CacheError::InvalidValue("certainty")),
+        }
+    }
+}
+
+// normalized text
+
+use normalized::InlineBlock;
+
+codec_struct! {
+    normalized::Manuscript { meta, text, facsimile }
+    normalized::Text { lang, pages }
+    normalized::Page { lang, n, status, facs, columns }
+    normalized::Column { lang, n, facs, lines }
+    normalized::Line { lang, n, facs, blocks }
+    normalized::Paragraph { lang, content }
+    normalized::Correction { lang, versions }
+    normalized::Version { lang, hand, content }
+    schema::Damage { lang, cert, agent, content }
+    schema::Choice { lang, surface, expansion }
+    schema::AbbrSurface { lang, content }
+    schema::AbbrExpansion { lang, content }
+}
+
+codec_enum! {
+    InlineBlock "block" {
+        0 => Lacuna(x),
+        1 => Anchor(x),
+        2 => Correction(x),
+        3 => Text(x),
+        4 => Uncertain(x),
+        5 => Abbreviation(x),
+        6 => Space(x),
+    }
+}
+
+// streamed text
+
+use streamed::{Block, BreakType};
+
+codec_struct! {
+    streamed::Manuscript { meta, content, facsimile, facs }
+    streamed::FacsLink { page, column, line, facs }
+    streamed::Paragraph { lang, content }
+    streamed::Correction { versions }
+    streamed::Version { lang, hand, content }
+    streamed::Uncertain { lang, cert, agent, content }
+    streamed::Abbreviation { surface_lang, surface, expansion_lang, expansion }
+}
+
+codec_enum! {
+    Block "block" {
+        0 => Break(x),
+        1 => Lacuna(x),
+        2 => Anchor(x),
+        3 => Text(x),
+        4 => Correction(x),
+        5 => Uncertain(x),
+        6 => Abbreviation(x),
+        7 => Space(x),
+    }
+}
+
+impl Codec for BreakType {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Line => out.push(0),
+            Self::Column => out.push(1),
+            Self::Page(n, status) => {
+                out.push(2);
+                n.encode(out);
+                status.encode(out);
+            }
+        }
+    }
+    fn decode(reader: &mut Reader) -> Result<Self, CacheError> {
+        match reader.byte()? {
+            0 => Ok(Self::Line),
+            1 => Ok(Self::Column),
+            2 => Ok(Self::Page(String::decode(reader)?, Option::decode(reader)?)),
+            _ => Err(CacheError::InvalidValue("break")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fuzz::Rng;
+
+    #[test]
+    fn sha256_vectors() {
+        assert_eq!(
+            ContentHash::of(b"").to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            ContentHash::of(b"abc").to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // two blocks of padding
+        assert_eq!(
+            ContentHash::of(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")
+                .to_string(),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn examples() {
+        for file in [
+            "examples/02_lines_consistent.xml",
+            "examples/07_multi-page.xml",
+            "examples/09_missing_pages.xml",
+            "examples/10_hand_notes.xml",
+            "examples/11_identifiers.xml",
+            "examples/12_history.xml",
+            "examples/13_layout.xml",
+            "examples/14_facsimile.xml",
+        ] {
+            let xml = std::fs::read_to_string(file).unwrap();
+            let source = ContentHash::of(xml.as_bytes());
+
+            let streamed = crate::from_xml(xml.as_bytes()).unwrap();
+            let entry = to_cache(&streamed, &source);
+            assert_eq!(cached_source(&entry), Ok(source), "{file}");
+            assert_eq!(from_cache(&entry), Ok((source, streamed)), "{file}");
+
+            let tei: schema::Tei = quick_xml::de::from_str(&xml).unwrap();
+            let normalized: normalized::Manuscript = tei.try_into().unwrap();
+            let entry = to_cache(&normalized, &source);
+            assert_eq!(from_cache(&entry), Ok((source, normalized)), "{file}");
+            assert_eq!(
+                from_cache::<streamed::Manuscript>(&entry),
+                Err(CacheError::WrongKind {
+                    expected: 1,
+                    found: 2
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn freshness() {
+        let xml = std::fs::read_to_string("examples/02_lines_consistent.xml").unwrap();
+        let ms = crate::from_xml(xml.as_bytes()).unwrap();
+        let entry = to_cache(&ms, &ContentHash::of(xml.as_bytes()));
+        assert_eq!(
+            from_cache_if_fresh(&entry, xml.as_bytes()),
+            Ok(Some(ms.clone()))
+        );
+        let changed = xml.replace("</TEI>", "</TEI>\n");
+        assert_eq!(
+            from_cache_if_fresh::<streamed::Manuscript>(&entry, changed.as_bytes()),
+            Ok(None)
+        );
+
+        let mut older = entry.clone();
+        older[4] = 1;
+        assert_eq!(
+            cached_source(&older),
+            Err(CacheError::UnsupportedVersion(1))
+        );
+        assert_eq!(cached_source(b"<TEI/>"), Err(CacheError::NotACacheEntry));
+    }
+
+    /// Random manuscripts survive the roundtrip, and no prefix of an entry decodes.
+    #[test]
+    fn fuzz_roundtrip() {
+        let xml = std::fs::read_to_string("examples/13_layout.xml").unwrap();
+        let template = crate::from_xml(xml.as_bytes()).unwrap();
+        let mut rng = Rng(0x5eed_cafe);
+        for _ in 0..200 {
+            let mut ms = template.clone();
+            ms.content = (0..rng.below(40)).map(|_| rng.block()).collect();
+            ms.meta.title = rng.string();
+            ms.meta.shelfmark = rng.option(Rng::string);
+            ms.facs = (0..rng.below(3))
+                .map(|_| streamed::FacsLink {
+                    page: rng.string(),
+                    column: rng.option(Rng::int),
+                    line: rng.option(Rng::int),
+                    facs: rng.string(),
+                })
+                .collect();
+            let source = ContentHash::of(ms.meta.title.as_bytes());
+            let entry = to_cache(&ms, &source);
+            assert_eq!(from_cache(&entry), Ok((source, ms)));
+            let cut = rng.below(entry.len() as u64) as usize;
+            assert!(from_cache::<streamed::Manuscript>(&entry[..cut]).is_err());
+        }
+    }
+
+    /// Corrupted entries are rejected or decoded, but never panic.
+    #[test]
+    fn fuzz_corruption() {
+        let xml = std::fs::read_to_string("examples/14_facsimile.xml").unwrap();
+        let ms = crate::from_xml(xml.as_bytes()).unwrap();
+        let entry = to_cache(&ms, &ContentHash::of(xml.as_bytes()));
+        let mut rng = Rng(0xbad_c0de);
+        for _ in 0..2000 {
+            let mut corrupted = entry.clone();
+            for _ in 0..=rng.below(4) {
+                let i = HEADER_LEN + rng.below((entry.len() - HEADER_LEN) as u64) as usize;
+                corrupted[i] = rng.next() as u8;
+            }
+            let _ = from_cache::<streamed::Manuscript>(&corrupted);
+        }
+    }
+}
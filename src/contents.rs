@@ -0,0 +1,237 @@
+//! Deriving the passages contained in a manuscript from its verse anchors.
+//!
+//! Every verse anchor has an `xml:id` of the form `A_V_{scheme-shorthand}_{book}-{chapter}-{verse}`
+//! (see the TEI subspec). Walking the anchors of one versification scheme in order and joining
+//! consecutive verses gives the contiguous passages present in a manuscript, e.g. `Ps 26:10–31:4`.
+//!
+//! This is an approximation: the number of verses in each chapter is not known, so the first
+//! verse of a chapter is taken to follow any verse of the chapter before. Verses lost at the end
+//! of a chapter are not detected, and `Ps 26:1` followed by `Ps 27:1` is the single passage
+//! `Ps 26:1–27:1`.
+
+use crate::{
+    normalized, schema,
+    streamed::{Block, BreakType},
+};
+
+/// A single verse, as encoded in the `xml:id` of an anchor.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct VerseId {
+    /// The OSIS abbreviation of the book
+    pub book: String,
+    pub chapter: u32,
+    pub verse: u32,
+}
+impl VerseId {
+    /// Parse the `xml:id` of an anchor (`A_V_MT_1Kg-3-4`).
+    ///
+    /// Returns the shorthand of the versification scheme and the verse.
+    #[must_use]
+    pub fn from_anchor_id(anchor_id: &str) -> Option<(&str, Self)> {
+        let (scheme, verse_id) = anchor_id.strip_prefix("A_V_")?.rsplit_once('_')?;
+        let mut parts = verse_id.rsplitn(3, '-');
+        let verse = parts.next()?.parse().ok()?;
+        let chapter = parts.next()?.parse().ok()?;
+        let book = parts.next().filter(|b| !b.is_empty())?;
+        Some((
+            scheme,
+            Self {
+                book: book.to_string(),
+                chapter,
+                verse,
+            },
+        ))
+    }
+
+    /// Whether `next` directly follows this verse.
+    ///
+    /// We do not know the number of verses in each chapter, so the first verse of the next chapter
+    /// is taken to follow any verse (see the [module documentation](self)).
+    #[must_use]
+    pub fn is_followed_by(&self, next: &Self) -> bool {
+        self.book == next.book
+            && ((next.chapter == self.chapter && self.verse.checked_add(1) == Some(next.verse))
+                || (self.chapter.checked_add(1) == Some(next.chapter) && next.verse == 1))
+    }
+}
+
+/// A contiguous passage of verses found in a manuscript.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ContentRange {
+    /// The long form of the versification scheme, as given in the anchors `@type`
+    pub scheme: String,
+    /// The first verse in this passage
+    pub start: VerseId,
+    /// The last verse in this passage
+    pub end: VerseId,
+    /// The page the first verse begins on
+    pub start_page: String,
+    /// The page the last verse begins on
+    pub end_page: String,
+}
+/// Formats as `Ps 26:10–31:4`, `Ps 26:10–12` or `Ps 26:10`.
+impl core::fmt::Display for ContentRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{} {}:{}",
+            self.start.book, self.start.chapter, self.start.verse
+        )?;
+        if self.start == self.end {
+            Ok(())
+        } else if self.start.chapter == self.end.chapter {
+            write!(f, "–{}", self.end.verse)
+        } else {
+            write!(f, "–{}:{}", self.end.chapter, self.end.verse)
+        }
+    }
+}
+impl From<&ContentRange> for normalized::ContentItem {
+    fn from(value: &ContentRange) -> Self {
+        Self {
+            locus: Some(schema::Locus {
+                from: value.start_page.clone(),
+                to: value.end_page.clone(),
+            }),
+            title: schema::MsItemTitle {
+                title_type: Some(value.scheme.clone()),
+                name: value.to_string(),
+            },
+        }
+    }
+}
+
+/// The contiguous passages in `blocks`, grouped by versification scheme.
+///
+/// Passages continue across chapters, so verses lost at the end of a chapter are not detected
+/// (see the [module documentation](self)).
+///
+/// The schemes are in the order of their first anchor, the passages of each scheme in the order
+/// of the text. Anchors whose `xml:id` cannot be parsed are ignored, as are anchors before the
+/// first page break.
+#[must_use]
+pub fn content_ranges(blocks: &[Block]) -> Vec<ContentRange> {
+    // one list of ranges per scheme
+    let mut by_scheme: Vec<(&str, Vec<ContentRange>)> = vec![];
+    let mut page = None;
+    for block in blocks {
+        match block {
+            Block::Break(BreakType::Page(n, _)) => page = Some(n),
+            Block::Anchor(anchor) => {
+                let (Some(page), Some((_, verse))) =
+                    (page, VerseId::from_anchor_id(&anchor.anchor_id))
+                else {
+                    continue;
+                };
+                let ranges = if let Some((_, ranges)) = by_scheme
+                    .iter_mut()
+                    .find(|(scheme, _)| *scheme == anchor.anchor_type)
+                {
+                    ranges
+                } else {
+                    by_scheme.push((&anchor.anchor_type, vec![]));
+                    &mut by_scheme.last_mut().expect("just pushed").1
+                };
+                match ranges.last_mut() {
+                    Some(range) if range.end.is_followed_by(&verse) => {
+                        range.end = verse;
+                        range.end_page.clone_from(page);
+                    }
+                    _ => ranges.push(ContentRange {
+                        scheme: anchor.anchor_type.clone(),
+                        start: verse.clone(),
+                        end: verse,
+                        start_page: page.clone(),
+                        end_page: page.clone(),
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+    by_scheme.into_iter().flat_map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::streamed::Anchor;
+
+    fn anchor(id: &str, scheme: &str) -> Block {
+        Block::Anchor(Anchor {
+            anchor_id: id.to_string(),
+            anchor_type: scheme.to_string(),
+        })
+    }
+
+    fn page(n: &str) -> Block {
+        Block::Break(BreakType::Page(n.to_string(), None))
+    }
+
+    #[test]
+    fn verse_id() {
+        assert_eq!(
+            VerseId::from_anchor_id("A_V_MT-Aleppo_1Kg-3-4"),
+            Some((
+                "MT-Aleppo",
+                VerseId {
+                    book: "1Kg".to_string(),
+                    chapter: 3,
+                    verse: 4,
+                }
+            ))
+        );
+        assert_eq!(VerseId::from_anchor_id("A_V_P_VERSE-NUMBER"), None);
+        assert_eq!(VerseId::from_anchor_id("B_V_MT_Ps-1-1"), None);
+    }
+
+    #[test]
+    fn followed_by() {
+        let verse = |chapter, verse| VerseId {
+            book: "Ps".to_string(),
+            chapter,
+            verse,
+        };
+        assert!(verse(26, 10).is_followed_by(&verse(26, 11)));
+        assert!(!verse(26, 10).is_followed_by(&verse(26, 12)));
+        // the end of a chapter is not known
+        assert!(verse(26, 1).is_followed_by(&verse(27, 1)));
+        assert!(!verse(26, 1).is_followed_by(&verse(28, 1)));
+        assert!(!verse(26, u32::MAX).is_followed_by(&verse(26, 0)));
+        assert!(!verse(u32::MAX, 1).is_followed_by(&verse(0, 1)));
+    }
+
+    #[test]
+    fn ranges() {
+        let blocks = vec![
+            page("01_r"),
+            anchor("A_V_MT_Ps-26-10", "Masoretic"),
+            anchor("A_V_LXX_Ps-25-10", "Septuagint"),
+            anchor("A_V_MT_Ps-26-11", "Masoretic"),
+            page("01_v"),
+            anchor("A_V_MT_Ps-27-1", "Masoretic"),
+            page("02_r"),
+            // 27:2 - 31:3 are lost
+            anchor("A_V_MT_Ps-31-4", "Masoretic"),
+            anchor("A_V_MT_Ps-31-5", "Masoretic"),
+            anchor("A_V_MT_Ps-31-x", "Masoretic"),
+        ];
+        let ranges = content_ranges(&blocks);
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|r| (
+                    r.scheme.as_str(),
+                    r.to_string(),
+                    r.start_page.as_str(),
+                    r.end_page.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Masoretic", "Ps 26:10–27:1".to_string(), "01_r", "01_v"),
+                ("Masoretic", "Ps 31:4–5".to_string(), "02_r", "02_r"),
+                ("Septuagint", "Ps 25:10".to_string(), "01_r", "01_r"),
+            ]
+        );
+    }
+}
@@ -1,6 +1,10 @@
 //! Normalize and Denormalize from types in [`schema`] to those in [`normalized`].
 
-use crate::{normalized, schema};
+use crate::{
+    normalized::{self, DateError},
+    schema,
+    vocabulary::{Vocabulary, checked_damage_agent, checked_gap_reason},
+};
 
 /// This publication statement MUST be present in every xml file and this is enforced.
 pub const PUBLICATION_STATEMENT: &str = "This digital reproduction is published as part of TanakhCC and licensed as https://creativecommons.org/publicdomain/zero/1.0.";
@@ -35,6 +39,30 @@ pub enum NormalizationError {
     ///
     /// last page - this page
     PageNumbersNotOrdered(String, String),
+    /// The `@agent` of a `<damage>` is not in the [`Vocabulary`]
+    UnknownDamageAgent(String),
+    /// A column without `@n` follows column `i32::MAX`, so there is no number left for it
+    ColumnNrOverflow,
+    /// A line without `@n` follows line `i32::MAX`, so there is no number left for it
+    ///
+    /// Argument is the column this line is in
+    LineNrOverflow(i32),
+    /// The `@reason` of a `<gap>` is not in the [`Vocabulary`]
+    UnknownGapReason(String),
+    /// The `@hand` of a `<rdg>` is not declared as a `<handNote>` in the header
+    UndeclaredHand(String),
+    /// A dating attribute (`@when`, `@notBefore`, `@notAfter`) is not a valid ISO date
+    InvalidDate(String, DateError),
+    /// An `<origDate>` has `@when` together with `@notBefore` or `@notAfter`
+    ///
+    /// Argument is the `@when`
+    AmbiguousDate(String),
+    /// `@notBefore` is after `@notAfter`
+    ///
+    /// `@notBefore` - `@notAfter`
+    EmptyDateRange(String, String),
+    /// A `@facs` points to a `<surface>` or `<zone>` that is not in the `<facsimile>`
+    UnknownFacs(String),
 }
 impl core::fmt::Display for NormalizationError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
@@ -81,23 +109,159 @@ impl core::fmt::Display for NormalizationError {
                     "The two pages {last} and {this} are in the wrong order or have the same name."
                 )
             }
+            Self::UnknownDamageAgent(x) => {
+                write!(f, "The damage agent \"{x}\" is not in the vocabulary.")
+            }
+            Self::ColumnNrOverflow => {
+                write!(f, "A column follows column {} and has no number.", i32::MAX)
+            }
+            Self::LineNrOverflow(x) => {
+                write!(
+                    f,
+                    "A line follows line {} in column {x} and has no number.",
+                    i32::MAX
+                )
+            }
+            Self::UnknownGapReason(x) => {
+                write!(f, "The gap reason \"{x}\" is not in the vocabulary.")
+            }
+            Self::UndeclaredHand(x) => {
+                write!(
+                    f,
+                    "The hand \"{x}\" is used in a correction but not declared in the handDesc."
+                )
+            }
+            Self::InvalidDate(x, e) => {
+                write!(f, "The date \"{x}\" is invalid: {e}")
+            }
+            Self::AmbiguousDate(x) => {
+                write!(
+                    f,
+                    "The date \"{x}\" is given together with notBefore or notAfter."
+                )
+            }
+            Self::EmptyDateRange(not_before, not_after) => {
+                write!(
+                    f,
+                    "The date range is empty: {not_before} is after {not_after}."
+                )
+            }
+            Self::UnknownFacs(x) => {
+                write!(
+                    f,
+                    "The facs \"{x}\" does not point to a surface or zone in the facsimile."
+                )
+            }
         }
     }
 }
 impl core::error::Error for NormalizationError {}
 
+/// Normalize, checking damage agents and gap reasons against the default [`Vocabulary`].
 impl TryFrom<schema::Tei> for normalized::Manuscript {
     type Error = NormalizationError;
 
     fn try_from(value: schema::Tei) -> Result<Self, Self::Error> {
-        let trimmed = value.trim();
+        (value, Some(&Vocabulary::default())).try_into()
+    }
+}
+
+/// Normalize, checking damage agents and gap reasons against the [`Vocabulary`], if one is given.
+impl TryFrom<(schema::Tei, Option<&Vocabulary>)> for normalized::Manuscript {
+    type Error = NormalizationError;
+
+    fn try_from(value: (schema::Tei, Option<&Vocabulary>)) -> Result<Self, Self::Error> {
+        let trimmed = value.0.trim();
+        let meta: normalized::Meta = trimmed.tei_header.try_into()?;
+        let text: normalized::Text = (trimmed.text, value.1).try_into()?;
+        check_hands(&text.pages, |hand| meta.allows_hand(hand))?;
+        let facsimile = trimmed.facsimile.unwrap_or_default();
+        check_facs(&facsimile, &text)?;
         Ok(Self {
-            meta: trimmed.tei_header.try_into()?,
-            text: trimmed.text.try_into()?,
+            meta,
+            text,
+            facsimile,
         })
     }
 }
 
+/// Make sure that every local `@facs` pointer (`#id`) points into the facsimile.
+///
+/// Other values (e.g. the URL of an image) are not checked.
+fn check_facs(
+    facsimile: &normalized::Facsimile,
+    text: &normalized::Text,
+) -> Result<(), NormalizationError> {
+    let pointers = text.pages.iter().flat_map(|p| {
+        core::iter::once(&p.facs).chain(
+            p.columns
+                .iter()
+                .flat_map(|c| core::iter::once(&c.facs).chain(c.lines.iter().map(|l| &l.facs))),
+        )
+    });
+    for facs in pointers.flatten() {
+        if facs.starts_with('#') && !facsimile.contains(facs) {
+            return Err(NormalizationError::UnknownFacs(facs.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Make sure that every damage agent and gap reason in `pages` is allowed by `vocabulary`.
+///
+/// Normalization checks the same when reading, so this is used before writing.
+pub(crate) fn check_vocabulary(
+    pages: &[normalized::Page],
+    vocabulary: &Vocabulary,
+) -> Result<(), NormalizationError> {
+    let blocks = pages
+        .iter()
+        .flat_map(|p| &p.columns)
+        .flat_map(|c| &c.lines)
+        .flat_map(|l| &l.blocks);
+    for block in blocks {
+        match block {
+            normalized::InlineBlock::Uncertain(x)
+                if vocabulary.damage_agent(&x.agent).is_none() =>
+            {
+                return Err(NormalizationError::UnknownDamageAgent(x.agent.clone()));
+            }
+            normalized::InlineBlock::Lacuna(x) if vocabulary.gap_reason(&x.reason).is_none() => {
+                return Err(NormalizationError::UnknownGapReason(x.reason.clone()));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Make sure that every hand used in a correction in `pages` is allowed by `allows_hand`.
+///
+/// For whole manuscripts, these are the hands declared in the header, see
+/// [`Meta::allows_hand`](normalized::Meta::allows_hand).
+pub(crate) fn check_hands(
+    pages: &[normalized::Page],
+    allows_hand: impl Fn(&str) -> bool,
+) -> Result<(), NormalizationError> {
+    let used_hands = pages
+        .iter()
+        .flat_map(|p| &p.columns)
+        .flat_map(|c| &c.lines)
+        .flat_map(|l| &l.blocks)
+        .filter_map(|b| match b {
+            normalized::InlineBlock::Correction(c) => Some(&c.versions),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|v| v.hand.as_deref());
+    for hand in used_hands {
+        if !allows_hand(hand) {
+            return Err(NormalizationError::UndeclaredHand(hand.to_string()));
+        }
+    }
+    Ok(())
+}
+
 impl TryFrom<schema::TeiHeader> for normalized::Meta {
     type Error = NormalizationError;
 
@@ -105,6 +269,56 @@ impl TryFrom<schema::TeiHeader> for normalized::Meta {
         if value.file_desc.publication_stmt.p != *PUBLICATION_STATEMENT {
             return Err(NormalizationError::PublicationStmtIncorrect);
         }
+        let (hand_desc, hands) = value
+            .file_desc
+            .source_desc
+            .ms_desc
+            .phys_desc
+            .hand_desc
+            .map(|d| -> Result<_, NormalizationError> {
+                Ok((
+                    d.summary,
+                    d.hand_notes
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?,
+                ))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let (material, support, extent, layouts) =
+            match value.file_desc.source_desc.ms_desc.phys_desc.object_desc {
+                Some(object_desc) => {
+                    let (material, support, extent) = object_desc
+                        .support_desc
+                        .map(|d| (d.material, d.support, d.extent))
+                        .unwrap_or_default();
+                    let layouts = object_desc
+                        .layout_desc
+                        .map(|d| d.layout)
+                        .unwrap_or_default();
+                    (material, support, extent, layouts)
+                }
+                None => (None, None, None, vec![]),
+            };
+        let (orig_date, orig_date_desc, orig_place, provenance) =
+            match value.file_desc.source_desc.ms_desc.history {
+                Some(history) => {
+                    let (orig_date, orig_place) = history
+                        .origin
+                        .map(|o| (o.orig_date, o.orig_place))
+                        .unwrap_or_default();
+                    let (orig_date, orig_date_desc) = match orig_date {
+                        Some(d) => (
+                            norm_orig_date(d.when, d.not_before, d.not_after)?,
+                            d.description,
+                        ),
+                        None => (None, None),
+                    };
+                    (orig_date, orig_date_desc, orig_place, history.provenance)
+                }
+                None => (None, None, None, vec![]),
+            };
         Ok(Self {
             alt_identifier: value
                 .file_desc
@@ -113,23 +327,28 @@ impl TryFrom<schema::TeiHeader> for normalized::Meta {
                 .ms_identifier
                 .alt_identifier
                 .into_iter()
-                .map(|a| a.idno.name)
+                .map(Into::into)
                 .collect(),
             title: value.file_desc.title_stmt.title,
+            country: value.file_desc.source_desc.ms_desc.ms_identifier.country,
+            settlement: value.file_desc.source_desc.ms_desc.ms_identifier.settlement,
             institution: value
                 .file_desc
                 .source_desc
                 .ms_desc
                 .ms_identifier
                 .institution,
+            repository: value.file_desc.source_desc.ms_desc.ms_identifier.repository,
             collection: value.file_desc.source_desc.ms_desc.ms_identifier.collection,
-            hand_desc: value
+            shelfmark: value
                 .file_desc
                 .source_desc
                 .ms_desc
-                .phys_desc
-                .hand_desc
-                .map(|d| d.summary),
+                .ms_identifier
+                .idno
+                .map(|i| i.name),
+            hand_desc,
+            hands,
             script_desc: value
                 .file_desc
                 .source_desc
@@ -137,20 +356,147 @@ impl TryFrom<schema::TeiHeader> for normalized::Meta {
                 .phys_desc
                 .script_desc
                 .map(|d| d.summary),
+            orig_date,
+            orig_date_desc,
+            orig_place,
+            provenance,
+            material,
+            support,
+            extent,
+            layouts,
+            contents: value
+                .file_desc
+                .source_desc
+                .ms_desc
+                .ms_contents
+                .map(|c| c.ms_item)
+                .unwrap_or_default(),
+            changes: value
+                .revision_desc
+                .map(|r| r.change)
+                .unwrap_or_default()
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Parse an optional dating attribute.
+fn norm_date(date: Option<String>) -> Result<Option<normalized::Date>, NormalizationError> {
+    date.map(|d| d.parse().map_err(|e| NormalizationError::InvalidDate(d, e)))
+        .transpose()
+}
+
+/// Parse `@notBefore` and `@notAfter`, making sure the range they span is not empty.
+fn norm_date_range(
+    not_before: Option<String>,
+    not_after: Option<String>,
+) -> Result<normalized::DateRange, NormalizationError> {
+    let range = normalized::DateRange {
+        not_before: norm_date(not_before.clone())?,
+        not_after: norm_date(not_after.clone())?,
+    };
+    if range.is_empty() {
+        return Err(NormalizationError::EmptyDateRange(
+            not_before.unwrap_or_default(),
+            not_after.unwrap_or_default(),
+        ));
+    }
+    Ok(range)
+}
+
+/// Build the date range from the dating attributes of an `<origDate>`.
+///
+/// `@when` excludes `@notBefore` and `@notAfter`.
+fn norm_orig_date(
+    when: Option<String>,
+    not_before: Option<String>,
+    not_after: Option<String>,
+) -> Result<Option<normalized::DateRange>, NormalizationError> {
+    if let Some(when) = when {
+        if not_before.is_some() || not_after.is_some() {
+            return Err(NormalizationError::AmbiguousDate(when));
+        }
+        return Ok(norm_date(Some(when))?.map(normalized::DateRange::exact));
+    }
+    let range = norm_date_range(not_before, not_after)?;
+    Ok((range != normalized::DateRange::default()).then_some(range))
+}
+
+impl TryFrom<schema::Change> for normalized::Change {
+    type Error = NormalizationError;
+
+    fn try_from(value: schema::Change) -> Result<Self, Self::Error> {
+        Ok(Self {
+            who: value.who,
+            when: norm_date(value.when)?,
+            status: value.status,
+            page: value.target,
+            description: value.description,
         })
     }
 }
 
-impl TryFrom<schema::Text> for normalized::Text {
+impl From<normalized::Change> for schema::Change {
+    fn from(value: normalized::Change) -> Self {
+        Self {
+            who: value.who,
+            when: value.when.map(|d| d.to_string()),
+            status: value.status,
+            target: value.page,
+            description: value.description,
+        }
+    }
+}
+
+impl From<schema::AltIdentifier> for normalized::Identifier {
+    fn from(value: schema::AltIdentifier) -> Self {
+        Self {
+            id_type: value.idno.id_type,
+            idno: value.idno.name,
+        }
+    }
+}
+
+impl From<normalized::Identifier> for schema::AltIdentifier {
+    fn from(value: normalized::Identifier) -> Self {
+        Self {
+            idno: schema::IdNo {
+                id_type: value.id_type,
+                name: value.idno,
+            },
+        }
+    }
+}
+
+impl TryFrom<schema::HandNote> for normalized::Hand {
+    type Error = NormalizationError;
+
+    fn try_from(value: schema::HandNote) -> Result<Self, Self::Error> {
+        let range = norm_date_range(value.not_before, value.not_after)?;
+        Ok(Self {
+            id: value.xml_id,
+            description: value.description,
+            scribe: value.scribe,
+            script: value.script,
+            not_before: range.not_before,
+            not_after: range.not_after,
+        })
+    }
+}
+
+impl TryFrom<(schema::Text, Option<&Vocabulary>)> for normalized::Text {
     type Error = NormalizationError;
 
-    fn try_from(value: schema::Text) -> Result<Self, Self::Error> {
+    fn try_from(value: (schema::Text, Option<&Vocabulary>)) -> Result<Self, Self::Error> {
         Ok(Self {
             lang: value
+                .0
                 .body
                 .lang
                 .ok_or(NormalizationError::NoDefaultLanguage)?,
-            pages: try_norm_pages(value.body.pages)?,
+            pages: try_norm_pages(value.0.body.pages, value.1)?,
         })
     }
 }
@@ -159,7 +505,10 @@ impl TryFrom<schema::Text> for normalized::Text {
 ///
 /// This can fail because two pages may be in the wrong order in the [`schema`] version, but this
 /// is no longer allowed in the [`normalized`] form.
-fn try_norm_pages(pages: Vec<schema::Page>) -> Result<Vec<normalized::Page>, NormalizationError> {
+fn try_norm_pages(
+    pages: Vec<schema::Page>,
+    vocabulary: Option<&Vocabulary>,
+) -> Result<Vec<normalized::Page>, NormalizationError> {
     let mut res = Vec::<normalized::Page>::with_capacity(pages.len());
 
     let mut last_name = None;
@@ -170,19 +519,31 @@ fn try_norm_pages(pages: Vec<schema::Page>) -> Result<Vec<normalized::Page>, Nor
             return Err(NormalizationError::PageNumbersNotOrdered(l, page.n));
         }
         last_name = Some(page.n.clone());
-        res.push(page.try_into()?);
+        res.push((page, vocabulary).try_into()?);
     }
     Ok(res)
 }
 
+/// Normalize, checking damage agents and gap reasons against the default [`Vocabulary`].
 impl TryFrom<schema::Page> for normalized::Page {
     type Error = NormalizationError;
 
     fn try_from(value: schema::Page) -> Result<Self, Self::Error> {
+        (value, Some(&Vocabulary::default())).try_into()
+    }
+}
+
+/// Normalize, checking damage agents and gap reasons against the [`Vocabulary`], if one is given.
+impl TryFrom<(schema::Page, Option<&Vocabulary>)> for normalized::Page {
+    type Error = NormalizationError;
+
+    fn try_from(value: (schema::Page, Option<&Vocabulary>)) -> Result<Self, Self::Error> {
         Ok(Self {
-            n: value.n,
-            lang: value.lang,
-            columns: try_norm_columns(value.columns)?,
+            n: value.0.n,
+            lang: value.0.lang,
+            status: value.0.status,
+            facs: value.0.facs,
+            columns: try_norm_columns(value.0.columns, value.1)?,
         })
     }
 }
@@ -193,10 +554,12 @@ impl TryFrom<schema::Page> for normalized::Page {
 /// xml and we raise errors when there are conflicts (columnnumbers used multiple times etc.)
 fn try_norm_columns(
     columns: Vec<schema::Column>,
+    vocabulary: Option<&Vocabulary>,
 ) -> Result<Vec<normalized::Column>, NormalizationError> {
     let mut res = Vec::<normalized::Column>::with_capacity(columns.len());
 
-    let mut next_column_nr = 1;
+    // wider than the column numbers, so that the column after `i32::MAX` does not overflow
+    let mut next_column_nr = 1_i64;
     for col in columns {
         // make sure that the divtype was actually set to column
         if col.div_type != "column" {
@@ -206,18 +569,20 @@ fn try_norm_columns(
         if let Some(x) = col.n {
             // because gaps can include entire columns, the next column might be offset more then
             // one
-            if x >= next_column_nr {
-                next_column_nr = x;
+            if i64::from(x) >= next_column_nr {
+                next_column_nr = x.into();
             } else {
                 // we cannot decrement the next_column_nr, this would mean a column-nr is used
                 // multiple times
                 return Err(NormalizationError::ColumnNrOverlap(x));
             }
         }
+        let n = i32::try_from(next_column_nr).map_err(|_| NormalizationError::ColumnNrOverflow)?;
         let normalized_col = normalized::Column {
             lang: col.lang,
-            n: next_column_nr,
-            lines: try_norm_lines(col.lines, next_column_nr)?,
+            n,
+            facs: col.facs,
+            lines: try_norm_lines(col.lines, n, vocabulary)?,
         };
         // now auto-increment to the next column
         next_column_nr += 1;
@@ -229,10 +594,12 @@ fn try_norm_columns(
 fn try_norm_lines(
     lines: Vec<schema::Line>,
     col_nr: i32,
+    vocabulary: Option<&Vocabulary>,
 ) -> Result<Vec<normalized::Line>, NormalizationError> {
     let mut res = Vec::<normalized::Line>::with_capacity(lines.len());
 
-    let mut next_line_nr = 1;
+    // wider than the line numbers, so that the line after `i32::MAX` does not overflow
+    let mut next_line_nr = 1_i64;
     for line in lines {
         // make sure that the divtype was actually set to line
         if line.div_type != "line" {
@@ -243,8 +610,8 @@ fn try_norm_lines(
         if let Some(x) = line.n {
             // because gaps can include entire lines, the next line might be offset more then
             // one
-            if x >= next_line_nr {
-                next_line_nr = x;
+            if i64::from(x) >= next_line_nr {
+                next_line_nr = x.into();
             } else {
                 // we cannot decrement the next_line_nr, this would mean a line-nr is used
                 // multiple times
@@ -254,11 +621,13 @@ fn try_norm_lines(
 
         let normalized_line = normalized::Line {
             lang: line.lang,
-            n: next_line_nr,
+            n: i32::try_from(next_line_nr)
+                .map_err(|_| NormalizationError::LineNrOverflow(col_nr))?,
+            facs: line.facs,
             blocks: line
                 .blocks
                 .into_iter()
-                .map(<schema::InlineBlock as TryInto<normalized::InlineBlock>>::try_into)
+                .map(|b| (b, vocabulary).try_into())
                 .collect::<Result<Vec<_>, _>>()?,
         };
         // now auto-increment to the next line
@@ -268,13 +637,20 @@ fn try_norm_lines(
     Ok(res)
 }
 
-impl TryFrom<schema::InlineBlock> for normalized::InlineBlock {
+impl TryFrom<(schema::InlineBlock, Option<&Vocabulary>)> for normalized::InlineBlock {
     type Error = NormalizationError;
 
-    fn try_from(value: schema::InlineBlock) -> Result<Self, Self::Error> {
-        Ok(match value {
+    fn try_from(value: (schema::InlineBlock, Option<&Vocabulary>)) -> Result<Self, Self::Error> {
+        let vocabulary = value.1;
+        Ok(match value.0 {
             schema::InlineBlock::P(x) => match x.value {
-                schema::TextDamageOrChoice::Damage(y) => normalized::InlineBlock::Uncertain(y),
+                schema::TextDamageOrChoice::Damage(y) => {
+                    normalized::InlineBlock::Uncertain(normalized::Uncertain {
+                        agent: checked_damage_agent(vocabulary, &y.agent)
+                            .ok_or(NormalizationError::UnknownDamageAgent(y.agent))?,
+                        ..y
+                    })
+                }
                 schema::TextDamageOrChoice::Text(y) => {
                     normalized::InlineBlock::Text(normalized::Paragraph {
                         lang: x.lang,
@@ -283,7 +659,11 @@ impl TryFrom<schema::InlineBlock> for normalized::InlineBlock {
                 }
                 schema::TextDamageOrChoice::Choice(y) => normalized::InlineBlock::Abbreviation(y),
             },
-            schema::InlineBlock::Gap(x) => normalized::InlineBlock::Lacuna(x),
+            schema::InlineBlock::Gap(x) => normalized::InlineBlock::Lacuna(normalized::Lacuna {
+                reason: checked_gap_reason(vocabulary, &x.reason)
+                    .ok_or(NormalizationError::UnknownGapReason(x.reason))?,
+                ..x
+            }),
             schema::InlineBlock::Anchor(x) => normalized::InlineBlock::Anchor(x.into()),
             schema::InlineBlock::App(x) => normalized::InlineBlock::Correction(x.into()),
             schema::InlineBlock::Space(x) => normalized::InlineBlock::Space(x),
@@ -325,13 +705,32 @@ impl From<schema::Anchor> for normalized::Anchor {
 
 // denormalizing (from normalized form to schema-form)
 
+impl From<normalized::Hand> for schema::HandNote {
+    fn from(value: normalized::Hand) -> Self {
+        Self {
+            xml_id: value.id,
+            description: value.description,
+            scribe: value.scribe,
+            script: value.script,
+            not_before: value.not_before.map(|d| d.to_string()),
+            not_after: value.not_after.map(|d| d.to_string()),
+        }
+    }
+}
+
 impl TryFrom<normalized::Manuscript> for schema::Tei {
     type Error = NormalizationError;
 
     fn try_from(value: normalized::Manuscript) -> Result<Self, Self::Error> {
+        check_hands(&value.text.pages, |hand| value.meta.allows_hand(hand))?;
         Ok(Self {
             xmlns: "http://www.tei-c.org/ns/1.0".to_string(),
             tei_header: value.meta.into(),
+            facsimile: if value.facsimile.surface.is_empty() {
+                None
+            } else {
+                Some(value.facsimile)
+            },
             text: value.text.try_into()?,
         })
     }
@@ -348,25 +747,143 @@ impl From<normalized::Meta> for schema::TeiHeader {
                 source_desc: schema::SourceDesc {
                     ms_desc: schema::MsDesc {
                         ms_identifier: schema::MsIdentifier {
+                            country: value.country,
+                            settlement: value.settlement,
                             institution: value.institution,
+                            repository: value.repository,
                             collection: value.collection,
+                            idno: value.shelfmark.map(|s| schema::IdNo {
+                                id_type: None,
+                                name: s,
+                            }),
                             alt_identifier: value
                                 .alt_identifier
                                 .into_iter()
-                                .map(|a| schema::AltIdentifier {
-                                    idno: schema::IdNo { name: a },
-                                })
+                                .map(Into::into)
                                 .collect(),
                         },
+                        ms_contents: if value.contents.is_empty() {
+                            None
+                        } else {
+                            Some(schema::MsContents {
+                                ms_item: value.contents,
+                            })
+                        },
                         phys_desc: schema::PhysDesc {
-                            hand_desc: value.hand_desc.map(|d| schema::HandDesc { summary: d }),
+                            object_desc: denorm_object_desc(
+                                value.material,
+                                value.support,
+                                value.extent,
+                                value.layouts,
+                            ),
+                            hand_desc: if value.hand_desc.is_none() && value.hands.is_empty() {
+                                None
+                            } else {
+                                Some(schema::HandDesc {
+                                    summary: value.hand_desc,
+                                    hand_notes: value.hands.into_iter().map(Into::into).collect(),
+                                })
+                            },
                             script_desc: value
                                 .script_desc
                                 .map(|d| schema::ScriptDesc { summary: d }),
                         },
+                        history: denorm_history(
+                            value.orig_date,
+                            value.orig_date_desc,
+                            value.orig_place,
+                            value.provenance,
+                        ),
                     },
                 },
             },
+            revision_desc: if value.changes.is_empty() {
+                None
+            } else {
+                Some(schema::RevisionDesc {
+                    change: value.changes.into_iter().map(Into::into).collect(),
+                })
+            },
+        }
+    }
+}
+
+/// Build the `<objectDesc>`, leaving out all elements that would be empty.
+fn denorm_object_desc(
+    material: Option<normalized::Material>,
+    support: Option<String>,
+    extent: Option<normalized::Extent>,
+    layouts: Vec<normalized::Layout>,
+) -> Option<schema::ObjectDesc> {
+    let support_desc = if material.is_none() && support.is_none() && extent.is_none() {
+        None
+    } else {
+        Some(schema::SupportDesc {
+            material,
+            support,
+            extent,
+        })
+    };
+    let layout_desc = if layouts.is_empty() {
+        None
+    } else {
+        Some(schema::LayoutDesc { layout: layouts })
+    };
+    if support_desc.is_none() && layout_desc.is_none() {
+        None
+    } else {
+        Some(schema::ObjectDesc {
+            support_desc,
+            layout_desc,
+        })
+    }
+}
+
+/// Build the `<history>`, leaving out all elements that would be empty.
+fn denorm_history(
+    orig_date: Option<normalized::DateRange>,
+    orig_date_desc: Option<String>,
+    orig_place: Option<String>,
+    provenance: Vec<String>,
+) -> Option<schema::History> {
+    let orig_date = if orig_date.is_none() && orig_date_desc.is_none() {
+        None
+    } else {
+        Some(denorm_orig_date(
+            orig_date.unwrap_or_default(),
+            orig_date_desc,
+        ))
+    };
+    let origin = if orig_date.is_none() && orig_place.is_none() {
+        None
+    } else {
+        Some(schema::Origin {
+            orig_date,
+            orig_place,
+        })
+    };
+    if origin.is_none() && provenance.is_empty() {
+        None
+    } else {
+        Some(schema::History { origin, provenance })
+    }
+}
+
+/// Build an `<origDate>`, using `@when` for exact dates.
+fn denorm_orig_date(range: normalized::DateRange, description: Option<String>) -> schema::OrigDate {
+    if range.is_exact() {
+        schema::OrigDate {
+            when: range.not_before.map(|d| d.to_string()),
+            not_before: None,
+            not_after: None,
+            description,
+        }
+    } else {
+        schema::OrigDate {
+            when: None,
+            not_before: range.not_before.map(|d| d.to_string()),
+            not_after: range.not_after.map(|d| d.to_string()),
+            description,
         }
     }
 }
@@ -391,9 +908,11 @@ impl TryFrom<normalized::Page> for schema::Page {
     type Error = NormalizationError;
     fn try_from(value: normalized::Page) -> Result<Self, Self::Error> {
         Ok(Self {
+            facs: value.facs,
             lang: value.lang,
             n: value.n,
             div_type: "page".to_string(),
+            status: value.status,
             columns: value
                 .columns
                 .into_iter()
@@ -408,6 +927,7 @@ impl TryFrom<normalized::Column> for schema::Column {
 
     fn try_from(value: normalized::Column) -> Result<Self, Self::Error> {
         Ok(Self {
+            facs: value.facs,
             lang: value.lang,
             div_type: "column".to_string(),
             n: Some(value.n),
@@ -425,6 +945,7 @@ impl TryFrom<normalized::Line> for schema::Line {
 
     fn try_from(value: normalized::Line) -> Result<Self, Self::Error> {
         Ok(Self {
+            facs: value.facs,
             lang: value.lang,
             div_type: "line".to_string(),
             n: Some(value.n),
@@ -517,22 +1038,41 @@ mod test {
             meta: crate::normalized::Meta {
                 alt_identifier: vec![],
                 title: "Manuskript Name".to_string(),
+                country: None,
+                settlement: None,
                 institution: Some("University of does-not-exist".to_string()),
+                repository: None,
                 collection: Some("Collectors Edition 2 electric boogaloo".to_string()),
+                shelfmark: None,
                 hand_desc: Some("There are two recognizable Hands: hand1 and hand2.".to_string()),
+                hands: vec![],
                 script_desc: Some("Die Schrift in diesem Manuskript gibt es.".to_string()),
+                orig_date: None,
+                orig_date_desc: None,
+                orig_place: None,
+                provenance: vec![],
+                material: None,
+                support: None,
+                extent: None,
+                layouts: vec![],
+                contents: vec![],
+                changes: vec![],
             },
             text: crate::normalized::Text {
                 lang: "hbo-Hebr".to_string(),
                 pages: vec![crate::normalized::Page {
+                    facs: None,
+                    status: None,
                     lang: None,
                     n: "34_v".to_string(),
                     columns: vec![
                         crate::normalized::Column {
+                            facs: None,
                             lang: None,
                             n: 1,
                             lines: vec![
                                 crate::normalized::Line {
+                                    facs: None,
                                     lang: None,
                                     n: 2,
                                     blocks: vec![
@@ -563,6 +1103,7 @@ mod test {
                                     ],
                                 },
                                 crate::normalized::Line {
+                                    facs: None,
                                     lang: Some("hbo-Hebr-x-babli".to_string()),
                                     n: 3,
                                     blocks: vec![crate::normalized::InlineBlock::Text(
@@ -575,9 +1116,11 @@ mod test {
                             ],
                         },
                         crate::normalized::Column {
+                            facs: None,
                             lang: None,
                             n: 2,
                             lines: vec![crate::normalized::Line {
+                                facs: None,
                                 lang: None,
                                 n: 2,
                                 blocks: vec![
@@ -590,7 +1133,7 @@ mod test {
                                     crate::normalized::InlineBlock::Uncertain(
                                         crate::normalized::Uncertain {
                                             lang: None,
-                                            cert: Some("high".to_string()),
+                                            cert: Some(crate::normalized::Certainty::High),
                                             agent: "water".to_string(),
                                             content: "d".to_string(),
                                         },
@@ -607,7 +1150,9 @@ mod test {
                                             reason: "lost".to_string(),
                                             unit: crate::normalized::ExtentUnit::Character,
                                             n: 12,
-                                            cert: Some("0.10".to_string()),
+                                            cert: Some(crate::normalized::Certainty::Probability(
+                                                crate::schema::Probability::new(0.1).unwrap(),
+                                            )),
                                         },
                                     ),
                                     crate::normalized::InlineBlock::Abbreviation(
@@ -646,10 +1191,293 @@ mod test {
                     ],
                 }],
             },
+            facsimile: crate::normalized::Facsimile::default(),
         };
         assert_eq!(norm_res.unwrap(), expected);
     }
 
+    /// origin dates and places are read from the history
+    #[test]
+    fn history_dates() {
+        let xml = include_str!("../examples/12_history.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        assert_eq!(
+            normed.meta.orig_date,
+            Some(crate::normalized::DateRange {
+                not_before: Some(crate::normalized::Date::year(900)),
+                not_after: Some(crate::normalized::Date::year(950)),
+            })
+        );
+        assert_eq!(
+            normed.meta.orig_date_desc,
+            Some("first half of the 10th century".to_string())
+        );
+        assert_eq!(normed.meta.orig_place, Some("Tiberias".to_string()));
+        assert_eq!(normed.meta.provenance.len(), 2);
+
+        let denormed: crate::schema::Tei = normed.clone().try_into().unwrap();
+        let renormed: crate::normalized::Manuscript = denormed.try_into().unwrap();
+        assert_eq!(renormed, normed);
+
+        // all witnesses before 1000 CE
+        let undated: crate::normalized::Manuscript = quick_xml::de::from_str::<crate::schema::Tei>(
+            include_str!("../examples/10_hand_notes.xml"),
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        let witnesses = [&normed, &undated];
+        let early = witnesses
+            .iter()
+            .filter(|m| m.meta.written_before(crate::normalized::Date::year(1000)))
+            .count();
+        assert_eq!(early, 1);
+        assert!(
+            !normed
+                .meta
+                .written_before(crate::normalized::Date::year(950))
+        );
+
+        let norm_with = |from: &str, to: &str| {
+            let xml = xml.replace(from, to);
+            let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(&xml);
+            TryInto::<crate::normalized::Manuscript>::try_into(xml_res.unwrap())
+        };
+        assert_eq!(
+            norm_with(r#"notAfter="0950""#, r#"notAfter="10th century""#),
+            Err(crate::denorm::NormalizationError::InvalidDate(
+                "10th century".to_string(),
+                crate::normalized::DateError::Malformed
+            ))
+        );
+        assert_eq!(
+            norm_with(r#"notAfter="0950""#, r#"notAfter="0950-02-31""#),
+            Err(crate::denorm::NormalizationError::InvalidDate(
+                "0950-02-31".to_string(),
+                crate::normalized::DateError::InvalidDay(31)
+            ))
+        );
+        assert_eq!(
+            norm_with(r#"notAfter="0950""#, r#"notAfter="0850""#),
+            Err(crate::denorm::NormalizationError::EmptyDateRange(
+                "0900".to_string(),
+                "0850".to_string()
+            ))
+        );
+        assert_eq!(
+            norm_with(r#"notAfter="0950""#, r#"when="0950""#),
+            Err(crate::denorm::NormalizationError::AmbiguousDate(
+                "0950".to_string()
+            ))
+        );
+    }
+
+    /// A line without `@n` after line `i32::MAX` would need a number that does not exist.
+    #[test]
+    fn line_nr_at_maximum() {
+        let xml = include_str!("../examples/02_lines_consistent.xml").replace(
+            r#"<div type="line" n="3">"#,
+            r#"<div type="line" n="2147483647">"#,
+        );
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(&xml);
+        assert_eq!(
+            crate::normalized::Manuscript::try_from(xml_res.unwrap()),
+            Err(super::NormalizationError::LineNrOverflow(1))
+        );
+    }
+
+    /// A column without `@n` after column `i32::MAX` would need a number that does not exist.
+    #[test]
+    fn column_nr_at_maximum() {
+        let xml = include_str!("../examples/02_lines_consistent.xml").replace(
+            r#"<div type="column" n="1">"#,
+            r#"<div type="column" n="2147483647">"#,
+        );
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(&xml);
+        assert_eq!(
+            crate::normalized::Manuscript::try_from(xml_res.unwrap()),
+            Err(super::NormalizationError::ColumnNrOverflow)
+        );
+    }
+
+    #[test]
+    fn facs() {
+        let xml = include_str!("../examples/14_facsimile.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        let page = &normed.text.pages[0];
+        assert_eq!(page.facs.as_deref(), Some("#s_01_r"));
+        assert_eq!(page.columns[0].facs.as_deref(), Some("#z_01_r_c1"));
+        assert_eq!(
+            page.columns[0]
+                .lines
+                .iter()
+                .map(|l| (l.n, l.facs.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![(1, Some("#z_01_r_c1_l1")), (3, Some("#z_01_r_c1_l3"))]
+        );
+        assert_eq!(normed.facsimile.surface.len(), 2);
+
+        let denormed: crate::schema::Tei = normed.clone().try_into().unwrap();
+        let normed_again: crate::normalized::Manuscript = denormed.try_into().unwrap();
+        assert_eq!(normed, normed_again);
+
+        let dangling = xml.replace("#z_01_r_c1_l3", "#z_01_r_c1_l2");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(&dangling);
+        assert_eq!(
+            crate::normalized::Manuscript::try_from(xml_res.unwrap()),
+            Err(super::NormalizationError::UnknownFacs(
+                "#z_01_r_c1_l2".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn layout_deviations() {
+        let xml = include_str!("../examples/13_layout.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        assert_eq!(
+            normed.meta.material,
+            Some(crate::normalized::Material::Parchment)
+        );
+        assert_eq!(
+            normed.meta.extent.as_ref().and_then(|e| e.leaves()),
+            Some(3)
+        );
+        let page = |n: &str| normed.text.pages.iter().find(|p| p.n == n).unwrap();
+        // numbered columns and lines count the ones before them
+        assert_eq!(page("03_r").column_count(), 3);
+        assert_eq!(page("03_r").columns[1].line_count(), 3);
+        assert_eq!(page("02_v").columns[1].line_count(), 2);
+        assert_eq!(page("02_r").column_count(), 0);
+        assert_eq!(
+            normed.layout_deviations(),
+            vec![
+                // a space at the end adds a line
+                crate::normalized::LayoutDeviation::Lines {
+                    page: "01_r".to_string(),
+                    column: 2,
+                    actual: 4,
+                },
+                // the gap completes the columns, but not the lines
+                crate::normalized::LayoutDeviation::Lines {
+                    page: "01_v".to_string(),
+                    column: 1,
+                    actual: 4,
+                },
+                crate::normalized::LayoutDeviation::Columns {
+                    page: "03_r".to_string(),
+                    actual: 3,
+                },
+            ]
+        );
+
+        let denormed: crate::schema::Tei = normed.clone().try_into().unwrap();
+        let renormed: crate::normalized::Manuscript = denormed.try_into().unwrap();
+        assert_eq!(renormed, normed);
+    }
+
+    #[test]
+    fn typed_identifiers() {
+        let xml = include_str!("../examples/11_identifiers.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        assert_eq!(normed.meta.country, Some("Vatican City".to_string()));
+        assert_eq!(normed.meta.repository, Some("Manuscripts".to_string()));
+        assert_eq!(normed.meta.shelfmark, Some("Vat. gr. 1209".to_string()));
+        assert_eq!(normed.meta.identifier("Rahlfs"), Some("B"));
+        assert_eq!(normed.meta.identifier("Gregory-Aland"), Some("03"));
+        assert_eq!(normed.meta.identifier("Kennicott"), None);
+        assert_eq!(
+            normed.meta.alt_identifier[2],
+            crate::normalized::Identifier {
+                id_type: None,
+                idno: "Codex Vaticanus".to_string(),
+            }
+        );
+
+        let denormed: crate::schema::Tei = normed.clone().try_into().unwrap();
+        let renormed: crate::normalized::Manuscript = denormed.try_into().unwrap();
+        assert_eq!(renormed, normed);
+    }
+
+    /// declared hands are read from the header and checked against their use in corrections
+    #[test]
+    fn declared_hands() {
+        let xml = include_str!("../examples/10_hand_notes.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        assert_eq!(
+            normed.meta.hand_desc,
+            Some("There are two recognizable Hands.".to_string())
+        );
+        assert_eq!(
+            normed.meta.hands,
+            vec![
+                crate::normalized::Hand {
+                    id: "hand1".to_string(),
+                    description: Some("The hand of the main scribe.".to_string()),
+                    scribe: Some("main-scribe".to_string()),
+                    script: Some("square".to_string()),
+                    not_before: Some(crate::normalized::Date::year(900)),
+                    not_after: Some(crate::normalized::Date::year(950)),
+                },
+                crate::normalized::Hand {
+                    id: "hand2".to_string(),
+                    description: Some("A later corrector.".to_string()),
+                    scribe: None,
+                    script: None,
+                    not_before: Some(crate::normalized::Date::year(1100)),
+                    not_after: None,
+                },
+            ]
+        );
+        assert_eq!(
+            normed.meta.hand("#hand2").map(|h| h.id.as_str()),
+            Some("hand2")
+        );
+
+        let denormed: crate::schema::Tei = normed.clone().try_into().unwrap();
+        let renormed: crate::normalized::Manuscript = denormed.try_into().unwrap();
+        assert_eq!(renormed, normed);
+
+        let xml = xml.replace(r##"hand="#hand2""##, r#"hand="hand3""#);
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(&xml);
+        let norm_res: Result<crate::normalized::Manuscript, _> = xml_res.unwrap().try_into();
+        assert_eq!(
+            norm_res,
+            Err(super::NormalizationError::UndeclaredHand(
+                "hand3".to_string()
+            ))
+        );
+    }
+
+    /// certainty is aggregated per page
+    #[test]
+    fn certainty_by_page() {
+        let xml = include_str!("../examples/07_multi-page.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        let normed: crate::normalized::Manuscript = xml_res.unwrap().try_into().unwrap();
+        let by_page = normed.certainty_by_page();
+        assert_eq!(by_page.len(), 2);
+
+        let (name, first) = by_page[0];
+        assert_eq!(name, "34_v");
+        assert_eq!(first.high, 1);
+        assert_eq!(first.probabilities, 1);
+        assert_eq!(first.min_probability, Some(0.1));
+        assert_eq!(first.mean_probability(), Some(0.1));
+        assert_eq!(first.total(), 2);
+
+        let (name, second) = by_page[1];
+        assert_eq!(name, "35_r");
+        assert_eq!(second.unspecified, 2);
+        assert_eq!(second.mean_probability(), None);
+        assert_eq!(second.total(), 2);
+    }
+
     /// normalization after denormalization is the identity
     ///
     /// Note that the other direction is not correct:
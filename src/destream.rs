@@ -24,6 +24,11 @@ pub enum StreamError {
     ///
     /// This needs to be marked as a column spanning lacuna instead
     NoLinesInColumn(i32),
+    /// A [`FacsLink`](streamed::FacsLink) points to a page, column or line that is not in the text
+    FacsPositionMissing(streamed::FacsLink),
+    /// A line or column spanning lacuna or space moves the line or column number beyond
+    /// [`i32::MAX`]
+    IndexOverflow,
 }
 impl core::fmt::Display for StreamError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
@@ -58,6 +63,26 @@ impl core::fmt::Display for StreamError {
                     "Column {col_idx} contained no lines. Please mark this as a column-spanning lacuna instead."
                 )
             }
+            Self::FacsPositionMissing(link) => {
+                write!(
+                    f,
+                    "The facs \"{}\" is linked to page {}",
+                    link.facs, link.page
+                )?;
+                if let Some(column) = link.column {
+                    write!(f, ", column {column}")?;
+                }
+                if let Some(line) = link.line {
+                    write!(f, ", line {line}")?;
+                }
+                write!(f, ", which does not exist.")
+            }
+            Self::IndexOverflow => {
+                write!(
+                    f,
+                    "A lacuna or space is so large that the following line or column number is too large."
+                )
+            }
         }
     }
 }
@@ -67,13 +92,41 @@ impl TryFrom<normalized::Manuscript> for streamed::Manuscript {
     type Error = StreamError;
 
     fn try_from(value: normalized::Manuscript) -> Result<Self, Self::Error> {
+        let facs = facs_links(&value.text.pages);
         Ok(Self {
             meta: value.meta,
             content: value.text.try_into()?,
+            facsimile: value.facsimile,
+            facs,
         })
     }
 }
 
+/// Collect the `facs` of all `pages` and their columns and lines.
+pub(crate) fn facs_links(pages: &[normalized::Page]) -> Vec<streamed::FacsLink> {
+    let mut res = vec![];
+    for page in pages {
+        let mut push = |column, line, facs: &Option<String>| {
+            if let Some(facs) = facs {
+                res.push(streamed::FacsLink {
+                    page: page.n.clone(),
+                    column,
+                    line,
+                    facs: facs.clone(),
+                });
+            }
+        };
+        push(None, None, &page.facs);
+        for column in &page.columns {
+            push(Some(column.n), None, &column.facs);
+            for line in &column.lines {
+                push(Some(column.n), Some(line.n), &line.facs);
+            }
+        }
+    }
+    res
+}
+
 /// An iterator over the individual [`Block`](streamed::Block)s representing a single
 /// [`Page`](normalized::Page).
 pub struct BlocksFromPage<'a> {
@@ -96,10 +149,10 @@ pub struct BlocksFromPage<'a> {
     /// the logical numbering of the line (i.e. getting larger when passing line-spanning
     /// lacuna)
     line_idx: i32,
-    /// Will be initialized as `Some(own name)`.
+    /// Will be initialized as `Some((own name, own status))`.
     ///
-    /// When `Some(x)`, will output `PageBreak(x)`, taking ownership and leaving None here
-    return_own_startbreak_next: Option<String>,
+    /// When `Some((x, s))`, will output `PageBreak(x, s)`, taking ownership and leaving None here
+    return_own_startbreak_next: Option<PageStart>,
     /// signals that the next `Break(BreakType::Line)` should be skipped
     skip_next_linebreak: bool,
     /// signals that the next `Break(BreakType::Column)` should be skipped
@@ -125,7 +178,7 @@ impl<'a> BlocksFromPage<'a> {
             default_language,
             col_idx: 0,
             line_idx: 0,
-            return_own_startbreak_next: Some(page.n),
+            return_own_startbreak_next: Some((page.n, page.status)),
             skip_next_linebreak: false,
             // the column break after the initial page break has to be skipped
             skip_next_columnbreak: true,
@@ -138,7 +191,10 @@ impl<'a> BlocksFromPage<'a> {
     /// This also checks line index consistency and errors if the line index is inconsistent
     fn load_next_line(&mut self, next_line: normalized::Line) -> Result<(), StreamError> {
         // a new logical line has started - increase the logical line number
-        self.line_idx += 1;
+        self.line_idx = self
+            .line_idx
+            .checked_add(1)
+            .ok_or(StreamError::IndexOverflow)?;
         // this lines language is either given, or supplied from the column
         if let Some(new_lang) = next_line.lang {
             self.current_language.clone_from(&new_lang);
@@ -166,7 +222,10 @@ impl<'a> BlocksFromPage<'a> {
     /// Returns:
     /// - true IFF this was the first column loaded
     fn load_next_column(&mut self, next_column: normalized::Column) -> Result<(), StreamError> {
-        self.col_idx += 1;
+        self.col_idx = self
+            .col_idx
+            .checked_add(1)
+            .ok_or(StreamError::IndexOverflow)?;
         self.line_idx = 0;
         // this columns language is either given, or supplied from the page
         self.language_in_col = if let Some(new_lang) = next_column.lang {
@@ -200,9 +259,9 @@ impl Iterator for BlocksFromPage<'_> {
     type Item = Result<streamed::Block, StreamError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(own_name) = self.return_own_startbreak_next.take() {
+        if let Some((own_name, own_status)) = self.return_own_startbreak_next.take() {
             return Some(Ok(streamed::Block::Break(streamed::BreakType::Page(
-                own_name,
+                own_name, own_status,
             ))));
         }
 
@@ -272,7 +331,10 @@ impl Iterator for BlocksFromPage<'_> {
                 n,
                 ..
             }) => {
-                self.line_idx += n;
+                let Some(idx) = self.line_idx.checked_add(n) else {
+                    return Some(Err(StreamError::IndexOverflow));
+                };
+                self.line_idx = idx;
                 self.skip_next_linebreak = true;
                 return Some(Ok(streamed_block));
             }
@@ -284,7 +346,10 @@ impl Iterator for BlocksFromPage<'_> {
                 n,
                 ..
             }) => {
-                self.col_idx += n;
+                let Some(idx) = self.col_idx.checked_add(n) else {
+                    return Some(Err(StreamError::IndexOverflow));
+                };
+                self.col_idx = idx;
                 self.skip_next_columnbreak = true;
                 return Some(Ok(streamed_block));
             }
@@ -294,7 +359,10 @@ impl Iterator for BlocksFromPage<'_> {
                 quantity,
                 ..
             }) => {
-                self.line_idx += quantity;
+                let Some(idx) = self.line_idx.checked_add(quantity) else {
+                    return Some(Err(StreamError::IndexOverflow));
+                };
+                self.line_idx = idx;
                 self.skip_next_linebreak = true;
                 return Some(Ok(streamed_block));
             }
@@ -306,7 +374,10 @@ impl Iterator for BlocksFromPage<'_> {
                 quantity,
                 ..
             }) => {
-                self.col_idx += quantity;
+                let Some(idx) = self.col_idx.checked_add(quantity) else {
+                    return Some(Err(StreamError::IndexOverflow));
+                };
+                self.col_idx = idx;
                 self.skip_next_columnbreak = true;
                 return Some(Ok(streamed_block));
             }
@@ -329,6 +400,16 @@ impl normalized::Page {
     pub fn into_streamed(self, default_language: &str) -> BlocksFromPage<'_> {
         BlocksFromPage::new(self, default_language)
     }
+
+    /// Like [`into_streamed`](Self::into_streamed), but return the name and status of the page
+    /// separately instead of as a leading [`BreakType::Page`](streamed::BreakType::Page).
+    #[must_use]
+    pub fn into_streamed_content(self, default_language: &str) -> (PageStart, BlocksFromPage<'_>) {
+        let start = (self.n.clone(), self.status);
+        let mut blocks = BlocksFromPage::new(self, default_language);
+        blocks.return_own_startbreak_next = None;
+        (start, blocks)
+    }
 }
 
 impl TryFrom<normalized::Text> for Vec<streamed::Block> {
@@ -446,16 +527,72 @@ impl TryFrom<streamed::Manuscript> for normalized::Manuscript {
     type Error = StreamError;
 
     fn try_from(value: streamed::Manuscript) -> Result<Self, Self::Error> {
+        let mut text: normalized::Text = value.content.try_into()?;
+        for link in value.facs {
+            apply_facs_link(&mut text.pages, link)?;
+        }
         Ok(Self {
             meta: value.meta,
-            text: value.content.try_into()?,
+            text,
+            facsimile: value.facsimile,
         })
     }
 }
 
+/// Destream a single page, setting the `facs` its links point to.
+///
+/// Blocks after a page break are discarded.
+impl TryFrom<streamed::Page> for normalized::Page {
+    type Error = StreamError;
+
+    fn try_from(value: streamed::Page) -> Result<Self, Self::Error> {
+        let (mut page, _next_page) =
+            transform_until_page_end(&mut value.blocks.into_iter(), value.name, value.status)?;
+        for link in value.facs {
+            apply_facs_link(core::slice::from_mut(&mut page), link)?;
+        }
+        Ok(page)
+    }
+}
+
+/// Set the `facs` of the page, column or line in `pages` that `link` points to.
+pub(crate) fn apply_facs_link(
+    pages: &mut [normalized::Page],
+    link: streamed::FacsLink,
+) -> Result<(), StreamError> {
+    let Some(page) = pages.iter_mut().find(|p| p.n == link.page) else {
+        return Err(StreamError::FacsPositionMissing(link));
+    };
+    let target = match (link.column, link.line) {
+        (None, None) => Some(&mut page.facs),
+        (Some(c), None) => page
+            .columns
+            .iter_mut()
+            .find(|col| col.n == c)
+            .map(|col| &mut col.facs),
+        (Some(c), Some(l)) => page
+            .columns
+            .iter_mut()
+            .find(|col| col.n == c)
+            .and_then(|col| col.lines.iter_mut().find(|line| line.n == l))
+            .map(|line| &mut line.facs),
+        (None, Some(_)) => None,
+    };
+    match target {
+        Some(facs) => {
+            *facs = Some(link.facs);
+            Ok(())
+        }
+        None => Err(StreamError::FacsPositionMissing(link)),
+    }
+}
+
+/// The name and workflow status of a page, as given in its [`BreakType::Page`](streamed::BreakType::Page).
+pub type PageStart = (String, Option<normalized::PageStatus>);
+
 /// consume a stream of blocks until the current page ends
 ///
-/// then return the built page and the name of the NEXT [`Page`]
+/// then return the built page (with `status`) and the name and status of the NEXT [`Page`]
 /// - this is None, if the stream simply ended without us knowing the name of the next page
 /// - this name is part of the [`BreakType`](streamed::BreakType) ending this [`Page`], which we have to consume to see it
 ///
@@ -463,6 +600,9 @@ impl TryFrom<streamed::Manuscript> for normalized::Manuscript {
 /// You may forward to the next [`BreakType::Page`](streamed::BreakType::Page), consume it and then continue with the next page if
 /// you want to unroll
 ///
+/// If the page ends before any block was consumed, it is a [missing
+/// page](normalized::Page::is_missing) and is returned without columns.
+///
 /// # Errors
 /// Any of the Destream errors can occur while converting streamed blocks to normalized blocks.
 ///
@@ -470,7 +610,8 @@ impl TryFrom<streamed::Manuscript> for normalized::Manuscript {
 pub fn transform_until_page_end(
     stream: &mut impl Iterator<Item = streamed::Block>,
     page_nr: String,
-) -> Result<(normalized::Page, Option<String>), StreamError> {
+    status: Option<normalized::PageStatus>,
+) -> Result<(normalized::Page, Option<PageStart>), StreamError> {
     // these are logical indices we are building, keeping track of lacuna sizes
     let mut line_idx = 1;
     let mut column_idx = 1;
@@ -510,12 +651,8 @@ pub fn transform_until_page_end(
             }
         }
 
-        // this page is done, and there is another one afterwards
-        if let streamed::Block::Break(streamed::BreakType::Page(next_name)) = block {
-            break 'stream Some(next_name);
-        }
-        // add this block to this line:
-        handle_block(
+        // add this block to this line, unless it ends this page and there is another one afterwards
+        if let Some(next_page) = handle_block(
             block,
             &mut blocks_in_line,
             &mut lines,
@@ -524,7 +661,9 @@ pub fn transform_until_page_end(
             &mut language_use_in_col,
             &mut line_idx,
             &mut column_idx,
-        );
+        )? {
+            break 'stream Some(next_page);
+        }
     };
 
     // now we need to add the remaining blocks as a final line/column as in a column break
@@ -533,6 +672,7 @@ pub fn transform_until_page_end(
         let most_common_lang_in_line =
             most_common_lang(&language_use_in_line).map(std::string::ToString::to_string);
         lines.push(normalized::Line {
+            facs: None,
             lang: most_common_lang_in_line,
             n: line_idx,
             blocks: core::mem::take(&mut blocks_in_line),
@@ -544,19 +684,36 @@ pub fn transform_until_page_end(
             most_common_lang(&language_use_in_col).map(std::string::ToString::to_string);
         let take_lines = core::mem::take(&mut lines);
         columns.push(normalized::Column {
+            facs: None,
             lang: most_common_lang_in_col,
             n: column_idx,
             lines: take_lines,
         });
     }
 
+    // a page without any content is a missing page, which has no language
+    if columns.is_empty() {
+        return Ok((
+            normalized::Page {
+                facs: None,
+                lang: None,
+                columns,
+                n: page_nr,
+                status,
+            },
+            next_page_name,
+        ));
+    }
+
     let most_common_lang = normalize_language(&mut columns, &language_use)?;
 
     Ok((
         normalized::Page {
+            facs: None,
             lang: Some(most_common_lang.to_string()),
             columns,
             n: page_nr,
+            status,
         },
         next_page_name,
     ))
@@ -567,7 +724,7 @@ impl FromIterator<streamed::Block> for Result<normalized::Text, StreamError> {
         let mut blocks_iter = iter.into_iter();
         let mut pages = Vec::new();
         let mut langs_in_text = HashMap::<String, i32>::new();
-        let mut next_page_name = match blocks_iter.next() {
+        let (mut next_page_name, mut next_page_status) = match blocks_iter.next() {
             None => {
                 // empty text, just return a trivial Text
                 most_common_lang(&langs_in_text);
@@ -576,21 +733,21 @@ impl FromIterator<streamed::Block> for Result<normalized::Text, StreamError> {
                     pages,
                 });
             }
-            Some(streamed::Block::Break(streamed::BreakType::Page(x))) => x,
+            Some(streamed::Block::Break(streamed::BreakType::Page(x, s))) => (x, s),
             Some(_) => {
                 return Err(StreamError::FirstPageNameMissing);
             }
         };
         loop {
-            let (this_page, new_name) = transform_until_page_end(&mut blocks_iter, next_page_name)?;
-            let most_common_lang_in_page = this_page
-                .lang
-                .as_ref()
-                .expect("transform_until_page_end always sets language");
-            if let Some(this_lang_val) = langs_in_text.get_mut(most_common_lang_in_page) {
-                *this_lang_val += 1;
-            } else {
-                langs_in_text.insert(most_common_lang_in_page.clone(), 1);
+            let (this_page, new_name) =
+                transform_until_page_end(&mut blocks_iter, next_page_name, next_page_status)?;
+            // transform_until_page_end sets the language on all pages except missing ones
+            if let Some(most_common_lang_in_page) = this_page.lang.as_ref() {
+                if let Some(this_lang_val) = langs_in_text.get_mut(most_common_lang_in_page) {
+                    *this_lang_val += 1;
+                } else {
+                    langs_in_text.insert(most_common_lang_in_page.clone(), 1);
+                }
             }
             pages.push(this_page);
             let Some(y) = new_name else {
@@ -606,7 +763,7 @@ impl FromIterator<streamed::Block> for Result<normalized::Text, StreamError> {
                     pages,
                 });
             };
-            next_page_name = y;
+            (next_page_name, next_page_status) = y;
         }
     }
 }
@@ -633,14 +790,16 @@ fn end_line(
     blocks_in_line: Vec<normalized::InlineBlock>,
     line_idx: &mut i32,
     language_use_in_line: &mut HashMap<String, i32>,
-) {
+) -> Result<(), StreamError> {
     lines.push(normalized::Line {
+        facs: None,
         lang: most_common_lang(language_use_in_line).map(std::string::ToString::to_string),
         n: *line_idx,
         blocks: blocks_in_line,
     });
     *language_use_in_line = HashMap::<String, i32>::new();
-    *line_idx += 1;
+    *line_idx = line_idx.checked_add(1).ok_or(StreamError::IndexOverflow)?;
+    Ok(())
 }
 
 /// End a column with the current `lines`, push it to the columns, increase the index to the
@@ -651,15 +810,19 @@ fn end_column(
     line_idx: &mut i32,
     column_idx: &mut i32,
     language_use_in_col: &mut HashMap<String, i32>,
-) {
+) -> Result<(), StreamError> {
     columns.push(normalized::Column {
+        facs: None,
         lang: most_common_lang(language_use_in_col).map(std::string::ToString::to_string),
         n: *column_idx,
         lines,
     });
     *language_use_in_col = HashMap::<String, i32>::new();
-    *column_idx += 1;
+    *column_idx = column_idx
+        .checked_add(1)
+        .ok_or(StreamError::IndexOverflow)?;
     *line_idx = 1;
+    Ok(())
 }
 
 // this function is admittedly ugly - however, most of it is is one large match statement which
@@ -668,8 +831,8 @@ fn end_column(
 /// update language use and forward line and column indexes when
 /// a line or column is ended by this block
 ///
-/// # Panics
-/// MUST NOT BE CALLED on `block = Block::Break(BreakType::Page())!`
+/// A pagebreak cannot be added to this page; its name and status are returned instead, and the
+/// caller has to end this page.
 #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 fn handle_block(
     block: streamed::Block,
@@ -680,13 +843,10 @@ fn handle_block(
     language_use_in_col: &mut HashMap<String, i32>,
     line_idx: &mut i32,
     column_idx: &mut i32,
-) {
+) -> Result<Option<PageStart>, StreamError> {
     match block {
-        streamed::Block::Break(streamed::BreakType::Page(_n)) => {
-            panic!(
-                "handle_block MUST NOT be called on a pagebreak; \
-                you need to handle this because handle_block cannot split pages"
-            )
+        streamed::Block::Break(streamed::BreakType::Page(name, status)) => {
+            return Ok(Some((name, status)));
         }
         // end this line, start a new one
         streamed::Block::Break(streamed::BreakType::Line) => {
@@ -695,7 +855,7 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
         }
         // end this column, start a new one
         streamed::Block::Break(streamed::BreakType::Column) => {
@@ -705,7 +865,7 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
 
             // now end the column and go to the next one
             end_column(
@@ -714,7 +874,7 @@ fn handle_block(
                 line_idx,
                 column_idx,
                 language_use_in_col,
-            );
+            )?;
         }
         // end this line, skip several, start a new one
         streamed::Block::Space(
@@ -731,9 +891,11 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
             // skip `extent` lines
-            *line_idx += extent;
+            *line_idx = line_idx
+                .checked_add(extent)
+                .ok_or(StreamError::IndexOverflow)?;
         }
         // end this column, skip several, start a new one
         streamed::Block::Space(
@@ -751,7 +913,7 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
 
             // and finally end the column, skip some and and go to the next one
             end_column(
@@ -760,8 +922,10 @@ fn handle_block(
                 line_idx,
                 column_idx,
                 language_use_in_col,
-            );
-            *column_idx += extent;
+            )?;
+            *column_idx = column_idx
+                .checked_add(extent)
+                .ok_or(StreamError::IndexOverflow)?;
         }
         // end this line, skip several, start a new one
         streamed::Block::Lacuna(
@@ -779,9 +943,11 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
             // skip `extent` lines
-            *line_idx += extent;
+            *line_idx = line_idx
+                .checked_add(extent)
+                .ok_or(StreamError::IndexOverflow)?;
         }
         // end this column, skip several, start a new one
         streamed::Block::Lacuna(
@@ -799,7 +965,7 @@ fn handle_block(
                 core::mem::take(blocks_in_line),
                 line_idx,
                 language_use_in_line,
-            );
+            )?;
 
             // and finally end the column, skip some and and go to the next one
             end_column(
@@ -808,8 +974,10 @@ fn handle_block(
                 line_idx,
                 column_idx,
                 language_use_in_col,
-            );
-            *column_idx += extent;
+            )?;
+            *column_idx = column_idx
+                .checked_add(extent)
+                .ok_or(StreamError::IndexOverflow)?;
         }
         // these are the normal blocks - just convert them
         streamed::Block::Text(x) => {
@@ -844,6 +1012,7 @@ fn handle_block(
             blocks_in_line.push(normalized::InlineBlock::Abbreviation(x.into()));
         }
     }
+    Ok(None)
 }
 
 fn normalize_language<'b>(
@@ -991,6 +1160,7 @@ impl From<streamed::Paragraph> for normalized::Paragraph {
 
 #[cfg(test)]
 mod test {
+    use crate::fuzz::Rng;
     use crate::normalized;
     use crate::normalized::Abbreviation;
     use crate::streamed;
@@ -1027,13 +1197,28 @@ mod test {
             meta: streamed::Meta {
                 alt_identifier: vec![],
                 title: "Manuskript Name".to_string(),
+                country: None,
+                settlement: None,
                 institution: Some("University of does-not-exist".to_string()),
+                repository: None,
                 collection: Some("Collectors Edition 2 electric boogaloo".to_string()),
+                shelfmark: Some("34 verso".to_string()),
                 hand_desc: Some("There are two recognizable Hands: hand1 and hand2.".to_string()),
+                hands: vec![],
                 script_desc: Some("Die Schrift in diesem Manuskript gibt es.".to_string()),
+                orig_date: None,
+                orig_date_desc: None,
+                orig_place: None,
+                provenance: vec![],
+                material: None,
+                support: None,
+                extent: None,
+                layouts: vec![],
+                contents: vec![],
+                changes: vec![],
             },
             content: vec![
-                streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+                streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "hbo-Hebr".to_string(),
                     content: "line 1 content. This line is completely preserved.".to_string(),
@@ -1042,7 +1227,7 @@ mod test {
                     reason: "lost".to_string(),
                     unit: streamed::ExtentUnit::Line,
                     n: 1,
-                    cert: Some("high".to_string()),
+                    cert: Some(crate::normalized::Certainty::High),
                 }),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "hbo-Hebr".to_string(),
@@ -1065,13 +1250,15 @@ mod test {
                     reason: "lost".to_string(),
                     unit: streamed::ExtentUnit::Column,
                     n: 2,
-                    cert: Some("high".to_string()),
+                    cert: Some(crate::normalized::Certainty::High),
                 }),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "hbo-Hebr".to_string(),
                     content: "text".to_string(),
                 }),
             ],
+            facsimile: streamed::Facsimile::default(),
+            facs: vec![],
         };
         assert_eq!(streamed_res.unwrap(), expected);
     }
@@ -1139,12 +1326,16 @@ mod test {
         let normalized = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: None,
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1159,7 +1350,7 @@ mod test {
         assert_eq!(
             streamed,
             vec![
-                streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+                streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "hbo-Hebr".to_string(),
                     content: "text in hbo-Hebr".to_string()
@@ -1173,12 +1364,16 @@ mod test {
         let normalized = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: Some("syr".to_string()),
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: Some("grc".to_string()),
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1193,7 +1388,7 @@ mod test {
         assert_eq!(
             streamed,
             vec![
-                streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+                streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "grc".to_string(),
                     content: "text".to_string()
@@ -1207,13 +1402,17 @@ mod test {
         let normalized = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![
                         normalized::Line {
+                            facs: None,
                             lang: Some("grc".to_string()),
                             n: 1,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1222,6 +1421,7 @@ mod test {
                             })],
                         },
                         normalized::Line {
+                            facs: None,
                             lang: Some("grc".to_string()),
                             n: 2,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1230,6 +1430,7 @@ mod test {
                             })],
                         },
                         normalized::Line {
+                            facs: None,
                             lang: None,
                             n: 3,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1245,7 +1446,7 @@ mod test {
         assert_eq!(
             streamed,
             vec![
-                streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+                streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "grc".to_string(),
                     content: "text".to_string()
@@ -1266,13 +1467,17 @@ mod test {
         let expected = normalized::Text {
             lang: "grc".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![
                         normalized::Line {
+                            facs: None,
                             lang: None,
                             n: 1,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1281,6 +1486,7 @@ mod test {
                             })],
                         },
                         normalized::Line {
+                            facs: None,
                             lang: None,
                             n: 2,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1289,6 +1495,7 @@ mod test {
                             })],
                         },
                         normalized::Line {
+                            facs: None,
                             lang: Some("hbo-Hebr".to_string()),
                             n: 3,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1308,14 +1515,18 @@ mod test {
         let normalized = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![
                     normalized::Column {
+                        facs: None,
                         lang: Some("grc".to_string()),
                         n: 1,
                         lines: vec![
                             normalized::Line {
+                                facs: None,
                                 lang: None,
                                 n: 1,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1326,6 +1537,7 @@ mod test {
                                 )],
                             },
                             normalized::Line {
+                                facs: None,
                                 lang: Some("hbo-Hebr".to_string()),
                                 n: 2,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1336,6 +1548,7 @@ mod test {
                                 )],
                             },
                             normalized::Line {
+                                facs: None,
                                 lang: None,
                                 n: 3,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1348,9 +1561,11 @@ mod test {
                         ],
                     },
                     normalized::Column {
+                        facs: None,
                         lang: Some("hbo-Hebr".to_string()),
                         n: 2,
                         lines: vec![normalized::Line {
+                            facs: None,
                             lang: None,
                             n: 1,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1366,7 +1581,7 @@ mod test {
         assert_eq!(
             streamed,
             vec![
-                streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+                streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
                 streamed::Block::Text(streamed::Paragraph {
                     lang: "grc".to_string(),
                     content: "text".to_string()
@@ -1392,14 +1607,18 @@ mod test {
         let expected = normalized::Text {
             lang: "grc".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![
                     normalized::Column {
+                        facs: None,
                         lang: None,
                         n: 1,
                         lines: vec![
                             normalized::Line {
+                                facs: None,
                                 lang: None,
                                 n: 1,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1410,6 +1629,7 @@ mod test {
                                 )],
                             },
                             normalized::Line {
+                                facs: None,
                                 lang: Some("hbo-Hebr".to_string()),
                                 n: 2,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1420,6 +1640,7 @@ mod test {
                                 )],
                             },
                             normalized::Line {
+                                facs: None,
                                 lang: None,
                                 n: 3,
                                 blocks: vec![normalized::InlineBlock::Text(
@@ -1432,9 +1653,11 @@ mod test {
                         ],
                     },
                     normalized::Column {
+                        facs: None,
                         lang: None,
                         n: 2,
                         lines: vec![normalized::Line {
+                            facs: None,
                             lang: None,
                             n: 1,
                             blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1455,12 +1678,16 @@ mod test {
         let normalized: normalized::Text = normalized::Text {
             lang: "lang".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: None,
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Text(normalized::Paragraph {
@@ -1473,7 +1700,7 @@ mod test {
         };
         let streamed: Vec<streamed::Block> = normalized.try_into().unwrap();
         let expected = vec![
-            streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+            streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
             streamed::Block::Text(streamed::Paragraph {
                 lang: "lang".to_string(),
                 content: "content".to_string(),
@@ -1488,12 +1715,16 @@ mod test {
         let normalized: normalized::Text = normalized::Text {
             lang: "lang".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: None,
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Abbreviation(Abbreviation {
@@ -1513,7 +1744,7 @@ mod test {
         };
         let streamed: Vec<streamed::Block> = normalized.try_into().unwrap();
         let expected = vec![
-            streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+            streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
             streamed::Block::Abbreviation(streamed::Abbreviation {
                 surface_lang: "grc".to_string(),
                 surface: "πιπι".to_string(),
@@ -1527,12 +1758,16 @@ mod test {
         let expected_destreamed: normalized::Text = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: None,
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Abbreviation(Abbreviation {
@@ -1558,12 +1793,16 @@ mod test {
         let normalized: normalized::Text = normalized::Text {
             lang: "hbo-Hebr".to_string(),
             pages: vec![normalized::Page {
+                facs: None,
+                status: None,
                 lang: None,
                 n: "page1".to_string(),
                 columns: vec![normalized::Column {
+                    facs: None,
                     lang: None,
                     n: 1,
                     lines: vec![normalized::Line {
+                        facs: None,
                         lang: None,
                         n: 1,
                         blocks: vec![normalized::InlineBlock::Correction(
@@ -1589,7 +1828,7 @@ mod test {
         };
         let streamed: Vec<streamed::Block> = normalized.try_into().unwrap();
         let expected = vec![
-            streamed::Block::Break(streamed::BreakType::Page("page1".to_string())),
+            streamed::Block::Break(streamed::BreakType::Page("page1".to_string(), None)),
             streamed::Block::Correction(streamed::Correction {
                 versions: vec![
                     streamed::Version {
@@ -1624,8 +1863,106 @@ mod test {
 
         let destreamed: Result<normalized::Manuscript, _> = streamed.clone().try_into();
         assert!(destreamed.is_ok());
-        let restreamed: Result<streamed::Manuscript, _> = destreamed.unwrap().try_into();
+        let destreamed = destreamed.unwrap();
+        assert_eq!(streamed.certainty_by_page(), destreamed.certainty_by_page());
+        let restreamed: Result<streamed::Manuscript, _> = destreamed.try_into();
         assert!(restreamed.is_ok());
         assert_eq!(streamed, restreamed.unwrap());
     }
+
+    /// Missing pages are only a pagebreak in the streamed form and roundtrip as pages without
+    /// columns
+    #[test]
+    fn missing_pages() {
+        let xml = include_str!("../examples/09_missing_pages.xml");
+        let xml_res: Result<crate::schema::Tei, _> = quick_xml::de::from_str(xml);
+        assert!(xml_res.is_ok());
+        let norm_res: Result<crate::normalized::Manuscript, _> = xml_res.unwrap().try_into();
+        assert!(norm_res.is_ok());
+        let normalized = norm_res.unwrap();
+        assert_eq!(normalized.missing_pages(), vec!["01_v", "02_r", "03_r"]);
+
+        let streamed: streamed::Manuscript = normalized.clone().try_into().unwrap();
+        assert_eq!(
+            streamed.content[2..5],
+            [
+                streamed::Block::Break(streamed::BreakType::Page("01_v".to_string(), None)),
+                streamed::Block::Break(streamed::BreakType::Page("02_r".to_string(), None)),
+                streamed::Block::Break(streamed::BreakType::Page("02_v".to_string(), None)),
+            ]
+        );
+        assert_eq!(
+            streamed.content.last(),
+            Some(&streamed::Block::Break(streamed::BreakType::Page(
+                "03_r".to_string(),
+                None
+            )))
+        );
+        assert_eq!(streamed.missing_pages(), vec!["01_v", "02_r", "03_r"]);
+
+        let destreamed: normalized::Manuscript = streamed.try_into().unwrap();
+        assert_eq!(destreamed, normalized);
+    }
+
+    /// Random block sequences are destreamed and streamed again without panicking.
+    #[test]
+    fn fuzz_blocks() {
+        let mut rng = Rng(0xb10c_5eed);
+        for _ in 0..2000 {
+            let blocks = rng.blocks(30);
+            let page_name = rng.string();
+            let _ =
+                super::transform_until_page_end(&mut blocks.clone().into_iter(), page_name, None);
+            let text: Result<normalized::Text, _> = blocks.into_iter().collect();
+            if let Ok(text) = text {
+                let _ = Vec::<streamed::Block>::try_from(text);
+            }
+        }
+    }
+
+    /// Line numbers beyond `i32::MAX` are reported instead of overflowing.
+    #[test]
+    fn index_overflow() {
+        let text = |lang: &str| {
+            streamed::Block::Text(streamed::Paragraph {
+                lang: lang.to_string(),
+                content: "text".to_string(),
+            })
+        };
+        let gap = normalized::Lacuna {
+            unit: normalized::ExtentUnit::Line,
+            n: i32::MAX,
+            ..Default::default()
+        };
+        let blocks = vec![
+            streamed::Block::Break(streamed::BreakType::Page("1r".to_string(), None)),
+            text("grc"),
+            streamed::Block::Lacuna(gap.clone()),
+            text("grc"),
+        ];
+        let destreamed: Result<normalized::Text, _> = blocks.into_iter().collect();
+        assert_eq!(destreamed, Err(super::StreamError::IndexOverflow));
+
+        let page = normalized::Page {
+            lang: None,
+            n: "1r".to_string(),
+            status: None,
+            facs: None,
+            columns: vec![normalized::Column {
+                lang: None,
+                n: 1,
+                facs: None,
+                lines: vec![normalized::Line {
+                    lang: None,
+                    n: 1,
+                    facs: None,
+                    blocks: vec![normalized::InlineBlock::Lacuna(gap)],
+                }],
+            }],
+        };
+        assert_eq!(
+            page.into_streamed("grc").collect::<Result<Vec<_>, _>>(),
+            Err(super::StreamError::IndexOverflow)
+        );
+    }
 }
@@ -0,0 +1,154 @@
+//! Random input for the tests that make sure conversions never panic.
+
+use crate::{
+    normalized,
+    schema::{self, Certainty, ExtentUnit, PageStatus},
+    streamed::{self, Block, BreakType},
+};
+
+/// A small deterministic PRNG (xorshift64*), so the fuzz tests are reproducible.
+pub(crate) struct Rng(pub u64);
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+    pub fn int(&mut self) -> i32 {
+        match self.below(3) {
+            0 => i32::MIN,
+            1 => i32::MAX,
+            _ => (self.next() as i32) >> self.below(32),
+        }
+    }
+    pub fn string(&mut self) -> String {
+        const CHARS: [char; 8] = ['a', 'Z', ' ', '<', '\n', 'ב', 'λ', '𐤀'];
+        (0..self.below(12))
+            .map(|_| CHARS[self.below(8) as usize])
+            .collect()
+    }
+    pub fn option<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        (self.below(2) == 0).then(|| f(self))
+    }
+    pub fn certainty(&mut self) -> Certainty {
+        match self.below(5) {
+            0 => Certainty::High,
+            1 => Certainty::Medium,
+            2 => Certainty::Low,
+            3 => Certainty::Unknown,
+            _ => Certainty::Probability(
+                schema::Probability::new(self.below(1001) as f64 / 1000.0).unwrap(),
+            ),
+        }
+    }
+    pub fn unit(&mut self) -> ExtentUnit {
+        [ExtentUnit::Character, ExtentUnit::Line, ExtentUnit::Column][self.below(3) as usize]
+    }
+    pub fn block(&mut self) -> Block {
+        match self.below(10) {
+            0 => Block::Break(BreakType::Line),
+            1 => Block::Break(BreakType::Column),
+            2 => Block::Break(BreakType::Page(
+                self.string(),
+                self.option(|r| [PageStatus::Draft, PageStatus::Approved][r.below(2) as usize]),
+            )),
+            3 => Block::Lacuna(schema::Gap {
+                reason: self.string(),
+                unit: self.unit(),
+                n: self.int(),
+                cert: self.option(Self::certainty),
+            }),
+            4 => Block::Anchor(normalized::Anchor {
+                anchor_id: self.string(),
+                anchor_type: self.string(),
+            }),
+            5 => Block::Correction(streamed::Correction {
+                versions: (0..self.below(3))
+                    .map(|_| streamed::Version {
+                        lang: self.string(),
+                        hand: self.option(Self::string),
+                        content: self.string(),
+                    })
+                    .collect(),
+            }),
+            6 => Block::Uncertain(streamed::Uncertain {
+                lang: self.string(),
+                cert: self.option(Self::certainty),
+                agent: self.string(),
+                content: self.string(),
+            }),
+            7 => Block::Abbreviation(streamed::Abbreviation {
+                surface_lang: self.string(),
+                surface: self.string(),
+                expansion_lang: self.string(),
+                expansion: self.string(),
+            }),
+            8 => Block::Space(schema::Space {
+                quantity: self.int(),
+                unit: self.unit(),
+            }),
+            _ => Block::Text(streamed::Paragraph {
+                lang: self.string(),
+                content: self.string(),
+            }),
+        }
+    }
+
+    /// Up to `max` random blocks, in no particular order.
+    pub fn blocks(&mut self, max: u64) -> Vec<Block> {
+        (0..self.below(max)).map(|_| self.block()).collect()
+    }
+
+    /// A few surfaces with random image sizes and zones anywhere, also outside the image.
+    pub fn facsimile(&mut self) -> schema::Facsimile {
+        schema::Facsimile {
+            surface: (0..self.below(3))
+                .map(|i| schema::Surface {
+                    xml_id: format!("s{i}"),
+                    graphic: self.option(|r| schema::Graphic {
+                        url: r.string(),
+                        width: r.option(|r| r.next() as u32),
+                        height: r.option(|r| r.next() as u32),
+                    }),
+                    zone: (0..self.below(4))
+                        .map(|j| schema::Zone {
+                            xml_id: format!("z{i}_{j}"),
+                            ulx: self.option(Self::int),
+                            uly: self.option(Self::int),
+                            lrx: self.option(Self::int),
+                            lry: self.option(Self::int),
+                            points: self.option(|r| {
+                                schema::Points(
+                                    (0..r.below(4))
+                                        .map(|_| schema::Point {
+                                            x: r.int(),
+                                            y: r.int(),
+                                        })
+                                        .collect(),
+                                )
+                            }),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// A `@facs` pointing to a surface or zone of `facsimile`, or nowhere.
+    pub fn facs(&mut self, facsimile: &schema::Facsimile) -> Option<String> {
+        let ids = facsimile
+            .surface
+            .iter()
+            .flat_map(|s| std::iter::once(&s.xml_id).chain(s.zone.iter().map(|z| &z.xml_id)))
+            .collect::<Vec<_>>();
+        match self.below(ids.len() as u64 + 2) as usize {
+            0 => None,
+            1 => Some(self.string()),
+            i => Some(format!("#{}", ids[i - 2])),
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Export finished transcriptions as ground truth for training HTR models.
+//!
+//! Every line becomes one [`GroundTruthLine`] with its diplomatic text and its address in the
+//! manuscript. Lines linked to a [`Zone`](normalized::Zone) also carry the image and region, so
+//! they can be written as PAGE-XML. [`RenderRules`] decide how blocks that do not simply
+//! contain the text of the line are rendered.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{normalized, schema::Points, streamed};
+
+/// How to render [`Lacuna`](normalized::Lacuna)s.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LacunaRule {
+    /// Leave the lacuna out of the text
+    Skip,
+    /// Write this character once for each missing character, or once for a lacuna spanning lines
+    /// or columns
+    Mark(char),
+    /// Leave the entire line (or verse) out
+    Drop,
+}
+
+/// How to render [`Uncertain`](normalized::Uncertain) text.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UncertainRule {
+    /// Use the reconstructed text as if it were certain
+    Keep,
+    /// Leave the entire line (or verse) out
+    Drop,
+}
+
+/// How to render [`Abbreviation`](normalized::Abbreviation)s.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AbbreviationRule {
+    /// The abbreviation as written in the manuscript
+    Surface,
+    /// The expansion supplied by the transcriber
+    Expansion,
+}
+
+/// How to render [`Correction`](normalized::Correction)s.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CorrectionRule {
+    /// The first version, i.e. the text before any correction
+    FirstVersion,
+    /// The last version, i.e. the text visible on the page now
+    LastVersion,
+    /// Leave the entire line (or verse) out
+    Drop,
+}
+
+/// The rules deciding how blocks are rendered as plain text.
+///
+/// These are used for the lines of ground truth and for the verses exported to
+/// [Bible software](crate::bible).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RenderRules {
+    pub lacuna: LacunaRule,
+    pub uncertain: UncertainRule,
+    pub abbreviation: AbbreviationRule,
+    pub correction: CorrectionRule,
+}
+/// Only text that is known entirely, as it is visible on the page.
+impl Default for RenderRules {
+    fn default() -> Self {
+        Self {
+            lacuna: LacunaRule::Drop,
+            uncertain: UncertainRule::Keep,
+            abbreviation: AbbreviationRule::Surface,
+            correction: CorrectionRule::LastVersion,
+        }
+    }
+}
+impl RenderRules {
+    /// The text of `blocks`, or `None` if it is dropped by these rules.
+    ///
+    /// Blocks are joined with a single space, as in the [surface form](crate::surface_form).
+    #[must_use]
+    pub fn render(&self, blocks: &[streamed::Block]) -> Option<String> {
+        let mut parts = Vec::<String>::new();
+        for block in blocks {
+            match block {
+                streamed::Block::Text(x) => parts.push(x.content.clone()),
+                streamed::Block::Uncertain(x) => match self.uncertain {
+                    UncertainRule::Keep => parts.push(x.content.clone()),
+                    UncertainRule::Drop => return None,
+                },
+                streamed::Block::Abbreviation(x) => match self.abbreviation {
+                    AbbreviationRule::Surface => parts.push(x.surface.clone()),
+                    AbbreviationRule::Expansion => parts.push(x.expansion.clone()),
+                },
+                streamed::Block::Correction(x) => {
+                    let version = match self.correction {
+                        CorrectionRule::FirstVersion => x.versions.first(),
+                        CorrectionRule::LastVersion => x.versions.last(),
+                        CorrectionRule::Drop => return None,
+                    };
+                    parts.extend(version.map(|v| v.content.clone()));
+                }
+                streamed::Block::Lacuna(x) => match self.lacuna {
+                    LacunaRule::Skip => {}
+                    LacunaRule::Mark(c) => {
+                        let count = match x.unit {
+                            streamed::ExtentUnit::Character => {
+                                usize::try_from(x.n).unwrap_or_default()
+                            }
+                            streamed::ExtentUnit::Line | streamed::ExtentUnit::Column => 1,
+                        };
+                        parts.push(core::iter::repeat_n(c, count).collect());
+                    }
+                    LacunaRule::Drop => return None,
+                },
+                streamed::Block::Anchor(_)
+                | streamed::Block::Space(_)
+                | streamed::Block::Break(_) => {}
+            }
+        }
+        Some(
+            parts
+                .iter()
+                .map(|p| p.trim())
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+
+    /// The text of `line`, or `None` if the line is dropped by these rules or empty.
+    #[must_use]
+    pub fn render_line(&self, line: &normalized::Line) -> Option<String> {
+        // the language is not rendered, and converting inline blocks does not fail
+        let blocks = line
+            .blocks
+            .iter()
+            .map(|b| streamed::Block::try_from((String::new(), b.clone())))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        self.render(&blocks).filter(|text| !text.is_empty())
+    }
+}
+
+/// A single line of ground truth.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct GroundTruthLine {
+    /// The name of the page
+    pub page: String,
+    pub column: i32,
+    pub line: i32,
+    /// The diplomatic text of the line, rendered with [`RenderRules`]
+    pub text: String,
+    /// The `facs` of the line, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facs: Option<String>,
+    /// The url of the image showing the line, if the line is linked to a zone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// The bounding box of the line on the image as `(ulx, uly, lrx, lry)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<(i32, i32, i32, i32)>,
+    /// The outline of the line on the image, if given as a polygon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<Points>,
+}
+
+/// All lines in `ms` that are not dropped by `rules`, in order.
+///
+/// Empty lines (e.g. lines containing only a lacuna that is skipped) are left out.
+#[must_use]
+pub fn lines(ms: &normalized::Manuscript, rules: &RenderRules) -> Vec<GroundTruthLine> {
+    let mut res = vec![];
+    for page in &ms.text.pages {
+        for column in &page.columns {
+            for line in &column.lines {
+                let Some(text) = rules.render_line(line) else {
+                    continue;
+                };
+                let zone = line.facs.as_deref().and_then(|f| ms.facsimile.zone(f));
+                res.push(GroundTruthLine {
+                    page: page.n.clone(),
+                    column: column.n,
+                    line: line.n,
+                    text,
+                    facs: line.facs.clone(),
+                    image: zone
+                        .and_then(|(s, _)| s.graphic.as_ref())
+                        .map(|g| g.url.clone()),
+                    bbox: zone.and_then(|(_, z)| z.bounding_box()),
+                    points: zone.and_then(|(_, z)| z.points.clone()),
+                });
+            }
+        }
+    }
+    res
+}
+
+/// Write `lines` as JSON Lines, one object per line.
+///
+/// # Errors
+/// Only if serialization fails, which does not happen for valid [`GroundTruthLine`]s.
+pub fn to_jsonl(lines: &[GroundTruthLine]) -> Result<String, serde_json::Error> {
+    let mut res = String::new();
+    for line in lines {
+        res.push_str(&serde_json::to_string(line)?);
+        res.push('\n');
+    }
+    Ok(res)
+}
+
+// PAGE-XML
+
+/// The namespace of PAGE-XML 2019.
+pub const PAGE_NAMESPACE: &str = "http://schema.primaresearch.org/PAGE/gts/pagecontent/2019-07-15";
+
+#[derive(Serialize, Debug)]
+struct PcGts {
+    #[serde(rename = "@xmlns")]
+    xmlns: &'static str,
+    #[serde(rename = "Metadata")]
+    metadata: PageMetadata,
+    #[serde(rename = "Page")]
+    page: PagePage,
+}
+
+#[derive(Serialize, Debug)]
+struct PageMetadata {
+    #[serde(rename = "Creator")]
+    creator: &'static str,
+    #[serde(rename = "Created")]
+    created: String,
+    #[serde(rename = "LastChange")]
+    last_change: String,
+}
+
+#[derive(Serialize, Debug)]
+struct PagePage {
+    #[serde(rename = "@imageFilename")]
+    image_filename: String,
+    #[serde(rename = "@imageWidth")]
+    image_width: u32,
+    #[serde(rename = "@imageHeight")]
+    image_height: u32,
+    #[serde(rename = "TextRegion")]
+    text_region: Vec<PageTextRegion>,
+}
+
+#[derive(Serialize, Debug)]
+struct PageTextRegion {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "Coords")]
+    coords: PageCoords,
+    #[serde(rename = "TextLine")]
+    text_line: Vec<PageTextLine>,
+}
+
+#[derive(Serialize, Debug)]
+struct PageCoords {
+    #[serde(rename = "@points")]
+    points: String,
+}
+
+#[derive(Serialize, Debug)]
+struct PageTextLine {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "Coords")]
+    coords: PageCoords,
+    #[serde(rename = "TextEquiv")]
+    text_equiv: PageTextEquiv,
+}
+
+#[derive(Serialize, Debug)]
+struct PageTextEquiv {
+    #[serde(rename = "Unicode")]
+    unicode: String,
+}
+
+/// The outline of a line or region in PAGE-XML: its polygon if given, else its bounding box.
+fn page_points(points: Option<&Points>, bbox: (i32, i32, i32, i32)) -> String {
+    match points {
+        Some(p) => p.to_string(),
+        None => {
+            let (ulx, uly, lrx, lry) = bbox;
+            format!("{ulx},{uly} {lrx},{uly} {lrx},{lry} {ulx},{lry}")
+        }
+    }
+}
+
+/// Make `s` usable as part of an `@id`.
+fn id_part(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Write one PAGE-XML file for each surface in `ms` with at least one line of ground truth.
+///
+/// Returns the `xml:id` of each surface with its file. Each column on the surface becomes a text
+/// region, outlined by the zone of the column or else by the bounding box of its lines. Lines
+/// without a zone and surfaces without an image of known size are left out. `created` is the
+/// timestamp written to the metadata, e.g. `2025-03-01T12:00:00`.
+///
+/// # Errors
+/// Only if serialization fails.
+pub fn to_page_xml(
+    ms: &normalized::Manuscript,
+    rules: &RenderRules,
+    created: &str,
+) -> Result<Vec<(String, String)>, quick_xml::SeError> {
+    let mut res = vec![];
+    for surface in &ms.facsimile.surface {
+        let Some((url, width, height)) = surface
+            .graphic
+            .as_ref()
+            .and_then(|g| Some((g.url.clone(), g.width?, g.height?)))
+        else {
+            continue;
+        };
+        let mut regions = vec![];
+        for page in &ms.text.pages {
+            for column in &page.columns {
+                let text_line = column
+                    .lines
+                    .iter()
+                    .filter_map(|line| {
+                        let (s, zone) = ms.facsimile.zone(line.facs.as_deref()?)?;
+                        if s.xml_id != surface.xml_id {
+                            return None;
+                        }
+                        let bbox = zone.bounding_box()?;
+                        Some((
+                            bbox,
+                            PageTextLine {
+                                id: format!("l_{}_{}_{}", id_part(&page.n), column.n, line.n),
+                                coords: PageCoords {
+                                    points: page_points(zone.points.as_ref(), bbox),
+                                },
+                                text_equiv: PageTextEquiv {
+                                    unicode: rules.render_line(line)?,
+                                },
+                            },
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                if text_line.is_empty() {
+                    continue;
+                }
+                let column_zone = column
+                    .facs
+                    .as_deref()
+                    .and_then(|f| ms.facsimile.zone(f))
+                    .and_then(|(_, z)| Some((z.bounding_box()?, z.points.as_ref())));
+                let coords = match column_zone {
+                    Some((bbox, points)) => page_points(points, bbox),
+                    None => {
+                        let bbox =
+                            text_line
+                                .iter()
+                                .map(|(b, _)| *b)
+                                .fold(text_line[0].0, |acc, b| {
+                                    (
+                                        acc.0.min(b.0),
+                                        acc.1.min(b.1),
+                                        acc.2.max(b.2),
+                                        acc.3.max(b.3),
+                                    )
+                                });
+                        page_points(None, bbox)
+                    }
+                };
+                regions.push(PageTextRegion {
+                    id: format!("r_{}_{}", id_part(&page.n), column.n),
+                    coords: PageCoords { points: coords },
+                    text_line: text_line.into_iter().map(|(_, l)| l).collect(),
+                });
+            }
+        }
+        if regions.is_empty() {
+            continue;
+        }
+        let pc_gts = PcGts {
+            xmlns: PAGE_NAMESPACE,
+            metadata: PageMetadata {
+                creator: "critic-format",
+                created: created.to_string(),
+                last_change: created.to_string(),
+            },
+            page: PagePage {
+                image_filename: url,
+                image_width: width,
+                image_height: height,
+                text_region: regions,
+            },
+        };
+        res.push((
+            surface.xml_id.clone(),
+            quick_xml::se::to_string_with_root("PcGts", &pc_gts)?,
+        ));
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(path: &str) -> normalized::Manuscript {
+        let xml = std::fs::read_to_string(path).unwrap();
+        let tei: crate::schema::Tei = quick_xml::de::from_str(&xml).unwrap();
+        tei.try_into().unwrap()
+    }
+
+    #[test]
+    fn render_rules() {
+        let ms = ms("examples/01_all_elements.xml");
+        let line = &ms.text.pages[0].columns[1].lines[0];
+        assert_eq!(RenderRules::default().render_line(line), None);
+        let rules = RenderRules {
+            lacuna: LacunaRule::Mark('?'),
+            ..Default::default()
+        };
+        assert_eq!(
+            rules.render_line(line).as_deref(),
+            Some(
+                "Hier ein an d erer, wo der Buchstabe nur etwas kaputt ist. ???????????? JHWH sam stuff 2"
+            )
+        );
+        let rules = RenderRules {
+            lacuna: LacunaRule::Skip,
+            uncertain: UncertainRule::Keep,
+            abbreviation: AbbreviationRule::Expansion,
+            correction: CorrectionRule::FirstVersion,
+        };
+        assert_eq!(
+            rules.render_line(line).as_deref(),
+            Some("Hier ein an d erer, wo der Buchstabe nur etwas kaputt ist. Jahwe sam stuff 1")
+        );
+        let rules = RenderRules {
+            uncertain: UncertainRule::Drop,
+            ..rules
+        };
+        assert_eq!(rules.render_line(line), None);
+    }
+
+    #[test]
+    fn jsonl() {
+        let ms = ms("examples/14_facsimile.xml");
+        let rules = RenderRules {
+            lacuna: LacunaRule::Skip,
+            ..Default::default()
+        };
+        let lines = lines(&ms, &rules);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            to_jsonl(&lines).unwrap(),
+            concat!(
+                r##"{"page":"01_r","column":1,"line":1,"text":"text","facs":"#z_01_r_c1_l1","image":"https://example.org/images/01_r.jpg","bbox":[100,200,900,300]}"##,
+                "\n",
+                r##"{"page":"01_r","column":1,"line":3,"text":"text","facs":"#z_01_r_c1_l3","image":"https://example.org/images/01_r.jpg","bbox":[100,480,910,600],"points":"100,500 900,480 910,590 100,600"}"##,
+                "\n",
+                r#"{"page":"01_v","column":1,"line":1,"text":"text"}"#,
+                "\n",
+            )
+        );
+        // the first line contains a lacuna
+        assert_eq!(super::lines(&ms, &RenderRules::default()).len(), 2);
+    }
+
+    /// PAGE-XML written here can be read by the HTR import again.
+    #[test]
+    fn page_xml() {
+        let ms = ms("examples/14_facsimile.xml");
+        let rules = RenderRules {
+            lacuna: LacunaRule::Skip,
+            ..Default::default()
+        };
+        let files = to_page_xml(&ms, &rules, "2025-03-01T12:00:00").unwrap();
+        // the lines of 01_v are not linked to zones
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "s_01_r");
+        let page = crate::htr::from_page_xml(
+            files[0].1.as_bytes(),
+            "01_r",
+            &crate::htr::HtrOptions::new("hbo-Hebr"),
+        )
+        .unwrap();
+        let text = |content: &str| {
+            crate::streamed::Block::Text(crate::streamed::Paragraph {
+                lang: "hbo-Hebr".to_string(),
+                content: content.to_string(),
+            })
+        };
+        assert_eq!(
+            page.blocks,
+            vec![
+                text("text"),
+                crate::streamed::Block::Break(crate::streamed::BreakType::Line),
+                text("text"),
+            ]
+        );
+        assert_eq!(
+            page.surface.zone[0].bounding_box(),
+            Some((100, 200, 900, 2800))
+        );
+        assert_eq!(
+            page.surface.zone[2].points,
+            Some("100,500 900,480 910,590 100,600".parse().unwrap())
+        );
+    }
+}
@@ -0,0 +1,859 @@
+//! Import the output of handwritten text recognition (HTR) from PAGE-XML and ALTO.
+//!
+//! Tools like eScriptorium and Transkribus export one file per image. Text regions (PAGE) or text
+//! blocks (ALTO) become columns, text lines become lines, in document order. The outlines of
+//! regions and lines and the baselines of PAGE-XML lines are kept as
+//! [`Zone`](normalized::Zone)s on a [`Surface`](normalized::Surface) for the image, so that the
+//! editor can show them next to the transcription.
+//!
+//! Blocks never split a word, because the [surface text](crate::surface_form) joins blocks with
+//! whitespace. A word is therefore [`Uncertain`](streamed::Uncertain) as a whole if any of its
+//! glyphs has a low confidence.
+
+use serde::Deserialize;
+
+use crate::{
+    normalized,
+    schema::{self, Probability},
+    streamed,
+};
+
+/// How to turn HTR output into blocks.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HtrOptions {
+    /// The language of all imported text
+    pub default_language: String,
+    /// Mark words with a confidence below this as [`Uncertain`](streamed::Uncertain)
+    ///
+    /// `None` imports all text as [`Paragraph`](streamed::Paragraph)s.
+    pub uncertain_below: Option<f64>,
+    /// The damage agent of uncertain words
+    ///
+    /// This is empty by default, i.e. the agent still has to be given by the transcriber.
+    pub uncertain_agent: String,
+}
+impl HtrOptions {
+    #[must_use]
+    pub fn new(default_language: &str) -> Self {
+        Self {
+            default_language: default_language.to_string(),
+            uncertain_below: None,
+            uncertain_agent: String::new(),
+        }
+    }
+}
+
+/// The problems that can occur when importing HTR output.
+#[derive(Debug)]
+pub enum HtrError {
+    /// The input is not valid PAGE-XML or ALTO
+    DeSer(quick_xml::DeError),
+    /// The coordinates of the element with this id cannot be read
+    InvalidCoordinates(String),
+}
+impl core::fmt::Display for HtrError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::DeSer(e) => {
+                write!(f, "Unable to deserialize HTR output: {e}")
+            }
+            Self::InvalidCoordinates(x) => {
+                write!(f, "The coordinates of \"{x}\" are not valid.")
+            }
+        }
+    }
+}
+impl core::error::Error for HtrError {}
+
+/// A single page imported from HTR output.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HtrPage {
+    /// The transcription, without a leading page break
+    ///
+    /// Together with [`facs`](HtrPage::facs), this can be passed to
+    /// [`Manuscript::replace_page`](streamed::Manuscript::replace_page) as a [`streamed::Page`].
+    pub blocks: Vec<streamed::Block>,
+    /// The image, with one zone for each column, line and baseline
+    pub surface: normalized::Surface,
+    /// The links from the page, its columns and its lines to [`surface`](HtrPage::surface)
+    pub facs: Vec<streamed::FacsLink>,
+}
+
+/// A word recognized by HTR, with the lowest confidence of its glyphs if known.
+struct Word {
+    text: String,
+    conf: Option<f64>,
+}
+
+/// A line recognized by HTR.
+struct Line {
+    id: String,
+    outline: Option<normalized::Points>,
+    baseline: Option<normalized::Points>,
+    words: Vec<Word>,
+}
+
+/// A region of lines recognized by HTR.
+struct Region {
+    id: String,
+    outline: Option<normalized::Points>,
+    lines: Vec<Line>,
+}
+
+/// Make `s` usable as part of an `xml:id`.
+fn id_part(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The rectangle `(x, y, width, height)` of the element `id` as a polygon.
+fn rectangle(
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    id: &str,
+) -> Result<normalized::Points, HtrError> {
+    let (Some(right), Some(bottom)) = (x.checked_add(width), y.checked_add(height)) else {
+        return Err(HtrError::InvalidCoordinates(id.to_string()));
+    };
+    Ok(schema::Points(vec![
+        normalized::Point { x, y },
+        normalized::Point { x: right, y },
+        normalized::Point {
+            x: right,
+            y: bottom,
+        },
+        normalized::Point { x, y: bottom },
+    ]))
+}
+
+fn zone(id: String, outline: Option<normalized::Points>) -> normalized::Zone {
+    normalized::Zone {
+        xml_id: id,
+        ulx: None,
+        uly: None,
+        lrx: None,
+        lry: None,
+        points: outline,
+    }
+}
+
+/// Split `text` into words that all have the confidence `conf`.
+fn words_with_conf(text: &str, conf: Option<f64>) -> Vec<Word> {
+    text.split_whitespace()
+        .map(|w| Word {
+            text: w.to_string(),
+            conf,
+        })
+        .collect()
+}
+
+/// The lower of two optional confidences.
+fn min_conf(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Turn the words of a line into blocks, joining words of equal certainty.
+fn line_blocks(words: Vec<Word>, options: &HtrOptions) -> Vec<streamed::Block> {
+    let mut res = vec![];
+    // the words in the current block and, if uncertain, the lowest confidence
+    let mut current: Option<(String, Option<f64>)> = None;
+    for word in words {
+        let uncertain = match (word.conf, options.uncertain_below) {
+            (Some(conf), Some(threshold)) => conf < threshold,
+            _ => false,
+        };
+        let conf = if uncertain { word.conf } else { None };
+        match current.as_mut() {
+            Some((text, c)) if c.is_some() == uncertain => {
+                text.push(' ');
+                text.push_str(&word.text);
+                *c = min_conf(*c, conf);
+            }
+            _ => {
+                res.extend(current.take().map(|c| block(c, options)));
+                current = Some((word.text, conf));
+            }
+        }
+    }
+    res.extend(current.map(|c| block(c, options)));
+    res
+}
+
+fn block((content, conf): (String, Option<f64>), options: &HtrOptions) -> streamed::Block {
+    match conf {
+        Some(conf) => streamed::Block::Uncertain(streamed::Uncertain {
+            lang: options.default_language.clone(),
+            cert: Probability::new(conf.clamp(0.0, 1.0)).map(streamed::Certainty::Probability),
+            agent: options.uncertain_agent.clone(),
+            content,
+        }),
+        None => streamed::Block::Text(streamed::Paragraph {
+            lang: options.default_language.clone(),
+            content,
+        }),
+    }
+}
+
+/// Build the blocks, surface and links of the page `page_name` from recognized regions.
+fn build_page(
+    page_name: &str,
+    graphic: Option<normalized::Graphic>,
+    regions: Vec<Region>,
+    options: &HtrOptions,
+) -> HtrPage {
+    let prefix = id_part(page_name);
+    let surface_id = format!("s_{prefix}");
+    let mut blocks = vec![];
+    let mut zones = vec![];
+    let mut facs = vec![streamed::FacsLink {
+        page: page_name.to_string(),
+        column: None,
+        line: None,
+        facs: format!("#{surface_id}"),
+    }];
+    let mut link = |column, line, id: &str, outline: Option<_>, baseline: Option<_>| {
+        let zone_id = format!("z_{prefix}_{}", id_part(id));
+        let baseline_id = format!("{zone_id}_baseline");
+        // without an outline, the line is linked to its baseline
+        let linked = if outline.is_none() && baseline.is_some() {
+            &baseline_id
+        } else {
+            zones.push(zone(zone_id.clone(), outline));
+            &zone_id
+        };
+        facs.push(streamed::FacsLink {
+            page: page_name.to_string(),
+            column: Some(column),
+            line,
+            facs: format!("#{linked}"),
+        });
+        if baseline.is_some() {
+            zones.push(zone(baseline_id, baseline));
+        }
+    };
+    // columns without lines are not allowed
+    for (column_idx, region) in regions
+        .into_iter()
+        .filter(|r| !r.lines.is_empty())
+        .enumerate()
+    {
+        let column_nr = i32::try_from(column_idx + 1).unwrap_or(i32::MAX);
+        if column_idx > 0 {
+            blocks.push(streamed::Block::Break(streamed::BreakType::Column));
+        }
+        link(column_nr, None, &region.id, region.outline, None);
+        for (line_idx, line) in region.lines.into_iter().enumerate() {
+            let line_nr = i32::try_from(line_idx + 1).unwrap_or(i32::MAX);
+            if line_idx > 0 {
+                blocks.push(streamed::Block::Break(streamed::BreakType::Line));
+            }
+            link(
+                column_nr,
+                Some(line_nr),
+                &line.id,
+                line.outline,
+                line.baseline,
+            );
+            blocks.extend(line_blocks(line.words, options));
+        }
+    }
+    HtrPage {
+        blocks,
+        surface: normalized::Surface {
+            xml_id: surface_id,
+            graphic,
+            zone: zones,
+        },
+        facs,
+    }
+}
+
+// PAGE-XML
+
+/// The root of a PAGE-XML file.
+#[derive(Deserialize, Debug)]
+struct PcGts {
+    #[serde(rename = "Page")]
+    page: PagePage,
+}
+
+#[derive(Deserialize, Debug)]
+struct PagePage {
+    #[serde(rename = "@imageFilename")]
+    image_filename: String,
+    #[serde(rename = "@imageWidth")]
+    image_width: Option<u32>,
+    #[serde(rename = "@imageHeight")]
+    image_height: Option<u32>,
+    #[serde(rename = "$value", default)]
+    elements: Vec<PageElement>,
+}
+
+/// The children of `<Page>`, of which only text regions are imported.
+#[derive(Deserialize, Debug)]
+enum PageElement {
+    TextRegion(PageTextRegion),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageTextRegion {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "Coords")]
+    coords: Option<PageCoords>,
+    #[serde(rename = "TextLine", default)]
+    text_line: Vec<PageTextLine>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageCoords {
+    #[serde(rename = "@points")]
+    points: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageTextLine {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "Coords")]
+    coords: Option<PageCoords>,
+    #[serde(rename = "Baseline")]
+    baseline: Option<PageCoords>,
+    #[serde(rename = "Word", default)]
+    word: Vec<PageWord>,
+    #[serde(rename = "TextEquiv")]
+    text_equiv: Option<PageTextEquiv>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageWord {
+    #[serde(rename = "Glyph", default)]
+    glyph: Vec<PageGlyph>,
+    #[serde(rename = "TextEquiv")]
+    text_equiv: Option<PageTextEquiv>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageGlyph {
+    #[serde(rename = "TextEquiv")]
+    text_equiv: Option<PageTextEquiv>,
+}
+
+#[derive(Deserialize, Debug)]
+struct PageTextEquiv {
+    #[serde(rename = "@conf")]
+    conf: Option<f64>,
+    #[serde(rename = "Unicode", default)]
+    unicode: String,
+}
+
+/// Parse PAGE-XML `@points` of the element `id`.
+fn page_points(
+    coords: Option<PageCoords>,
+    id: &str,
+) -> Result<Option<normalized::Points>, HtrError> {
+    coords
+        .map(|c| {
+            c.points
+                .parse()
+                .map_err(|_| HtrError::InvalidCoordinates(id.to_string()))
+        })
+        .transpose()
+}
+
+impl TryFrom<PageTextLine> for Line {
+    type Error = HtrError;
+
+    fn try_from(value: PageTextLine) -> Result<Self, Self::Error> {
+        let words = if value.word.is_empty() {
+            value
+                .text_equiv
+                .map(|t| words_with_conf(&t.unicode, t.conf))
+                .unwrap_or_default()
+        } else {
+            value
+                .word
+                .into_iter()
+                .filter_map(|w| {
+                    let text = w.text_equiv.as_ref()?.unicode.trim().to_string();
+                    let conf = w
+                        .glyph
+                        .iter()
+                        .filter_map(|g| g.text_equiv.as_ref()?.conf)
+                        .fold(w.text_equiv.as_ref()?.conf, |acc, c| min_conf(acc, Some(c)));
+                    (!text.is_empty()).then_some(Word { text, conf })
+                })
+                .collect()
+        };
+        Ok(Self {
+            outline: page_points(value.coords, &value.id)?,
+            baseline: page_points(value.baseline, &value.id)?,
+            id: value.id,
+            words,
+        })
+    }
+}
+
+/// Import the PAGE-XML in `buf_reader` as the page `page_name`.
+///
+/// The reading order given in the file is ignored, regions and lines are read in document order.
+///
+/// # Errors
+/// If the input is not PAGE-XML or contains invalid coordinates.
+pub fn from_page_xml(
+    buf_reader: impl std::io::BufRead,
+    page_name: &str,
+    options: &HtrOptions,
+) -> Result<HtrPage, HtrError> {
+    let pc_gts: PcGts = quick_xml::de::from_reader(buf_reader).map_err(HtrError::DeSer)?;
+    let page = pc_gts.page;
+    let regions = page
+        .elements
+        .into_iter()
+        .filter_map(|e| match e {
+            PageElement::TextRegion(r) => Some(r),
+            PageElement::Other => None,
+        })
+        .map(|r| {
+            Ok(Region {
+                outline: page_points(r.coords, &r.id)?,
+                lines: r
+                    .text_line
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+                id: r.id,
+            })
+        })
+        .collect::<Result<_, HtrError>>()?;
+    let graphic = normalized::Graphic {
+        url: page.image_filename,
+        width: page.image_width,
+        height: page.image_height,
+    };
+    Ok(build_page(page_name, Some(graphic), regions, options))
+}
+
+// ALTO
+
+/// The root of an ALTO file.
+#[derive(Deserialize, Debug)]
+struct Alto {
+    #[serde(rename = "Description")]
+    description: Option<AltoDescription>,
+    #[serde(rename = "Layout")]
+    layout: AltoLayout,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoDescription {
+    #[serde(rename = "sourceImageInformation")]
+    source_image_information: Option<AltoSourceImageInformation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoSourceImageInformation {
+    #[serde(rename = "fileName")]
+    file_name: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoLayout {
+    #[serde(rename = "Page")]
+    page: AltoPage,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoPage {
+    #[serde(rename = "@WIDTH")]
+    width: Option<f64>,
+    #[serde(rename = "@HEIGHT")]
+    height: Option<f64>,
+    #[serde(rename = "$value", default)]
+    spaces: Vec<AltoPageSpace>,
+}
+
+/// The print spaces and margins of a page, all of which can hold text blocks.
+#[derive(Deserialize, Debug)]
+struct AltoPageSpace {
+    #[serde(rename = "$value", default)]
+    elements: Vec<AltoBlockElement>,
+}
+
+#[derive(Deserialize, Debug)]
+enum AltoBlockElement {
+    TextBlock(AltoTextBlock),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoTextBlock {
+    #[serde(rename = "@ID")]
+    id: String,
+    #[serde(rename = "@HPOS")]
+    hpos: Option<f64>,
+    #[serde(rename = "@VPOS")]
+    vpos: Option<f64>,
+    #[serde(rename = "@WIDTH")]
+    width: Option<f64>,
+    #[serde(rename = "@HEIGHT")]
+    height: Option<f64>,
+    #[serde(rename = "Shape")]
+    shape: Option<AltoShape>,
+    #[serde(rename = "TextLine", default)]
+    text_line: Vec<AltoTextLine>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoShape {
+    #[serde(rename = "Polygon")]
+    polygon: Option<AltoPolygon>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoPolygon {
+    #[serde(rename = "@POINTS")]
+    points: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoTextLine {
+    #[serde(rename = "@ID")]
+    id: String,
+    #[serde(rename = "@HPOS")]
+    hpos: Option<f64>,
+    #[serde(rename = "@VPOS")]
+    vpos: Option<f64>,
+    #[serde(rename = "@WIDTH")]
+    width: Option<f64>,
+    #[serde(rename = "@HEIGHT")]
+    height: Option<f64>,
+    #[serde(rename = "Shape")]
+    shape: Option<AltoShape>,
+    #[serde(rename = "$value", default)]
+    elements: Vec<AltoLineElement>,
+}
+
+#[derive(Deserialize, Debug)]
+enum AltoLineElement {
+    String(AltoString),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoString {
+    #[serde(rename = "@CONTENT")]
+    content: String,
+    #[serde(rename = "@WC")]
+    wc: Option<f64>,
+    #[serde(rename = "Glyph", default)]
+    glyph: Vec<AltoGlyph>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AltoGlyph {
+    #[serde(rename = "@GC")]
+    gc: Option<f64>,
+}
+
+/// Round an ALTO measurement to whole pixels.
+#[allow(clippy::cast_possible_truncation)]
+fn pixels(value: f64) -> i32 {
+    value.round() as i32
+}
+
+/// The outline of an ALTO block or line: its polygon if given, else its rectangle
+/// `(HPOS, VPOS, WIDTH, HEIGHT)`.
+///
+/// ALTO allows both `x,y x,y` and `x y x y` in `@POINTS`.
+fn alto_outline(
+    rect: (Option<f64>, Option<f64>, Option<f64>, Option<f64>),
+    shape: Option<AltoShape>,
+    id: &str,
+) -> Result<Option<normalized::Points>, HtrError> {
+    if let Some(polygon) = shape.and_then(|s| s.polygon) {
+        let numbers = polygon
+            .points
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<f64>().map(pixels))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| HtrError::InvalidCoordinates(id.to_string()))?;
+        if numbers.len() % 2 != 0 {
+            return Err(HtrError::InvalidCoordinates(id.to_string()));
+        }
+        return Ok(Some(schema::Points(
+            numbers
+                .chunks_exact(2)
+                .map(|p| normalized::Point { x: p[0], y: p[1] })
+                .collect(),
+        )));
+    }
+    match rect {
+        (Some(x), Some(y), Some(w), Some(h)) => {
+            rectangle(pixels(x), pixels(y), pixels(w), pixels(h), id).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+impl TryFrom<AltoTextLine> for Line {
+    type Error = HtrError;
+
+    fn try_from(value: AltoTextLine) -> Result<Self, Self::Error> {
+        let words = value
+            .elements
+            .into_iter()
+            .filter_map(|e| match e {
+                AltoLineElement::String(s) => Some(Word {
+                    conf: s
+                        .glyph
+                        .iter()
+                        .filter_map(|g| g.gc)
+                        .fold(s.wc, |acc, c| min_conf(acc, Some(c))),
+                    text: s.content,
+                }),
+                AltoLineElement::Other => None,
+            })
+            .collect();
+        Ok(Self {
+            outline: alto_outline(
+                (value.hpos, value.vpos, value.width, value.height),
+                value.shape,
+                &value.id,
+            )?,
+            baseline: None,
+            id: value.id,
+            words,
+        })
+    }
+}
+
+/// Import the ALTO in `buf_reader` as the page `page_name`.
+///
+/// Text blocks from all print spaces and margins are read in document order. Hyphens at the end of
+/// lines are dropped, the hyphenated word is kept as is.
+///
+/// # Errors
+/// If the input is not ALTO or contains invalid coordinates.
+pub fn from_alto(
+    buf_reader: impl std::io::BufRead,
+    page_name: &str,
+    options: &HtrOptions,
+) -> Result<HtrPage, HtrError> {
+    let alto: Alto = quick_xml::de::from_reader(buf_reader).map_err(HtrError::DeSer)?;
+    let page = alto.layout.page;
+    let regions = page
+        .spaces
+        .into_iter()
+        .flat_map(|s| s.elements)
+        .filter_map(|e| match e {
+            AltoBlockElement::TextBlock(b) => Some(b),
+            AltoBlockElement::Other => None,
+        })
+        .map(|b| {
+            Ok(Region {
+                outline: alto_outline((b.hpos, b.vpos, b.width, b.height), b.shape, &b.id)?,
+                lines: b
+                    .text_line
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+                id: b.id,
+            })
+        })
+        .collect::<Result<_, HtrError>>()?;
+    let graphic = alto
+        .description
+        .and_then(|d| d.source_image_information)
+        .and_then(|s| s.file_name)
+        .map(|url| normalized::Graphic {
+            url,
+            width: page.width.and_then(|w| u32::try_from(pixels(w)).ok()),
+            height: page.height.and_then(|h| u32::try_from(pixels(h)).ok()),
+        });
+    Ok(build_page(page_name, graphic, regions, options))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn options() -> HtrOptions {
+        HtrOptions {
+            uncertain_below: Some(0.6),
+            ..HtrOptions::new("hbo-Hebr")
+        }
+    }
+
+    fn text(content: &str) -> streamed::Block {
+        streamed::Block::Text(streamed::Paragraph {
+            lang: "hbo-Hebr".to_string(),
+            content: content.to_string(),
+        })
+    }
+
+    fn uncertain(content: &str, conf: f64) -> streamed::Block {
+        streamed::Block::Uncertain(streamed::Uncertain {
+            lang: "hbo-Hebr".to_string(),
+            cert: Probability::new(conf).map(streamed::Certainty::Probability),
+            agent: String::new(),
+            content: content.to_string(),
+        })
+    }
+
+    /// Both fixtures contain the same page.
+    fn expected_blocks() -> Vec<streamed::Block> {
+        vec![
+            text("ויאמר יהוה"),
+            streamed::Block::Break(streamed::BreakType::Line),
+            text("אל"),
+            uncertain("משה לאמר", 0.41),
+            streamed::Block::Break(streamed::BreakType::Column),
+            text("דבר"),
+        ]
+    }
+
+    #[test]
+    fn page_xml() {
+        let xml = std::fs::File::open("examples/htr_page.xml").unwrap();
+        let page = from_page_xml(std::io::BufReader::new(xml), "01_r", &options()).unwrap();
+        assert_eq!(page.blocks, expected_blocks());
+        assert_eq!(page.surface.xml_id, "s_01_r");
+        assert_eq!(
+            page.surface.graphic,
+            Some(normalized::Graphic {
+                url: "01_r.jpg".to_string(),
+                width: Some(2000),
+                height: Some(3000),
+            })
+        );
+        // the empty region r3 is dropped, baselines are kept as zones of their own
+        assert_eq!(
+            page.surface
+                .zone
+                .iter()
+                .map(|z| z.xml_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "z_01_r_r1",
+                "z_01_r_r1l1",
+                "z_01_r_r1l1_baseline",
+                "z_01_r_r1l2_baseline",
+                "z_01_r_r2",
+                "z_01_r_r2l1",
+            ]
+        );
+        assert_eq!(
+            page.surface.zone[2].points,
+            Some("100,290 900,290".parse().unwrap())
+        );
+        assert_eq!(page.facs.len(), 6);
+        let line_1 = page
+            .facs
+            .iter()
+            .find(|f| f.column == Some(1) && f.line == Some(1))
+            .unwrap();
+        assert_eq!(line_1.facs, "#z_01_r_r1l1");
+        // without coords, the line is linked to its baseline
+        let line_2 = page
+            .facs
+            .iter()
+            .find(|f| f.column == Some(1) && f.line == Some(2))
+            .unwrap();
+        assert_eq!(line_2.facs, "#z_01_r_r1l2_baseline");
+
+        let certain = from_page_xml(
+            std::fs::read("examples/htr_page.xml").unwrap().as_slice(),
+            "01_r",
+            &HtrOptions::new("hbo-Hebr"),
+        )
+        .unwrap();
+        assert_eq!(
+            certain.blocks[2..4],
+            [text("אל משה לאמר"), expected_blocks()[4].clone()]
+        );
+    }
+
+    #[test]
+    fn alto() {
+        let xml = std::fs::File::open("examples/htr_alto.xml").unwrap();
+        let page = from_alto(std::io::BufReader::new(xml), "01_r", &options()).unwrap();
+        assert_eq!(page.blocks, expected_blocks());
+        assert_eq!(
+            page.surface.graphic,
+            Some(normalized::Graphic {
+                url: "01_r.jpg".to_string(),
+                width: Some(2000),
+                height: Some(3000),
+            })
+        );
+        assert_eq!(
+            page.surface
+                .zone
+                .iter()
+                .map(|z| (z.xml_id.as_str(), z.bounding_box()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("z_01_r_b1", Some((100, 200, 900, 2800))),
+                ("z_01_r_b1l1", Some((100, 200, 900, 300))),
+                ("z_01_r_b1l2", Some((100, 300, 900, 400))),
+                ("z_01_r_b2", Some((1000, 200, 1900, 2800))),
+                ("z_01_r_b2l1", Some((1000, 200, 1900, 300))),
+            ]
+        );
+    }
+
+    #[test]
+    fn overflowing_rectangle() {
+        let xml = std::fs::read_to_string("examples/htr_alto.xml")
+            .unwrap()
+            .replace(r#"ID="b1l1" HPOS="100""#, r#"ID="b1l1" HPOS="2147483000""#);
+        assert!(matches!(
+            from_alto(xml.as_bytes(), "01_r", &options()),
+            Err(HtrError::InvalidCoordinates(id)) if id == "b1l1"
+        ));
+    }
+
+    #[test]
+    fn into_manuscript() {
+        let xml = std::fs::File::open("examples/07_multi-page.xml").unwrap();
+        let mut ms = crate::from_xml(std::io::BufReader::new(xml)).unwrap();
+        let xml = std::fs::File::open("examples/htr_alto.xml").unwrap();
+        let page = from_alto(std::io::BufReader::new(xml), "35_r", &options()).unwrap();
+        let change = streamed::Change {
+            who: Some("htr".to_string()),
+            when: None,
+            status: Some(streamed::PageStatus::Draft),
+            page: None,
+            description: None,
+        };
+        let new_page = streamed::Page {
+            name: "35_r".to_string(),
+            status: Some(streamed::PageStatus::Draft),
+            blocks: page.blocks,
+            facs: page.facs,
+        };
+        ms.replace_page(new_page, change).unwrap();
+        ms.facsimile.surface.push(page.surface);
+        let xml_again = crate::to_xml(ms.clone()).unwrap();
+        let ms_again = crate::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+    }
+}
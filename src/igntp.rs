@@ -0,0 +1,586 @@
+//! Import of New Testament transcriptions in the TEI flavour of the
+//! [IGNTP](http://www.igntp.org) and the NTVMR.
+//!
+//! These transcriptions mark the layout with milestones (`<pb/>`, `<cb/>`, `<lb/>`, see
+//! [`milestone`](crate::milestone)) and the text with words (`<w>`) in verse units
+//! (`<ab n="B04K1V1">`). They are mapped onto blocks as follows:
+//!
+//! - every verse unit starts with a verse anchor in the scheme of the [`IgntpOptions`]
+//! - `<unclear>` becomes [`Uncertain`](streamed::Uncertain)
+//! - `<gap>` becomes a [`Lacuna`](streamed::Lacuna), and so does `<supplied>`, counting the
+//!   characters supplied by the editor
+//! - `<app>` becomes a [`Correction`](streamed::Correction) with one version per `<rdg>`, keeping
+//!   its `@hand` (`firsthand`, `corrector`, …)
+//! - `<space>` becomes [`Space`](streamed::Space)
+//!
+//! Notes and forme work are dropped. All other elements are read for their text and reported in
+//! [`IgntpImport::unmapped`] unless they only carry formatting (`<hi>`, `<abbr>`, `<pc>`, …).
+
+use quick_xml::events::BytesStart;
+
+use crate::{
+    milestone::{
+        ImportError, ImportState, Importer, attribute, extent_quantity, extent_unit, import, padded,
+    },
+    streamed,
+};
+
+/// The OSIS abbreviations of the books of the New Testament, in the order of the IGNTP book
+/// numbers (`B01` to `B27`).
+pub const NT_BOOKS: [&str; 27] = [
+    "Matt", "Mark", "Luke", "John", "Acts", "Rom", "1Cor", "2Cor", "Gal", "Eph", "Phil", "Col",
+    "1Thess", "2Thess", "1Tim", "2Tim", "Titus", "Phlm", "Heb", "Jas", "1Pet", "2Pet", "1John",
+    "2John", "3John", "Jude", "Rev",
+];
+
+/// Elements that only structure or format the text, and are read for their text.
+const TRANSPARENT_ELEMENTS: [&[u8]; 13] = [
+    b"TEI", b"text", b"body", b"div", b"ab", b"w", b"hi", b"abbr", b"num", b"pc", b"seg", b"lem",
+    b"foreign",
+];
+
+/// How to import IGNTP transcriptions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IgntpOptions {
+    /// The language of text without `@xml:lang`
+    pub default_language: String,
+    /// The long form of the versification scheme of the verse anchors
+    pub scheme: String,
+    /// The shorthand of the versification scheme of the verse anchors
+    pub scheme_shorthand: String,
+}
+impl IgntpOptions {
+    /// Anchors in the `Common` scheme, text in `default_language`.
+    #[must_use]
+    pub fn new(default_language: &str) -> Self {
+        Self {
+            default_language: default_language.to_string(),
+            scheme: "Common".to_string(),
+            scheme_shorthand: "C".to_string(),
+        }
+    }
+}
+
+/// The problems that can occur when importing IGNTP transcriptions.
+#[derive(Debug)]
+pub enum IgntpError {
+    /// The input is not well-formed XML
+    Xml(quick_xml::Error),
+    /// There is text, or a `<cb/>` or `<lb/>`, before the first `<pb/>`, so it is not on any page
+    TextBeforeFirstPage,
+    /// There is a `<pb/>`, `<cb/>` or `<lb/>` inside this element, which cannot be split into one
+    /// block before and one after the break
+    BreakInside(String),
+    /// The names of two pages are not in lexical order, even after
+    /// [`page_name`](crate::milestone::page_name)
+    ///
+    /// last page - this page
+    PagesNotOrdered(String, String),
+}
+impl core::fmt::Display for IgntpError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Xml(e) => {
+                write!(f, "Unable to read the IGNTP transcription: {e}")
+            }
+            Self::TextBeforeFirstPage => {
+                write!(
+                    f,
+                    "There is text or a break before the first <pb/>. Every text must start with a page break."
+                )
+            }
+            Self::BreakInside(x) => {
+                write!(
+                    f,
+                    "There is a page, column or line break inside <{x}>. Move it before or after the element."
+                )
+            }
+            Self::PagesNotOrdered(last, this) => {
+                write!(
+                    f,
+                    "The pages {last} and {this} are not in lexical order. Rename them so that they sort in reading order."
+                )
+            }
+        }
+    }
+}
+impl core::error::Error for IgntpError {}
+impl From<ImportError> for IgntpError {
+    fn from(value: ImportError) -> Self {
+        match value {
+            ImportError::BeforeFirstPage => Self::TextBeforeFirstPage,
+            ImportError::PagesNotOrdered(last, this) => Self::PagesNotOrdered(last, this),
+        }
+    }
+}
+
+/// An element that could not be mapped onto blocks.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Unmapped {
+    /// The element, with the attribute that prevented mapping it if any (`gap unit="quire"`)
+    pub element: String,
+    /// The page it is on
+    pub page: Option<String>,
+}
+
+/// The result of importing an IGNTP transcription.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IgntpImport {
+    pub blocks: Vec<streamed::Block>,
+    /// The elements that were not mapped, in order
+    pub unmapped: Vec<Unmapped>,
+}
+
+/// The book, chapter and verse of an IGNTP verse unit (`B04K1V1`).
+fn parse_verse(n: &str) -> Option<(&'static str, u32, u32)> {
+    let (book, rest) = n.strip_prefix('B')?.split_once('K')?;
+    let (chapter, verse) = rest.split_once('V')?;
+    let book = NT_BOOKS.get(book.parse::<usize>().ok()?.checked_sub(1)?)?;
+    Some((book, chapter.parse().ok()?, verse.parse().ok()?))
+}
+
+/// Append text to `text`, starting a new word with a space if `new_word` is set.
+fn append(text: &mut String, content: &str, new_word: &mut bool) {
+    if content.trim().is_empty() {
+        return;
+    }
+    if core::mem::take(new_word) && !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(content.trim());
+}
+
+/// An element whose text is collected into a single block.
+///
+/// An `<unclear>` or `<supplied>` spanning a break becomes one block before and one after the
+/// break, an `<app>` must not contain breaks.
+enum Capture {
+    /// `<unclear>`
+    Uncertain(streamed::Uncertain),
+    /// `<supplied>`, with its text
+    Supplied(String, String),
+    /// `<app>`
+    Correction(streamed::Correction),
+}
+
+/// The block collected by `capture`.
+///
+/// An empty `<unclear>` or `<supplied>`, e.g. the part after a break at its end, is dropped.
+fn finish(capture: Capture) -> Option<streamed::Block> {
+    match capture {
+        Capture::Uncertain(x) => (!x.content.is_empty()).then_some(streamed::Block::Uncertain(x)),
+        Capture::Supplied(reason, text) => {
+            let n = text.chars().filter(|c| !c.is_whitespace()).count();
+            (n > 0).then(|| {
+                streamed::Block::Lacuna(streamed::Lacuna {
+                    reason,
+                    unit: streamed::ExtentUnit::Character,
+                    n: i32::try_from(n).unwrap_or(i32::MAX),
+                    cert: None,
+                })
+            })
+        }
+        Capture::Correction(x) => Some(streamed::Block::Correction(x)),
+    }
+}
+
+/// The state while reading an IGNTP transcription.
+struct IgntpImporter<'a> {
+    options: &'a IgntpOptions,
+    state: ImportState,
+    unmapped: Vec<Unmapped>,
+    /// The element currently collecting text, and its depth
+    capture: Option<(Capture, usize)>,
+    /// The next text starts a new word
+    new_word: bool,
+}
+impl IgntpImporter<'_> {
+    fn report(&mut self, element: String) {
+        self.unmapped.push(Unmapped {
+            element,
+            page: self.state.page.clone(),
+        });
+    }
+
+    /// Handle the milestone `e` inside the current capture.
+    ///
+    /// An uncertain or supplied block is split at the break, a correction is rejected.
+    fn milestone_in_capture(&mut self, e: &BytesStart, name: &[u8]) -> Result<(), IgntpError> {
+        let (rest, depth) = match self.capture.take() {
+            Some((Capture::Uncertain(x), depth)) => {
+                let rest = Capture::Uncertain(streamed::Uncertain {
+                    content: String::new(),
+                    ..x.clone()
+                });
+                if let Some(block) = finish(Capture::Uncertain(x)) {
+                    self.state.push(block)?;
+                }
+                (rest, depth)
+            }
+            Some((Capture::Supplied(reason, text), depth)) => {
+                let rest = Capture::Supplied(reason.clone(), String::new());
+                if let Some(block) = finish(Capture::Supplied(reason, text)) {
+                    self.state.push(block)?;
+                }
+                (rest, depth)
+            }
+            _ => return Err(IgntpError::BreakInside(self.state.innermost(&[b"app"]))),
+        };
+        self.state.milestone(e, name)?;
+        self.capture = Some((rest, depth));
+        self.new_word = true;
+        Ok(())
+    }
+}
+impl Importer for IgntpImporter<'_> {
+    type Error = IgntpError;
+
+    fn state(&mut self) -> &mut ImportState {
+        &mut self.state
+    }
+
+    fn xml_error(e: quick_xml::Error) -> Self::Error {
+        IgntpError::Xml(e)
+    }
+
+    fn push_text(&mut self, content: &str) -> Result<(), IgntpError> {
+        if content.trim().is_empty() {
+            return Ok(());
+        }
+        if let Some((capture, _)) = self.capture.as_mut() {
+            let text = match capture {
+                Capture::Uncertain(x) => &mut x.content,
+                Capture::Supplied(_, x) => x,
+                Capture::Correction(x) => match x.versions.last_mut() {
+                    Some(v) => &mut v.content,
+                    // text directly in <app> is not part of any reading
+                    None => return Ok(()),
+                },
+            };
+            append(text, content, &mut self.new_word);
+            return Ok(());
+        }
+        append(self.state.text()?, content, &mut self.new_word);
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart) -> Result<(), IgntpError> {
+        let Some(name) = self.state.enter(e) else {
+            return Ok(());
+        };
+        let lang = self.state.language();
+        if self.capture.is_some() && matches!(name.as_slice(), b"pb" | b"cb" | b"lb") {
+            return self.milestone_in_capture(e, &name);
+        }
+        if let Some((capture, _)) = self.capture.as_mut() {
+            match (capture, name.as_slice()) {
+                (Capture::Correction(x), b"rdg") => {
+                    x.versions.push(streamed::Version {
+                        lang,
+                        hand: attribute(e, "hand"),
+                        content: String::new(),
+                    });
+                    self.new_word = false;
+                }
+                (_, b"w") => self.new_word = true,
+                (_, n) if TRANSPARENT_ELEMENTS.contains(&n) => {}
+                // nested markup is flattened into the text of the capturing element
+                (_, n) => {
+                    let element = String::from_utf8_lossy(n).into_owned();
+                    self.report(element);
+                }
+            }
+            return Ok(());
+        }
+        let depth = self.state.open.len();
+        match name.as_slice() {
+            b"pb" | b"cb" | b"lb" => self.state.milestone(e, &name)?,
+            b"ab" => {
+                let n = attribute(e, "n").unwrap_or_default();
+                // the continuation of a verse split by other markup
+                if matches!(attribute(e, "part").as_deref(), Some("M" | "F")) {
+                    return Ok(());
+                }
+                match parse_verse(&n) {
+                    Some((book, chapter, verse)) => {
+                        self.state.push(streamed::Block::Anchor(streamed::Anchor {
+                            anchor_id: format!(
+                                "A_V_{}_{book}-{chapter}-{verse}",
+                                self.options.scheme_shorthand
+                            ),
+                            anchor_type: self.options.scheme.clone(),
+                        }))?;
+                    }
+                    None => self.report(format!("ab n=\"{n}\"")),
+                }
+            }
+            b"w" => self.new_word = true,
+            b"gap" => {
+                let unit = match attribute(e, "unit").as_deref() {
+                    None | Some("char" | "chars" | "character") => streamed::ExtentUnit::Character,
+                    Some("line" | "lines") => streamed::ExtentUnit::Line,
+                    Some("column" | "columns") => streamed::ExtentUnit::Column,
+                    Some(other) => {
+                        let element = format!("gap unit=\"{other}\"");
+                        self.report(element);
+                        return Ok(());
+                    }
+                };
+                self.state.push(streamed::Block::Lacuna(streamed::Lacuna {
+                    reason: attribute(e, "reason").unwrap_or_default(),
+                    unit,
+                    n: extent_quantity(e),
+                    cert: attribute(e, "cert").and_then(|c| c.parse().ok()),
+                }))?;
+                self.new_word = true;
+            }
+            b"space" => {
+                self.state.push(streamed::Block::Space(streamed::Space {
+                    quantity: extent_quantity(e),
+                    unit: extent_unit(e),
+                }))?;
+                self.new_word = true;
+            }
+            b"unclear" => {
+                self.capture = Some((
+                    Capture::Uncertain(streamed::Uncertain {
+                        lang,
+                        cert: attribute(e, "cert").and_then(|c| c.parse().ok()),
+                        agent: attribute(e, "reason").unwrap_or_default(),
+                        content: String::new(),
+                    }),
+                    depth,
+                ));
+            }
+            b"supplied" => {
+                self.capture = Some((
+                    Capture::Supplied(attribute(e, "reason").unwrap_or_default(), String::new()),
+                    depth,
+                ));
+            }
+            b"app" => {
+                self.capture = Some((
+                    Capture::Correction(streamed::Correction { versions: vec![] }),
+                    depth,
+                ));
+            }
+            n if TRANSPARENT_ELEMENTS.contains(&n) => {}
+            n => {
+                let element = String::from_utf8_lossy(n).into_owned();
+                self.report(element);
+            }
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), IgntpError> {
+        let Some((name, depth)) = self.state.leave() else {
+            return Ok(());
+        };
+        if name == b"w" {
+            self.new_word = true;
+        }
+        if self.capture.as_ref().is_some_and(|(_, d)| *d == depth)
+            && let Some((capture, _)) = self.capture.take()
+            && let Some(block) = finish(capture)
+        {
+            self.state.push(block)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read an IGNTP transcription from `buf_reader` and convert it to blocks.
+///
+/// The text supplied by the editor in `<supplied>` is not kept. `@facs` and the numbering of
+/// columns and lines in `@n` are not converted. A `<pb/>` without `@n` is named by its position
+/// (`0001`, `0002`, …). If the page names are not in lexical order, their leading numbers are
+/// padded with zeros, so `1r` … `10v` become `01r` … `10v`. An `<unclear>` or `<supplied>` spanning
+/// a break is split into one block before and one after it.
+///
+/// # Errors
+/// If the input is not well-formed, has text or breaks before the first `<pb/>`, has a break
+/// inside an `<app>`, or has page names that are not in order even when padded.
+pub fn from_igntp(
+    buf_reader: impl std::io::BufRead,
+    options: &IgntpOptions,
+) -> Result<IgntpImport, IgntpError> {
+    let mut importer = IgntpImporter {
+        options,
+        state: ImportState::new(&options.default_language),
+        unmapped: vec![],
+        capture: None,
+        new_word: false,
+    };
+    import(buf_reader, &mut importer)?;
+    if let Some(width) = importer.state.sort_pages()? {
+        for page in importer.unmapped.iter_mut().filter_map(|u| u.page.as_mut()) {
+            *page = padded(page, width);
+        }
+    }
+    Ok(IgntpImport {
+        blocks: importer.state.blocks,
+        unmapped: importer.unmapped,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::streamed::{Block, BreakType};
+
+    fn text(content: &str) -> Block {
+        Block::Text(streamed::Paragraph {
+            lang: "grc".to_string(),
+            content: content.to_string(),
+        })
+    }
+
+    fn anchor(id: &str) -> Block {
+        Block::Anchor(streamed::Anchor {
+            anchor_id: id.to_string(),
+            anchor_type: "Common".to_string(),
+        })
+    }
+
+    #[test]
+    fn verse_units() {
+        assert_eq!(parse_verse("B04K1V1"), Some(("John", 1, 1)));
+        assert_eq!(parse_verse("B27K22V21"), Some(("Rev", 22, 21)));
+        assert_eq!(parse_verse("B28K1V1"), None);
+        assert_eq!(parse_verse("B04K1incipit"), None);
+    }
+
+    #[test]
+    fn breaks_inside_captures() {
+        let xml = r#"<body><pb n="1r"/><w>a<supplied reason="lacuna">bc<lb/>d</supplied></w> <w><unclear reason="faded">e<lb/></unclear></w></body>"#;
+        let import = from_igntp(xml.as_bytes(), &IgntpOptions::new("grc")).unwrap();
+        let supplied = |n| {
+            Block::Lacuna(streamed::Lacuna {
+                reason: "lacuna".to_string(),
+                unit: streamed::ExtentUnit::Character,
+                n,
+                cert: None,
+            })
+        };
+        assert_eq!(
+            import.blocks,
+            vec![
+                Block::Break(BreakType::Page("1r".to_string(), None)),
+                text("a"),
+                supplied(2),
+                Block::Break(BreakType::Line),
+                supplied(1),
+                Block::Uncertain(streamed::Uncertain {
+                    lang: "grc".to_string(),
+                    cert: None,
+                    agent: "faded".to_string(),
+                    content: "e".to_string(),
+                }),
+                Block::Break(BreakType::Line),
+            ]
+        );
+        let xml = r#"<body><pb n="1r"/><app><rdg hand="firsthand">a<lb/>b</rdg></app></body>"#;
+        assert!(matches!(
+            from_igntp(xml.as_bytes(), &IgntpOptions::new("grc")),
+            Err(IgntpError::BreakInside(x)) if x == "app"
+        ));
+    }
+
+    /// Folios `1r` to `10v` are renamed to sort in reading order.
+    #[test]
+    fn page_order() {
+        let xml = (1..=10)
+            .flat_map(|f| [format!("{f}r"), format!("{f}v")])
+            .fold(String::from("<body>"), |xml, n| {
+                format!("{xml}<pb n=\"{n}\"/><w>a</w><ex>b</ex>")
+            })
+            + "</body>";
+        let import = from_igntp(xml.as_bytes(), &IgntpOptions::new("grc")).unwrap();
+        let names = import
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                Block::Break(BreakType::Page(n, _)) => Some(n.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names[..3], ["01r", "01v", "02r"]);
+        assert_eq!(names[17..], ["09v", "10r", "10v"]);
+        assert_eq!(import.unmapped[0].page.as_deref(), Some("01r"));
+
+        let template = std::fs::File::open("examples/02_lines_consistent.xml").unwrap();
+        let mut ms = crate::from_xml(std::io::BufReader::new(template)).unwrap();
+        ms.content = import.blocks;
+        let xml = crate::to_xml(ms.clone()).unwrap();
+        assert_eq!(crate::from_xml(xml.as_bytes()).unwrap(), ms);
+    }
+
+    #[test]
+    fn sample() {
+        let xml = std::fs::File::open("examples/igntp_sample.xml").unwrap();
+        let import = from_igntp(std::io::BufReader::new(xml), &IgntpOptions::new("en")).unwrap();
+        assert_eq!(
+            import.blocks,
+            vec![
+                Block::Break(BreakType::Page("1r".to_string(), None)),
+                anchor("A_V_C_John-1-1"),
+                text("εν αρχη ην ο λο"),
+                Block::Lacuna(streamed::Lacuna {
+                    reason: "lacuna".to_string(),
+                    unit: streamed::ExtentUnit::Character,
+                    n: 2,
+                    cert: None,
+                }),
+                text("ς"),
+                Block::Break(BreakType::Line),
+                text("και ο λς ην προς τον"),
+                Block::Correction(streamed::Correction {
+                    versions: vec![
+                        streamed::Version {
+                            lang: "grc".to_string(),
+                            hand: Some("firsthand".to_string()),
+                            content: "θν".to_string(),
+                        },
+                        streamed::Version {
+                            lang: "grc".to_string(),
+                            hand: Some("corrector".to_string()),
+                            content: "θεον".to_string(),
+                        },
+                    ],
+                }),
+                anchor("A_V_C_John-1-2"),
+                text("ουτος"),
+                Block::Uncertain(streamed::Uncertain {
+                    lang: "grc".to_string(),
+                    cert: None,
+                    agent: "faded".to_string(),
+                    content: "ην".to_string(),
+                }),
+                Block::Lacuna(streamed::Lacuna {
+                    reason: "lacuna".to_string(),
+                    unit: streamed::ExtentUnit::Line,
+                    n: 2,
+                    cert: None,
+                }),
+                text("εν αρχη"),
+                Block::Break(BreakType::Column),
+                text("προς τον θεον"),
+                Block::Break(BreakType::Page("1v".to_string(), None)),
+            ]
+        );
+        assert_eq!(
+            import.unmapped,
+            vec![
+                Unmapped {
+                    element: "ex".to_string(),
+                    page: Some("1r".to_string()),
+                },
+                Unmapped {
+                    element: "gap unit=\"quire\"".to_string(),
+                    page: Some("1v".to_string()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! Export to [IIIF Presentation 3](https://iiif.io/api/presentation/3.0/) manifests.
+//!
+//! Every [`Surface`](normalized::Surface) in the facsimile becomes a canvas painted with its image.
+//! Every line linked to a [`Zone`](normalized::Zone) becomes a `supplementing` annotation with the
+//! [surface text](SurfaceBaseText) of the line, targeting the region of the zone on its canvas.
+//! These annotations can be shown next to the image in viewers such as Mirador.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{destream::StreamError, normalized, streamed, surface_form::SurfaceBaseText};
+
+/// The JSON-LD context of IIIF Presentation 3.
+pub const CONTEXT: &str = "http://iiif.io/api/presentation/3/context.json";
+
+/// A string in several languages, keyed by BCP 47 language tag or `none`.
+pub type LanguageMap = BTreeMap<String, Vec<String>>;
+
+/// A [`LanguageMap`] with a single value without a language.
+fn label(value: &str) -> LanguageMap {
+    BTreeMap::from([("none".to_string(), vec![value.to_string()])])
+}
+
+/// The problems that can occur when exporting a manuscript to IIIF.
+#[derive(Debug, PartialEq)]
+pub enum IiifError {
+    /// The surface with this id has no `<graphic>`, so there is no image to paint
+    MissingGraphic(String),
+    /// The `<graphic>` of the surface with this id has no `@width` or `@height`
+    MissingImageSize(String),
+    /// The lower right corner of the zone with this id is above or left of its upper left corner
+    InvalidZone(String),
+    /// A line could not be streamed to compute its surface text
+    Stream(StreamError),
+}
+impl core::fmt::Display for IiifError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::MissingGraphic(x) => {
+                write!(f, "The surface \"{x}\" has no graphic.")
+            }
+            Self::MissingImageSize(x) => {
+                write!(
+                    f,
+                    "The graphic of surface \"{x}\" needs a width and height to be used as a canvas."
+                )
+            }
+            Self::InvalidZone(x) => {
+                write!(
+                    f,
+                    "The lower right corner of zone \"{x}\" is above or left of its upper left corner."
+                )
+            }
+            Self::Stream(e) => {
+                write!(f, "Unable to compute the surface text of a line: {e}")
+            }
+        }
+    }
+}
+impl core::error::Error for IiifError {}
+
+/// A IIIF manifest, describing the manuscript and its images.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename = "Manifest")]
+pub struct Manifest {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub id: String,
+    pub label: LanguageMap,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<MetadataEntry>,
+    /// One canvas per surface
+    pub items: Vec<Canvas>,
+}
+
+/// A label-value pair shown to users of the viewer.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MetadataEntry {
+    pub label: LanguageMap,
+    pub value: LanguageMap,
+}
+
+/// A virtual container for a single image.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename = "Canvas")]
+pub struct Canvas {
+    pub id: String,
+    pub label: LanguageMap,
+    pub width: u32,
+    pub height: u32,
+    /// The page painting the image onto this canvas
+    pub items: Vec<AnnotationPage>,
+    /// The pages with the transcriptions of the lines on this canvas
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationPage>,
+}
+
+/// An ordered list of annotations.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename = "AnnotationPage")]
+pub struct AnnotationPage {
+    pub id: String,
+    pub items: Vec<Annotation>,
+}
+
+/// A W3C Web Annotation.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type", rename = "Annotation")]
+pub struct Annotation {
+    pub id: String,
+    /// `painting` for images, `supplementing` for transcriptions
+    pub motivation: String,
+    pub body: Body,
+    /// The canvas, optionally with a `#xywh=` fragment selecting a region
+    pub target: String,
+}
+
+/// The content of an [`Annotation`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(tag = "type")]
+pub enum Body {
+    /// An image painted onto a canvas
+    Image {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+        width: u32,
+        height: u32,
+    },
+    /// The transcription of a line
+    TextualBody {
+        value: String,
+        format: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+    },
+}
+
+/// Percent-encode `value` for use as a single segment in the path of an id.
+///
+/// Everything except the unreserved characters of RFC 3986 is encoded, so page names with spaces,
+/// slashes or non-ASCII characters give valid urls.
+fn path_segment(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            res.push(char::from(byte));
+        } else {
+            res.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    res
+}
+
+/// The media type of an image, guessed from the extension of its url.
+fn image_format(url: &str) -> Option<String> {
+    let extension = url.rsplit_once('.')?.1.to_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "tif" | "tiff" => Some("image/tiff"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+    .map(ToString::to_string)
+}
+
+/// The surface text of a single line, in the language `lang` unless set otherwise on a block.
+fn line_text(lang: &str, line: &normalized::Line) -> Result<String, StreamError> {
+    let blocks = line
+        .blocks
+        .iter()
+        .map(|b| streamed::Block::try_from((lang.to_string(), b.clone())))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(SurfaceBaseText::from_blocks(&blocks).destructure().0)
+}
+
+/// Export `ms` as a IIIF manifest.
+///
+/// All ids are built from `base_url`: the manifest is `{base_url}/manifest`, the canvas for the
+/// surface `s1` is `{base_url}/canvas/s1`, and so on. Ids and page names are percent-encoded in
+/// these urls. Lines without a `facs` pointing to a zone are not annotated.
+///
+/// # Errors
+/// If a surface has no image with a known size, a zone has a negative width or height, or a line
+/// cannot be streamed.
+pub fn manifest(ms: &normalized::Manuscript, base_url: &str) -> Result<Manifest, IiifError> {
+    let base_url = base_url.trim_end_matches('/');
+    let canvas_id = |surface: &normalized::Surface| {
+        format!("{base_url}/canvas/{}", path_segment(&surface.xml_id))
+    };
+
+    // the transcriptions of all linked lines, by surface id
+    let mut line_annotations = BTreeMap::<&str, Vec<Annotation>>::new();
+    for page in &ms.text.pages {
+        for column in &page.columns {
+            for line in &column.lines {
+                let Some((surface, zone)) = line.facs.as_deref().and_then(|f| ms.facsimile.zone(f))
+                else {
+                    continue;
+                };
+                let lang = line
+                    .lang
+                    .as_deref()
+                    .or(column.lang.as_deref())
+                    .or(page.lang.as_deref())
+                    .unwrap_or(&ms.text.lang);
+                let target = match zone.bounding_box() {
+                    Some((ulx, uly, lrx, lry)) => {
+                        let (Some(width), Some(height)) = (
+                            lrx.checked_sub(ulx).filter(|w| *w >= 0),
+                            lry.checked_sub(uly).filter(|h| *h >= 0),
+                        ) else {
+                            return Err(IiifError::InvalidZone(zone.xml_id.clone()));
+                        };
+                        format!("{}#xywh={ulx},{uly},{width},{height}", canvas_id(surface))
+                    }
+                    None => canvas_id(surface),
+                };
+                line_annotations
+                    .entry(&surface.xml_id)
+                    .or_default()
+                    .push(Annotation {
+                        id: format!(
+                            "{base_url}/annotation/{}/{}/{}",
+                            path_segment(&page.n),
+                            column.n,
+                            line.n
+                        ),
+                        motivation: "supplementing".to_string(),
+                        body: Body::TextualBody {
+                            value: line_text(lang, line).map_err(IiifError::Stream)?,
+                            format: "text/plain".to_string(),
+                            language: Some(lang.to_string()),
+                        },
+                        target,
+                    });
+            }
+        }
+    }
+
+    let mut items = Vec::with_capacity(ms.facsimile.surface.len());
+    for surface in &ms.facsimile.surface {
+        let graphic = surface
+            .graphic
+            .as_ref()
+            .ok_or_else(|| IiifError::MissingGraphic(surface.xml_id.clone()))?;
+        let (Some(width), Some(height)) = (graphic.width, graphic.height) else {
+            return Err(IiifError::MissingImageSize(surface.xml_id.clone()));
+        };
+        let id = canvas_id(surface);
+        // label the canvas with the page shown on it
+        let page_name = ms
+            .text
+            .pages
+            .iter()
+            .find(|p| {
+                p.facs
+                    .as_deref()
+                    .and_then(|f| ms.facsimile.surface(f))
+                    .is_some_and(|s| s.xml_id == surface.xml_id)
+            })
+            .map_or(surface.xml_id.as_str(), |p| p.n.as_str());
+        let annotations = match line_annotations.remove(surface.xml_id.as_str()) {
+            Some(lines) => vec![AnnotationPage {
+                id: format!("{id}/lines"),
+                items: lines,
+            }],
+            None => vec![],
+        };
+        items.push(Canvas {
+            label: label(page_name),
+            width,
+            height,
+            items: vec![AnnotationPage {
+                id: format!("{id}/painting"),
+                items: vec![Annotation {
+                    id: format!("{id}/painting/image"),
+                    motivation: "painting".to_string(),
+                    body: Body::Image {
+                        id: graphic.url.clone(),
+                        format: image_format(&graphic.url),
+                        width,
+                        height,
+                    },
+                    target: id.clone(),
+                }],
+            }],
+            annotations,
+            id,
+        });
+    }
+
+    let metadata = [
+        ("Institution", &ms.meta.institution),
+        ("Collection", &ms.meta.collection),
+        ("Shelfmark", &ms.meta.shelfmark),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        value.as_deref().map(|v| MetadataEntry {
+            label: label(name),
+            value: label(v),
+        })
+    })
+    .collect();
+
+    Ok(Manifest {
+        context: CONTEXT.to_string(),
+        id: format!("{base_url}/manifest"),
+        label: label(&ms.meta.title),
+        metadata,
+        items,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn facsimile_ms() -> normalized::Manuscript {
+        let xml = include_str!("../examples/14_facsimile.xml");
+        let tei: crate::schema::Tei = quick_xml::de::from_str(xml).unwrap();
+        tei.try_into().unwrap()
+    }
+
+    #[test]
+    fn manifest_fixture() {
+        let manifest = manifest(&facsimile_ms(), "https://example.org/iiif/ms1/").unwrap();
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../examples/14_facsimile.iiif.json")).unwrap();
+        assert_eq!(serde_json::to_value(&manifest).unwrap(), expected);
+        let roundtrip: Manifest = serde_json::from_value(expected).unwrap();
+        assert_eq!(roundtrip, manifest);
+    }
+
+    #[test]
+    fn missing_image_size() {
+        let mut ms = facsimile_ms();
+        ms.facsimile.surface[1]
+            .graphic
+            .as_mut()
+            .unwrap()
+            .width
+            .take();
+        assert_eq!(
+            manifest(&ms, "https://example.org/iiif/ms1"),
+            Err(IiifError::MissingImageSize("s_01_v".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_zone() {
+        let mut ms = facsimile_ms();
+        let zone = &mut ms.facsimile.surface[0].zone[1];
+        zone.lrx = Some(i32::MIN);
+        assert_eq!(
+            manifest(&ms, "https://example.org/iiif/ms1"),
+            Err(IiifError::InvalidZone("z_01_r_c1_l1".to_string()))
+        );
+        ms.facsimile.surface[0].zone[1].lrx = Some(900);
+        ms.facsimile.surface[0].zone[1].uly = Some(i32::MIN);
+        assert_eq!(
+            manifest(&ms, "https://example.org/iiif/ms1"),
+            Err(IiifError::InvalidZone("z_01_r_c1_l1".to_string()))
+        );
+    }
+
+    #[test]
+    fn page_names_are_encoded() {
+        let mut ms = facsimile_ms();
+        ms.text.pages[0].n = "MAPM from verse 347/ä".to_string();
+        let manifest = manifest(&ms, "https://example.org/iiif/ms1").unwrap();
+        let annotation = &manifest.items[0].annotations[0].items[0];
+        assert_eq!(
+            annotation.id,
+            "https://example.org/iiif/ms1/annotation/MAPM%20from%20verse%20347%2F%C3%A4/1/1"
+        );
+        assert_eq!(manifest.items[0].label, label("MAPM from verse 347/ä"));
+    }
+}
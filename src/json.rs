@@ -0,0 +1,1202 @@
+//! The versioned JSON interchange format for [`streamed::Manuscript`]s.
+//!
+//! This is the format exchanged with the web editor of
+//! [critic](https://github.com/curatorsigma/critic). It is designed independently of the XML
+//! serialization: blocks are tagged internally with `"type"`, optional fields are left out
+//! instead of set to `null`, and the document carries its [`FORMAT_VERSION`]. The JSON Schema of
+//! the format is generated with [`schema()`] and committed as `json-schema/manuscript.schema.json`,
+//! next to the TypeScript definitions of the types in `typescript/manuscript.d.ts`.
+//!
+//! Any change to the types in this module changes the format. It has to be deliberate: bump
+//! [`FORMAT_VERSION`] if old documents can no longer be read, and regenerate the schema and the
+//! TypeScript definitions.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "meta": { "title": "Codex Vaticanus", … },
+//!   "content": [
+//!     { "type": "PageBreak", "n": "1r" },
+//!     { "type": "Text", "lang": "grc", "content": "εν αρχη" },
+//!     { "type": "Lacuna", "reason": "lost", "unit": "line", "n": 1 }
+//!   ]
+//! }
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{normalized, schema, streamed};
+
+/// The version of the format written by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The problems that can occur when reading the JSON format.
+#[derive(Debug)]
+pub enum JsonError {
+    /// The input is not valid JSON for this format
+    Json(serde_json::Error),
+    /// The document has a version this crate cannot read
+    UnsupportedVersion(u32),
+    /// A date is not a valid date of the form `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    InvalidDate(String, normalized::DateError),
+    /// A `not_before` is after its `not_after`
+    EmptyDateRange(String, String),
+    /// A count has a minimum above its maximum
+    InvalidCount(u32, u32),
+    /// A content item has only one of `from_page` and `to_page`; this is the one it has
+    IncompleteLocus(String),
+}
+impl core::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Json(e) => {
+                write!(f, "Unable to read the JSON document: {e}")
+            }
+            Self::UnsupportedVersion(x) => {
+                write!(
+                    f,
+                    "The JSON document has version {x}, but only version {FORMAT_VERSION} is supported."
+                )
+            }
+            Self::InvalidDate(x, e) => {
+                write!(f, "\"{x}\" is not a valid date: {e}")
+            }
+            Self::EmptyDateRange(not_before, not_after) => {
+                write!(
+                    f,
+                    "The date range is empty: {not_before} is after {not_after}."
+                )
+            }
+            Self::InvalidCount(min, max) => {
+                write!(f, "The count {min}-{max} has a minimum above its maximum.")
+            }
+            Self::IncompleteLocus(x) => {
+                write!(
+                    f,
+                    "The passage on page {x} has only one of from_page and to_page."
+                )
+            }
+        }
+    }
+}
+impl core::error::Error for JsonError {}
+
+/// A complete manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Manuscript {
+    /// The version of this format, currently always 1
+    pub version: u32,
+    pub meta: Meta,
+    /// The text, starting with a page break
+    pub content: Vec<Block>,
+    /// The images the text is linked to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Surface>>", optional))]
+    pub surfaces: Vec<Surface>,
+    /// The links from pages, columns and lines to the `surfaces`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<FacsLink>>", optional))]
+    pub facs: Vec<FacsLink>,
+}
+
+/// A single block of the text.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(tag = "type")]
+pub enum Block {
+    /// Plain text
+    Text { lang: String, content: String },
+    /// Text that is damaged or otherwise hard to read
+    Uncertain {
+        lang: String,
+        /// `high`, `medium`, `low`, `unknown` or a probability such as `0.75`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(with = "Option<String>")]
+        #[cfg_attr(test, ts(as = "Option<String>", optional))]
+        cert: Option<schema::Certainty>,
+        /// The cause of the uncertainty
+        agent: String,
+        content: String,
+    },
+    /// An abbreviation and its expansion
+    Abbreviation {
+        surface_lang: String,
+        /// The abbreviation as written
+        surface: String,
+        expansion_lang: String,
+        /// The expansion supplied by the transcriber
+        expansion: String,
+    },
+    /// An ancient correction, with every version of the text
+    Correction { versions: Vec<Version> },
+    /// Text that is lost
+    Lacuna {
+        reason: String,
+        unit: ExtentUnit,
+        /// The number of units lost
+        n: i32,
+        /// `high`, `medium`, `low`, `unknown` or a probability such as `0.75`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[schemars(with = "Option<String>")]
+        #[cfg_attr(test, ts(as = "Option<String>", optional))]
+        cert: Option<schema::Certainty>,
+    },
+    /// Space intentionally left empty by the scribe
+    Space { quantity: i32, unit: ExtentUnit },
+    /// The beginning of a verse
+    Anchor {
+        /// `A_V_{scheme-shorthand}_{book}-{chapter}-{verse}`
+        anchor_id: String,
+        /// The long form of the versification scheme
+        anchor_type: String,
+    },
+    /// The beginning of a new line
+    LineBreak,
+    /// The beginning of a new column
+    ColumnBreak,
+    /// The beginning of a new page
+    PageBreak {
+        /// The name of the page
+        n: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(test, ts(optional))]
+        status: Option<PageStatus>,
+    },
+}
+
+/// A single version of a [`Block::Correction`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Version {
+    pub lang: String,
+    /// The scribal hand responsible for this version, one of the `hands` in the `meta`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub hand: Option<String>,
+    pub content: String,
+}
+
+/// The unit of a [`Block::Lacuna`] or [`Block::Space`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ExtentUnit {
+    Character,
+    Line,
+    Column,
+}
+
+/// The stage of the transcription workflow a page is in.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum PageStatus {
+    Draft,
+    Transcribed,
+    Reviewed,
+    Approved,
+}
+
+/// A link from a page, column or line to a [`Surface`] or [`Zone`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct FacsLink {
+    pub page: String,
+    /// The column, if the link is not for the page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub column: Option<i32>,
+    /// The line, if the link is not for the column or page itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub line: Option<i32>,
+    /// The pointer to the surface or zone, e.g. `#z_01_r_c1`
+    pub facs: String,
+}
+
+/// An image of a page.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Surface {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub graphic: Option<Graphic>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Zone>>", optional))]
+    pub zones: Vec<Zone>,
+}
+
+/// The image file of a [`Surface`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Graphic {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub height: Option<u32>,
+}
+
+/// A region of a [`Surface`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Zone {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub ulx: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub uly: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub lrx: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub lry: Option<i32>,
+    /// A polygon as `x,y x,y …`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(test, ts(as = "Option<String>", optional))]
+    pub points: Option<schema::Points>,
+}
+
+/// The header with any meta-information.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Meta {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub settlement: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub institution: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub repository: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub collection: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub shelfmark: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub hand_desc: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Hand>>", optional))]
+    pub hands: Vec<Hand>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub script_desc: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Identifier>>", optional))]
+    pub alt_identifiers: Vec<Identifier>,
+    /// The earliest date this manuscript may have been written, as `YYYY[-MM[-DD]]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub orig_not_before: Option<String>,
+    /// The latest date this manuscript may have been written, as `YYYY[-MM[-DD]]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub orig_not_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub orig_date_desc: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub orig_place: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<String>>", optional))]
+    pub provenance: Vec<String>,
+    /// `parchment`, `papyrus`, `paper` or any other material
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(test, ts(as = "Option<String>", optional))]
+    pub material: Option<schema::Material>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub support: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub extent: Option<Extent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Layout>>", optional))]
+    pub layouts: Vec<Layout>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<ContentItem>>", optional))]
+    pub contents: Vec<ContentItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Change>>", optional))]
+    pub changes: Vec<Change>,
+}
+
+/// A scribal hand.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Hand {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub scribe: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub script: Option<String>,
+    /// As `YYYY[-MM[-DD]]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub not_before: Option<String>,
+    /// As `YYYY[-MM[-DD]]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub not_after: Option<String>,
+}
+
+/// An alternative identifier of the manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Identifier {
+    /// The catalogue, e.g. `Gregory-Aland` or `Rahlfs`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    #[cfg_attr(test, ts(as = "Option<String>", optional))]
+    pub id_type: Option<schema::IdNoType>,
+    pub idno: String,
+}
+
+/// The extent of the manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Extent {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Measure>>", optional))]
+    pub measures: Vec<Measure>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(test, ts(as = "Option<Vec<Dimensions>>", optional))]
+    pub dimensions: Vec<Dimensions>,
+}
+
+/// A countable part of the manuscript, e.g. its leaves.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Measure {
+    pub unit: String,
+    pub quantity: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub description: Option<String>,
+}
+
+/// The size of a part of the manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Dimensions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub dim_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub width: Option<u32>,
+}
+
+/// A layout used in the manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Layout {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub columns: Option<Count>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub written_lines: Option<Count>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub description: Option<String>,
+}
+
+/// A number or range of numbers.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Count {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// A passage contained in the manuscript.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct ContentItem {
+    /// The first page of the passage, given together with `to_page`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub from_page: Option<String>,
+    /// The last page of the passage, given together with `from_page`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub to_page: Option<String>,
+    /// The versification scheme of `passage`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub scheme: Option<String>,
+    /// The passage, e.g. `Ps 26:10–31:4`
+    pub passage: String,
+}
+
+/// A change made to the file.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(test, derive(ts_rs::TS))]
+pub struct Change {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub who: Option<String>,
+    /// As `YYYY[-MM[-DD]]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub when: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub status: Option<PageStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub page: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(test, ts(optional))]
+    pub description: Option<String>,
+}
+
+// conversion from the streamed model
+
+impl From<&streamed::Block> for Block {
+    fn from(value: &streamed::Block) -> Self {
+        match value.clone() {
+            streamed::Block::Text(x) => Self::Text {
+                lang: x.lang,
+                content: x.content,
+            },
+            streamed::Block::Uncertain(x) => Self::Uncertain {
+                lang: x.lang,
+                cert: x.cert,
+                agent: x.agent,
+                content: x.content,
+            },
+            streamed::Block::Abbreviation(x) => Self::Abbreviation {
+                surface_lang: x.surface_lang,
+                surface: x.surface,
+                expansion_lang: x.expansion_lang,
+                expansion: x.expansion,
+            },
+            streamed::Block::Correction(x) => Self::Correction {
+                versions: x
+                    .versions
+                    .into_iter()
+                    .map(|v| Version {
+                        lang: v.lang,
+                        hand: v.hand,
+                        content: v.content,
+                    })
+                    .collect(),
+            },
+            streamed::Block::Lacuna(x) => Self::Lacuna {
+                reason: x.reason,
+                unit: x.unit.into(),
+                n: x.n,
+                cert: x.cert,
+            },
+            streamed::Block::Space(x) => Self::Space {
+                quantity: x.quantity,
+                unit: x.unit.into(),
+            },
+            streamed::Block::Anchor(x) => Self::Anchor {
+                anchor_id: x.anchor_id,
+                anchor_type: x.anchor_type,
+            },
+            streamed::Block::Break(streamed::BreakType::Line) => Self::LineBreak,
+            streamed::Block::Break(streamed::BreakType::Column) => Self::ColumnBreak,
+            streamed::Block::Break(streamed::BreakType::Page(n, status)) => Self::PageBreak {
+                n,
+                status: status.map(Into::into),
+            },
+        }
+    }
+}
+
+impl From<schema::ExtentUnit> for ExtentUnit {
+    fn from(value: schema::ExtentUnit) -> Self {
+        match value {
+            schema::ExtentUnit::Character => Self::Character,
+            schema::ExtentUnit::Line => Self::Line,
+            schema::ExtentUnit::Column => Self::Column,
+        }
+    }
+}
+
+impl From<schema::PageStatus> for PageStatus {
+    fn from(value: schema::PageStatus) -> Self {
+        match value {
+            schema::PageStatus::Draft => Self::Draft,
+            schema::PageStatus::Transcribed => Self::Transcribed,
+            schema::PageStatus::Reviewed => Self::Reviewed,
+            schema::PageStatus::Approved => Self::Approved,
+        }
+    }
+}
+
+impl From<&normalized::Surface> for Surface {
+    fn from(value: &normalized::Surface) -> Self {
+        Self {
+            id: value.xml_id.clone(),
+            graphic: value.graphic.as_ref().map(|g| Graphic {
+                url: g.url.clone(),
+                width: g.width,
+                height: g.height,
+            }),
+            zones: value
+                .zone
+                .iter()
+                .map(|z| Zone {
+                    id: z.xml_id.clone(),
+                    ulx: z.ulx,
+                    uly: z.uly,
+                    lrx: z.lrx,
+                    lry: z.lry,
+                    points: z.points.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&normalized::Meta> for Meta {
+    fn from(value: &normalized::Meta) -> Self {
+        let value = value.clone();
+        let date = |d: Option<normalized::Date>| d.map(|d| d.to_string());
+        let count = |c: Option<schema::Count>| {
+            c.map(|c| Count {
+                min: c.min,
+                max: c.max,
+            })
+        };
+        Self {
+            title: value.title,
+            country: value.country,
+            settlement: value.settlement,
+            institution: value.institution,
+            repository: value.repository,
+            collection: value.collection,
+            shelfmark: value.shelfmark,
+            hand_desc: value.hand_desc,
+            hands: value
+                .hands
+                .into_iter()
+                .map(|h| Hand {
+                    id: h.id,
+                    description: h.description,
+                    scribe: h.scribe,
+                    script: h.script,
+                    not_before: date(h.not_before),
+                    not_after: date(h.not_after),
+                })
+                .collect(),
+            script_desc: value.script_desc,
+            alt_identifiers: value
+                .alt_identifier
+                .into_iter()
+                .map(|i| Identifier {
+                    id_type: i.id_type,
+                    idno: i.idno,
+                })
+                .collect(),
+            orig_not_before: date(value.orig_date.and_then(|d| d.not_before)),
+            orig_not_after: date(value.orig_date.and_then(|d| d.not_after)),
+            orig_date_desc: value.orig_date_desc,
+            orig_place: value.orig_place,
+            provenance: value.provenance,
+            material: value.material,
+            support: value.support,
+            extent: value.extent.map(|e| Extent {
+                measures: e
+                    .measure
+                    .into_iter()
+                    .map(|m| Measure {
+                        unit: m.unit,
+                        quantity: m.quantity,
+                        description: m.description,
+                    })
+                    .collect(),
+                dimensions: e
+                    .dimensions
+                    .into_iter()
+                    .map(|d| Dimensions {
+                        dim_type: d.dim_type,
+                        unit: d.unit,
+                        height: d.height,
+                        width: d.width,
+                    })
+                    .collect(),
+            }),
+            layouts: value
+                .layouts
+                .into_iter()
+                .map(|l| Layout {
+                    columns: count(l.columns),
+                    written_lines: count(l.written_lines),
+                    description: l.description,
+                })
+                .collect(),
+            contents: value
+                .contents
+                .into_iter()
+                .map(|c| ContentItem {
+                    from_page: c.locus.as_ref().map(|l| l.from.clone()),
+                    to_page: c.locus.map(|l| l.to),
+                    scheme: c.title.title_type,
+                    passage: c.title.name,
+                })
+                .collect(),
+            changes: value
+                .changes
+                .into_iter()
+                .map(|c| Change {
+                    who: c.who,
+                    when: date(c.when),
+                    status: c.status.map(Into::into),
+                    page: c.page,
+                    description: c.description,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&streamed::Manuscript> for Manuscript {
+    fn from(value: &streamed::Manuscript) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            meta: (&value.meta).into(),
+            content: value.content.iter().map(Into::into).collect(),
+            surfaces: value.facsimile.surface.iter().map(Into::into).collect(),
+            facs: value
+                .facs
+                .iter()
+                .map(|f| FacsLink {
+                    page: f.page.clone(),
+                    column: f.column,
+                    line: f.line,
+                    facs: f.facs.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// conversion to the streamed model
+
+impl From<Block> for streamed::Block {
+    fn from(value: Block) -> Self {
+        match value {
+            Block::Text { lang, content } => Self::Text(streamed::Paragraph { lang, content }),
+            Block::Uncertain {
+                lang,
+                cert,
+                agent,
+                content,
+            } => Self::Uncertain(streamed::Uncertain {
+                lang,
+                cert,
+                agent,
+                content,
+            }),
+            Block::Abbreviation {
+                surface_lang,
+                surface,
+                expansion_lang,
+                expansion,
+            } => Self::Abbreviation(streamed::Abbreviation {
+                surface_lang,
+                surface,
+                expansion_lang,
+                expansion,
+            }),
+            Block::Correction { versions } => Self::Correction(streamed::Correction {
+                versions: versions
+                    .into_iter()
+                    .map(|v| streamed::Version {
+                        lang: v.lang,
+                        hand: v.hand,
+                        content: v.content,
+                    })
+                    .collect(),
+            }),
+            Block::Lacuna {
+                reason,
+                unit,
+                n,
+                cert,
+            } => Self::Lacuna(streamed::Lacuna {
+                reason,
+                unit: unit.into(),
+                n,
+                cert,
+            }),
+            Block::Space { quantity, unit } => Self::Space(streamed::Space {
+                quantity,
+                unit: unit.into(),
+            }),
+            Block::Anchor {
+                anchor_id,
+                anchor_type,
+            } => Self::Anchor(streamed::Anchor {
+                anchor_id,
+                anchor_type,
+            }),
+            Block::LineBreak => Self::Break(streamed::BreakType::Line),
+            Block::ColumnBreak => Self::Break(streamed::BreakType::Column),
+            Block::PageBreak { n, status } => {
+                Self::Break(streamed::BreakType::Page(n, status.map(Into::into)))
+            }
+        }
+    }
+}
+
+impl From<ExtentUnit> for schema::ExtentUnit {
+    fn from(value: ExtentUnit) -> Self {
+        match value {
+            ExtentUnit::Character => Self::Character,
+            ExtentUnit::Line => Self::Line,
+            ExtentUnit::Column => Self::Column,
+        }
+    }
+}
+
+impl From<PageStatus> for schema::PageStatus {
+    fn from(value: PageStatus) -> Self {
+        match value {
+            PageStatus::Draft => Self::Draft,
+            PageStatus::Transcribed => Self::Transcribed,
+            PageStatus::Reviewed => Self::Reviewed,
+            PageStatus::Approved => Self::Approved,
+        }
+    }
+}
+
+impl From<Surface> for normalized::Surface {
+    fn from(value: Surface) -> Self {
+        Self {
+            xml_id: value.id,
+            graphic: value.graphic.map(|g| normalized::Graphic {
+                url: g.url,
+                width: g.width,
+                height: g.height,
+            }),
+            zone: value
+                .zones
+                .into_iter()
+                .map(|z| normalized::Zone {
+                    xml_id: z.id,
+                    ulx: z.ulx,
+                    uly: z.uly,
+                    lrx: z.lrx,
+                    lry: z.lry,
+                    points: z.points,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parse an optional date.
+fn parse_date(date: Option<String>) -> Result<Option<normalized::Date>, JsonError> {
+    date.map(|d| d.parse().map_err(|e| JsonError::InvalidDate(d, e)))
+        .transpose()
+}
+
+/// Parse an optional date range, making sure it is not empty.
+fn parse_date_range(
+    not_before: Option<String>,
+    not_after: Option<String>,
+) -> Result<normalized::DateRange, JsonError> {
+    let range = normalized::DateRange {
+        not_before: parse_date(not_before.clone())?,
+        not_after: parse_date(not_after.clone())?,
+    };
+    if range.is_empty() {
+        return Err(JsonError::EmptyDateRange(
+            not_before.unwrap_or_default(),
+            not_after.unwrap_or_default(),
+        ));
+    }
+    Ok(range)
+}
+
+impl TryFrom<Count> for schema::Count {
+    type Error = JsonError;
+    fn try_from(value: Count) -> Result<Self, Self::Error> {
+        if value.min > value.max {
+            return Err(JsonError::InvalidCount(value.min, value.max));
+        }
+        Ok(Self {
+            min: value.min,
+            max: value.max,
+        })
+    }
+}
+
+impl TryFrom<Meta> for normalized::Meta {
+    type Error = JsonError;
+    fn try_from(value: Meta) -> Result<Self, Self::Error> {
+        let orig_date = parse_date_range(value.orig_not_before, value.orig_not_after)?;
+        Ok(Self {
+            title: value.title,
+            country: value.country,
+            settlement: value.settlement,
+            institution: value.institution,
+            repository: value.repository,
+            collection: value.collection,
+            shelfmark: value.shelfmark,
+            hand_desc: value.hand_desc,
+            hands: value
+                .hands
+                .into_iter()
+                .map(|h| {
+                    let range = parse_date_range(h.not_before, h.not_after)?;
+                    Ok(normalized::Hand {
+                        id: h.id,
+                        description: h.description,
+                        scribe: h.scribe,
+                        script: h.script,
+                        not_before: range.not_before,
+                        not_after: range.not_after,
+                    })
+                })
+                .collect::<Result<_, JsonError>>()?,
+            script_desc: value.script_desc,
+            alt_identifier: value
+                .alt_identifiers
+                .into_iter()
+                .map(|i| normalized::Identifier {
+                    id_type: i.id_type,
+                    idno: i.idno,
+                })
+                .collect(),
+            orig_date: (orig_date != normalized::DateRange::default()).then_some(orig_date),
+            orig_date_desc: value.orig_date_desc,
+            orig_place: value.orig_place,
+            provenance: value.provenance,
+            material: value.material,
+            support: value.support,
+            extent: value.extent.map(|e| normalized::Extent {
+                measure: e
+                    .measures
+                    .into_iter()
+                    .map(|m| schema::Measure {
+                        unit: m.unit,
+                        quantity: m.quantity,
+                        description: m.description,
+                    })
+                    .collect(),
+                dimensions: e
+                    .dimensions
+                    .into_iter()
+                    .map(|d| schema::Dimensions {
+                        dim_type: d.dim_type,
+                        unit: d.unit,
+                        height: d.height,
+                        width: d.width,
+                    })
+                    .collect(),
+            }),
+            layouts: value
+                .layouts
+                .into_iter()
+                .map(|l| {
+                    Ok(normalized::Layout {
+                        columns: l.columns.map(TryInto::try_into).transpose()?,
+                        written_lines: l.written_lines.map(TryInto::try_into).transpose()?,
+                        description: l.description,
+                    })
+                })
+                .collect::<Result<_, JsonError>>()?,
+            contents: value
+                .contents
+                .into_iter()
+                .map(|c| {
+                    let locus = match (c.from_page, c.to_page) {
+                        (Some(from), Some(to)) => Some(schema::Locus { from, to }),
+                        (None, None) => None,
+                        (Some(page), None) | (None, Some(page)) => {
+                            return Err(JsonError::IncompleteLocus(page));
+                        }
+                    };
+                    Ok(normalized::ContentItem {
+                        locus,
+                        title: schema::MsItemTitle {
+                            title_type: c.scheme,
+                            name: c.passage,
+                        },
+                    })
+                })
+                .collect::<Result<_, JsonError>>()?,
+            changes: value
+                .changes
+                .into_iter()
+                .map(|c| {
+                    Ok(normalized::Change {
+                        who: c.who,
+                        when: parse_date(c.when)?,
+                        status: c.status.map(Into::into),
+                        page: c.page,
+                        description: c.description,
+                    })
+                })
+                .collect::<Result<_, JsonError>>()?,
+        })
+    }
+}
+
+impl TryFrom<Manuscript> for streamed::Manuscript {
+    type Error = JsonError;
+    fn try_from(value: Manuscript) -> Result<Self, Self::Error> {
+        if value.version != FORMAT_VERSION {
+            return Err(JsonError::UnsupportedVersion(value.version));
+        }
+        Ok(Self {
+            meta: value.meta.try_into()?,
+            content: value.content.into_iter().map(Into::into).collect(),
+            facsimile: streamed::Facsimile {
+                surface: value.surfaces.into_iter().map(Into::into).collect(),
+            },
+            facs: value
+                .facs
+                .into_iter()
+                .map(|f| streamed::FacsLink {
+                    page: f.page,
+                    column: f.column,
+                    line: f.line,
+                    facs: f.facs,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Write `ms` in the JSON format.
+///
+/// # Errors
+/// Only if serialization fails, which should not happen for valid manuscripts.
+pub fn to_json(ms: &streamed::Manuscript) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&Manuscript::from(ms))
+}
+
+/// Read a manuscript in the JSON format.
+///
+/// # Errors
+/// If `json` is not a document of a supported version of the format.
+pub fn from_json(json: &str) -> Result<streamed::Manuscript, JsonError> {
+    // check the version first, so newer documents get a helpful error
+    #[derive(Deserialize)]
+    struct Versioned {
+        version: u32,
+    }
+    let versioned: Versioned = serde_json::from_str(json).map_err(JsonError::Json)?;
+    if versioned.version != FORMAT_VERSION {
+        return Err(JsonError::UnsupportedVersion(versioned.version));
+    }
+    let ms: Manuscript = serde_json::from_str(json).map_err(JsonError::Json)?;
+    ms.try_into()
+}
+
+/// The JSON Schema of the format.
+#[must_use]
+pub fn schema() -> serde_json::Value {
+    let mut schema = schemars::schema_for!(Manuscript);
+    schema.insert(
+        "$id".to_string(),
+        format!("urn:critic-format:manuscript:v{FORMAT_VERSION}").into(),
+    );
+    schema.to_value()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use ts_rs::{TS, TypeVisitor};
+
+    use super::*;
+
+    fn ms(path: &str) -> streamed::Manuscript {
+        let xml = std::fs::File::open(path).unwrap();
+        crate::from_xml(std::io::BufReader::new(xml)).unwrap()
+    }
+
+    #[test]
+    fn roundtrip() {
+        for file in [
+            "examples/02_lines_consistent.xml",
+            "examples/07_multi-page.xml",
+            "examples/09_missing_pages.xml",
+            "examples/10_hand_notes.xml",
+            "examples/11_identifiers.xml",
+            "examples/12_history.xml",
+            "examples/13_layout.xml",
+            "examples/14_facsimile.xml",
+        ] {
+            let ms = ms(file);
+            let json = to_json(&ms).unwrap();
+            assert_eq!(from_json(&json).unwrap(), ms, "{file}");
+        }
+    }
+
+    #[test]
+    fn block_shape() {
+        let blocks = [
+            streamed::Block::Break(streamed::BreakType::Page(
+                "1r".to_string(),
+                Some(schema::PageStatus::Draft),
+            )),
+            streamed::Block::Lacuna(streamed::Lacuna {
+                reason: "lost".to_string(),
+                unit: schema::ExtentUnit::Line,
+                n: 2,
+                cert: Some(schema::Certainty::High),
+            }),
+            streamed::Block::Break(streamed::BreakType::Line),
+            streamed::Block::Uncertain(streamed::Uncertain {
+                lang: "grc".to_string(),
+                cert: None,
+                agent: "water".to_string(),
+                content: "λογος".to_string(),
+            }),
+        ];
+        let json =
+            serde_json::to_value(blocks.iter().map(Block::from).collect::<Vec<_>>()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "type": "PageBreak", "n": "1r", "status": "draft" },
+                { "type": "Lacuna", "reason": "lost", "unit": "line", "n": 2, "cert": "high" },
+                { "type": "LineBreak" },
+                { "type": "Uncertain", "lang": "grc", "agent": "water", "content": "λογος" },
+            ])
+        );
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let json = to_json(&ms("examples/02_lines_consistent.xml"))
+            .unwrap()
+            .replacen("\"version\":1", "\"version\":2", 1);
+        assert!(matches!(
+            from_json(&json),
+            Err(JsonError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn incomplete_locus() {
+        let mut json: serde_json::Value =
+            serde_json::from_str(&to_json(&ms("examples/02_lines_consistent.xml")).unwrap())
+                .unwrap();
+        json["meta"]["contents"] = serde_json::json!([
+            { "from_page": "page1", "to_page": "page2", "passage": "Gen 1:1–2" },
+        ]);
+        let ms = from_json(&json.to_string()).unwrap();
+        assert_eq!(from_json(&to_json(&ms).unwrap()).unwrap(), ms);
+
+        for locus in [
+            serde_json::json!({ "from_page": "page1", "passage": "Gen 1:1" }),
+            serde_json::json!({ "to_page": "page1", "passage": "Gen 1:1" }),
+        ] {
+            json["meta"]["contents"] = serde_json::json!([locus]);
+            assert!(
+                matches!(
+                    from_json(&json.to_string()),
+                    Err(JsonError::IncompleteLocus(page)) if page == "page1"
+                ),
+                "{locus}"
+            );
+        }
+    }
+
+    /// The committed schema is the schema of the current format.
+    ///
+    /// Run with `UPDATE_JSON_SCHEMA=1` to regenerate it after a deliberate change.
+    #[test]
+    fn committed_schema() {
+        let path = "json-schema/manuscript.schema.json";
+        let generated = format!("{}\n", serde_json::to_string_pretty(&schema()).unwrap());
+        if std::env::var_os("UPDATE_JSON_SCHEMA").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(path).unwrap();
+        assert!(
+            committed == generated,
+            "{path} is out of date. Run the tests with UPDATE_JSON_SCHEMA=1 to regenerate it."
+        );
+    }
+
+    /// Collects the declarations of a type and all types it depends on.
+    #[derive(Default)]
+    struct Declarations(BTreeMap<String, String>);
+    impl TypeVisitor for Declarations {
+        fn visit<T: TS + 'static + ?Sized>(&mut self) {
+            if T::output_path().is_none() || self.0.contains_key(&T::ident()) {
+                return;
+            }
+            self.0.insert(
+                T::ident(),
+                format!("{}export {}\n", T::docs().unwrap_or_default(), T::decl()),
+            );
+            T::visit_dependencies(self);
+        }
+    }
+
+    /// The TypeScript definitions of all types of the format.
+    fn typescript_definitions() -> String {
+        let mut declarations = Declarations::default();
+        declarations.visit::<Manuscript>();
+        let mut res = String::from(
+            "// Generated from the JSON format of critic-format. Do not edit by hand.\n",
+        );
+        for declaration in declarations.0.values() {
+            res.push('\n');
+            res.push_str(declaration);
+        }
+        res
+    }
+
+    /// The committed TypeScript definitions are the definitions of the current format.
+    ///
+    /// Run with `UPDATE_TYPESCRIPT=1` to regenerate them after a deliberate change.
+    #[test]
+    fn committed_typescript_definitions() {
+        let path = "typescript/manuscript.d.ts";
+        let generated = typescript_definitions();
+        if std::env::var_os("UPDATE_TYPESCRIPT").is_some() {
+            std::fs::write(path, &generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(path).unwrap();
+        assert!(
+            committed == generated,
+            "{path} is out of date. Run the tests with UPDATE_TYPESCRIPT=1 to regenerate it."
+        );
+    }
+}
@@ -0,0 +1,318 @@
+//! Export of a diplomatic edition to LaTeX with [reledmac](https://ctan.org/pkg/reledmac).
+//!
+//! Every column is a numbered section with one paragraph per line, numbered with the line
+//! numbers of the manuscript, so lines lost in a lacuna are skipped in the numbering as well.
+//! The text is rendered diplomatically, following the Leiden conventions:
+//!
+//! - [`Uncertain`](normalized::Uncertain) letters get a dot below
+//! - a [`Lacuna`](normalized::Lacuna) is printed as `[...]` with one dot per missing character
+//! - an [`Abbreviation`](normalized::Abbreviation) is printed as written
+//! - a [`Correction`](normalized::Correction) is printed in its last version, with all versions
+//!   and their hands in a critical footnote
+//! - verse anchors become side notes
+//!
+//! Text is set in the [`polyglossia`](https://ctan.org/pkg/polyglossia) language of its `lang`.
+//! The document has to be compiled with XeLaTeX or LuaLaTeX, and fonts covering all scripts have
+//! to be added to the preamble.
+
+use std::collections::BTreeSet;
+
+use crate::{contents::VerseId, normalized};
+
+/// The `polyglossia` language for the BCP 47 tag `lang`, if there is one.
+#[must_use]
+pub fn polyglossia_language(lang: &str) -> Option<&'static str> {
+    let mut subtags = lang.split('-');
+    let primary = subtags.next().unwrap_or_default().to_lowercase();
+    match primary.as_str() {
+        "he" | "heb" | "hbo" => return Some("hebrew"),
+        "el" | "ell" | "grc" => return Some("greek"),
+        "la" | "lat" => return Some("latin"),
+        "syc" | "syr" => return Some("syriac"),
+        "ar" | "ara" => return Some("arabic"),
+        "cop" => return Some("coptic"),
+        "en" | "eng" => return Some("english"),
+        "de" | "deu" | "ger" => return Some("german"),
+        "fr" | "fra" | "fre" => return Some("french"),
+        _ => {}
+    }
+    // languages like Aramaic are set in the language of their script
+    subtags.find_map(|s| match s {
+        "Hebr" => Some("hebrew"),
+        "Grek" => Some("greek"),
+        "Syrc" => Some("syriac"),
+        "Latn" => Some("latin"),
+        _ => None,
+    })
+}
+
+/// Push `c` to `res`, escaped if it has a special meaning in LaTeX.
+fn push_escaped(res: &mut String, c: char) {
+    match c {
+        '\\' => res.push_str("\\textbackslash{}"),
+        '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+            res.push('\\');
+            res.push(c);
+        }
+        '^' => res.push_str("\\textasciicircum{}"),
+        '~' => res.push_str("\\textasciitilde{}"),
+        _ => res.push(c),
+    }
+}
+
+/// Escape the characters with a special meaning in LaTeX.
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_escaped(&mut res, c);
+    }
+    res
+}
+
+/// Escape `text` and put a dot below every letter.
+///
+/// Each character is dotted before it is escaped, so escape sequences get a single dot.
+fn dotted(text: &str) -> String {
+    let mut res = String::with_capacity(text.len() * 3);
+    for c in text.chars() {
+        push_escaped(&mut res, c);
+        if !c.is_whitespace() {
+            res.push('\u{0323}');
+        }
+    }
+    res
+}
+
+/// Renders the text of a manuscript, collecting the languages used.
+struct Renderer {
+    /// The `polyglossia` language of the document
+    main_language: &'static str,
+    /// The other `polyglossia` languages used
+    other_languages: BTreeSet<&'static str>,
+}
+impl Renderer {
+    /// `content` (already escaped) set in the language `lang`.
+    fn in_language(&mut self, lang: &str, content: &str) -> String {
+        match polyglossia_language(lang) {
+            Some(l) if l != self.main_language => {
+                self.other_languages.insert(l);
+                format!("\\text{l}{{{content}}}")
+            }
+            _ => content.to_string(),
+        }
+    }
+
+    fn block(&mut self, lang: &str, block: &normalized::InlineBlock) -> String {
+        let lang_of = |l: &Option<String>| l.clone().unwrap_or_else(|| lang.to_string());
+        match block {
+            normalized::InlineBlock::Text(x) => {
+                self.in_language(&lang_of(&x.lang), &escape(&x.content))
+            }
+            normalized::InlineBlock::Uncertain(x) => {
+                self.in_language(&lang_of(&x.lang), &dotted(&x.content))
+            }
+            normalized::InlineBlock::Abbreviation(x) => {
+                let lang = x.surface.lang.clone().unwrap_or_else(|| lang_of(&x.lang));
+                self.in_language(&lang, &escape(&x.surface.content))
+            }
+            normalized::InlineBlock::Lacuna(x) => match x.unit {
+                normalized::ExtentUnit::Character => {
+                    format!("[{}]", ".".repeat(usize::try_from(x.n).unwrap_or_default()))
+                }
+                normalized::ExtentUnit::Line | normalized::ExtentUnit::Column => {
+                    "[\\ldots]".to_string()
+                }
+            },
+            normalized::InlineBlock::Space(x) => match x.unit {
+                normalized::ExtentUnit::Character => format!("\\hspace{{{}em}}", x.quantity),
+                normalized::ExtentUnit::Line | normalized::ExtentUnit::Column => String::new(),
+            },
+            normalized::InlineBlock::Anchor(x) => match VerseId::from_anchor_id(&x.anchor_id) {
+                Some((_, verse)) => format!(
+                    "\\ledsidenote{{{} {}:{}}}",
+                    escape(&verse.book),
+                    verse.chapter,
+                    verse.verse
+                ),
+                None => String::new(),
+            },
+            normalized::InlineBlock::Correction(x) => {
+                let lang = lang_of(&x.lang);
+                let versions = x
+                    .versions
+                    .iter()
+                    .map(|v| {
+                        let text = self.in_language(
+                            &v.lang.clone().unwrap_or_else(|| lang.clone()),
+                            &escape(&v.content),
+                        );
+                        match v.hand.as_deref() {
+                            Some(hand) => format!("{text} \\textit{{{}}}", escape(hand)),
+                            None => text,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let lemma = x.versions.last().map_or_else(String::new, |v| {
+                    self.in_language(
+                        &v.lang.clone().unwrap_or_else(|| lang.clone()),
+                        &escape(&v.content),
+                    )
+                });
+                format!(
+                    "\\edtext{{{lemma}}}{{\\Afootnote{{{}}}}}",
+                    versions.join("; ")
+                )
+            }
+        }
+    }
+
+    fn line(&mut self, lang: &str, line: &normalized::Line) -> String {
+        let lang = line.lang.as_deref().unwrap_or(lang);
+        let blocks = line
+            .blocks
+            .iter()
+            .map(|b| self.block(lang, b))
+            .filter(|b| !b.is_empty())
+            .collect::<Vec<_>>();
+        format!(
+            "\\pstart\n\\setline{{{}}}\n{}\n\\pend\n",
+            line.n,
+            blocks.join(" ")
+        )
+    }
+}
+
+/// Export `ms` as a LaTeX document with a diplomatic edition of its text.
+#[must_use]
+pub fn to_latex(ms: &normalized::Manuscript) -> String {
+    let mut renderer = Renderer {
+        main_language: polyglossia_language(&ms.text.lang).unwrap_or("english"),
+        other_languages: BTreeSet::new(),
+    };
+    let mut body = String::new();
+    for page in &ms.text.pages {
+        body.push_str(&format!("\\section*{{{}}}\n", escape(&page.n)));
+        if page.is_missing() {
+            body.push_str("[missing]\n");
+        }
+        let lang = page.lang.as_deref().unwrap_or(&ms.text.lang);
+        for column in &page.columns {
+            if page.columns.len() > 1 {
+                body.push_str(&format!("\\subsection*{{Column {}}}\n", column.n));
+            }
+            let lang = column.lang.as_deref().unwrap_or(lang);
+            body.push_str("\\beginnumbering\n");
+            for line in &column.lines {
+                body.push_str(&renderer.line(lang, line));
+            }
+            body.push_str("\\endnumbering\n");
+        }
+    }
+
+    let mut res = String::from(
+        "\\documentclass{article}\n\\usepackage{fontspec}\n\\usepackage{polyglossia}\n",
+    );
+    res.push_str(&format!(
+        "\\setmainlanguage{{{}}}\n",
+        renderer.main_language
+    ));
+    if !renderer.other_languages.is_empty() {
+        res.push_str(&format!(
+            "\\setotherlanguages{{{}}}\n",
+            renderer
+                .other_languages
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+    }
+    res.push_str("\\usepackage{reledmac}\n\\firstlinenum{1}\n\\linenumincrement{1}\n");
+    res.push_str(&format!("\\title{{{}}}\n", escape(&ms.meta.title)));
+    res.push_str("\\begin{document}\n\\maketitle\n");
+    res.push_str(&body);
+    res.push_str("\\end{document}\n");
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(path: &str) -> normalized::Manuscript {
+        let xml = std::fs::read_to_string(path).unwrap();
+        let tei: crate::schema::Tei = quick_xml::de::from_str(&xml).unwrap();
+        tei.try_into().unwrap()
+    }
+
+    #[test]
+    fn languages() {
+        assert_eq!(polyglossia_language("hbo-Hebr"), Some("hebrew"));
+        assert_eq!(polyglossia_language("hbo-Hebr-x-babli"), Some("hebrew"));
+        assert_eq!(polyglossia_language("grc"), Some("greek"));
+        assert_eq!(polyglossia_language("arc-Hebr"), Some("hebrew"));
+        assert_eq!(polyglossia_language("xyz"), None);
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape("50% & {x}_1"), "50\\% \\& \\{x\\}\\_1");
+        assert_eq!(dotted("ab c"), "a\u{0323}b\u{0323} c\u{0323}");
+        assert_eq!(
+            dotted("a%\\"),
+            "a\u{0323}\\%\u{0323}\\textbackslash{}\u{0323}"
+        );
+    }
+
+    #[test]
+    fn diplomatic_edition() {
+        let mut ms = ms("examples/02_lines_consistent.xml");
+        let line = &mut ms.text.pages[0].columns[0].lines[0];
+        line.blocks = vec![
+            normalized::InlineBlock::Anchor(normalized::Anchor {
+                anchor_id: "A_V_MT_Gen-1-1".to_string(),
+                anchor_type: "Masoretic".to_string(),
+            }),
+            normalized::InlineBlock::Text(normalized::Paragraph {
+                lang: None,
+                content: "text".to_string(),
+            }),
+            normalized::InlineBlock::Uncertain(normalized::Uncertain {
+                lang: None,
+                cert: None,
+                agent: "water".to_string(),
+                content: "ab".to_string(),
+            }),
+            normalized::InlineBlock::Lacuna(normalized::Lacuna {
+                reason: "lost".to_string(),
+                unit: normalized::ExtentUnit::Character,
+                n: 3,
+                cert: None,
+            }),
+            normalized::InlineBlock::Correction(normalized::Correction {
+                lang: None,
+                versions: vec![
+                    normalized::Version {
+                        lang: None,
+                        hand: None,
+                        content: "first".to_string(),
+                    },
+                    normalized::Version {
+                        lang: Some("grc".to_string()),
+                        hand: Some("hand2".to_string()),
+                        content: "second".to_string(),
+                    },
+                ],
+            }),
+        ];
+        let latex = to_latex(&ms);
+        assert!(latex.contains("\\setmainlanguage{hebrew}\n\\setotherlanguages{greek}\n"));
+        assert!(latex.contains(concat!(
+            "\\beginnumbering\n\\pstart\n\\setline{1}\n",
+            "\\ledsidenote{Gen 1:1} text a\u{0323}b\u{0323} [...] ",
+            "\\edtext{\\textgreek{second}}{\\Afootnote{first; \\textgreek{second} \\textit{hand2}}}\n",
+            "\\pend\n\\pstart\n\\setline{3}\n"
+        )));
+        assert!(latex.ends_with("\\endnumbering\n\\end{document}\n"));
+    }
+}
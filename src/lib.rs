@@ -2,14 +2,33 @@
 //! [critic](https://github.com/curatorsigma/critic).
 
 use denorm::NormalizationError;
-use destream::{StreamError, transform_until_page_end};
+use destream::StreamError;
+use limits::{LimitError, Limits, Root};
 use streamed::Manuscript;
+use vocabulary::Vocabulary;
+pub mod bible;
+pub mod cache;
+pub mod contents;
 pub mod denorm;
 pub mod destream;
+pub mod ground_truth;
+pub mod htr;
+pub mod igntp;
+pub mod iiif;
+pub mod json;
+pub mod latex;
+pub mod limits;
+pub mod milestone;
 pub mod normalized;
+pub mod recovery;
 pub mod schema;
 pub mod streamed;
 pub mod surface_form;
+pub mod vocabulary;
+pub mod wlc;
+
+#[cfg(test)]
+mod fuzz;
 
 /// The problems that can occur when converting XML to the internal formats.
 #[derive(Debug)]
@@ -32,6 +51,8 @@ pub enum ConversionError {
     /// This indicates an ill-formed XML file.
     /// It may adhere to the formal RNG schema, but not the actual TEI subspec.
     DeSer(quick_xml::DeError),
+    /// The input or output exceeded the [`Options::limits`].
+    Limit(LimitError),
 }
 impl core::fmt::Display for ConversionError {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
@@ -54,25 +75,93 @@ impl core::fmt::Display for ConversionError {
             Self::DeSer(e) => {
                 write!(f, "Unable to deserialize the given Data: {e}")
             }
+            Self::Limit(e) => {
+                write!(f, "The given Data is too large: {e}")
+            }
         }
     }
 }
 impl core::error::Error for ConversionError {}
 
+/// Options for reading and writing XML.
+///
+/// The defaults check damage agents and gap reasons against the [`Vocabulary::default`] and read
+/// and write everything else the spec allows.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Options {
+    /// Check damage agents and gap reasons against this vocabulary, both when reading and when
+    /// writing.
+    ///
+    /// Defaults to the [`Vocabulary::default`]. When `None`, any term is accepted. Terms are still
+    /// normalized, see [`normalize_term`](vocabulary::normalize_term).
+    pub vocabulary: Option<Vocabulary>,
+    /// Check the hands of corrections on a single page against these ids (without `#`), both when
+    /// reading and when writing.
+    ///
+    /// A single page has no header to declare its hands in, so pass the ids of the
+    /// [`hands`](normalized::Meta::hands) of its manuscript here. When `None`, any hand is
+    /// accepted. Whole manuscripts are always checked against their header.
+    pub hands: Option<Vec<String>>,
+    /// Reject XML exceeding these limits, both when reading and when writing.
+    ///
+    /// Set this for untrusted input: at most [`Limits::max_bytes`] are read, and the other limits
+    /// are checked before anything is deserialized. When `None`, input of any size is accepted.
+    pub limits: Option<Limits>,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            vocabulary: Some(Vocabulary::default()),
+            hands: None,
+            limits: None,
+        }
+    }
+}
+impl Options {
+    /// Make sure that every hand used in a correction in `pages` is in [`Self::hands`].
+    fn check_hands(&self, pages: &[normalized::Page]) -> Result<(), NormalizationError> {
+        match &self.hands {
+            Some(hands) => denorm::check_hands(pages, |hand| {
+                let hand = hand.strip_prefix('#').unwrap_or(hand);
+                hands.iter().any(|h| h == hand)
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
 /// Directly Convert a Manuscript to XML.
 ///
 /// # Errors
 /// Can only be [`DeStream`](ConversionError::DeStream), [`DeNorm`](ConversionError::DeNorm) and
 /// [`Ser`](ConversionError::Ser) variants.
 pub fn to_xml(ms: crate::streamed::Manuscript) -> Result<String, ConversionError> {
+    to_xml_with_options(ms, &Options::default())
+}
+
+/// Like [`to_xml`], but with the given [`Options`].
+///
+/// # Errors
+/// Can only be [`DeStream`](ConversionError::DeStream), [`DeNorm`](ConversionError::DeNorm),
+/// [`Ser`](ConversionError::Ser) and [`Limit`](ConversionError::Limit) variants.
+pub fn to_xml_with_options(
+    ms: crate::streamed::Manuscript,
+    options: &Options,
+) -> Result<String, ConversionError> {
     let destreamed: crate::normalized::Manuscript =
         ms.try_into().map_err(ConversionError::DeStream)?;
+    if let Some(vocabulary) = &options.vocabulary {
+        denorm::check_vocabulary(&destreamed.text.pages, vocabulary)
+            .map_err(ConversionError::DeNorm)?;
+    }
     let denormed: crate::schema::Tei = destreamed.try_into().map_err(ConversionError::DeNorm)?;
     let sr = quick_xml::se::to_string_with_root("TEI", &denormed).map_err(ConversionError::Ser)?;
-    Ok(format!(
+    let xml = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <?xml-model href="https://raw.githubusercontent.com/tanakhcc/critic-format/refs/heads/master/tei_critic.rng" schematypens="http://relaxng.org/ns/structure/1.0" type="application/xml"?>{sr}"#
-    ))
+    );
+    limits::check_written(&xml, options.limits.as_ref(), Root::Tei)?;
+    Ok(xml)
 }
 
 /// Directly Convert a [`Vec<Block`] into an XML string representing a single page.
@@ -84,14 +173,45 @@ pub fn to_xml(ms: crate::streamed::Manuscript) -> Result<String, ConversionError
 /// [`Ser`](ConversionError::Ser) variants.
 /// Note that any excess blocks after the first pagebreak are discarded.
 /// In particular, the first pagebreak giving this pages name MUST NOT be present
+///
+/// No workflow status and no `@facs` is written; use [`page_to_xml_with_options`] for those.
 pub fn page_to_xml(
     blocks: impl IntoIterator<Item = streamed::Block>,
     pagename: String,
 ) -> Result<String, ConversionError> {
-    let (page, _next_name) = transform_until_page_end(&mut blocks.into_iter(), pagename)
-        .map_err(ConversionError::DeStream)?;
+    let page = streamed::Page {
+        name: pagename,
+        status: None,
+        blocks: blocks.into_iter().collect(),
+        facs: vec![],
+    };
+    page_to_xml_with_options(page, &Options::default())
+}
+
+/// Like [`page_to_xml`], but also write the workflow status and the links to the facsimile of the
+/// page and use the given [`Options`].
+///
+/// # Errors
+/// Can only be [`DeStream`](ConversionError::DeStream), [`DeNorm`](ConversionError::DeNorm),
+/// [`Ser`](ConversionError::Ser) and [`Limit`](ConversionError::Limit) variants.
+/// A link pointing to a column or line that is not on the page is a
+/// [`DeStream`](ConversionError::DeStream) error.
+pub fn page_to_xml_with_options(
+    page: streamed::Page,
+    options: &Options,
+) -> Result<String, ConversionError> {
+    let page: normalized::Page = page.try_into().map_err(ConversionError::DeStream)?;
+    if let Some(vocabulary) = &options.vocabulary {
+        denorm::check_vocabulary(core::slice::from_ref(&page), vocabulary)
+            .map_err(ConversionError::DeNorm)?;
+    }
+    options
+        .check_hands(core::slice::from_ref(&page))
+        .map_err(ConversionError::DeNorm)?;
     let denormed: crate::schema::Page = page.try_into().map_err(ConversionError::DeNorm)?;
-    quick_xml::se::to_string_with_root("div", &denormed).map_err(ConversionError::Ser)
+    let xml = quick_xml::se::to_string_with_root("div", &denormed).map_err(ConversionError::Ser)?;
+    limits::check_written(&xml, options.limits.as_ref(), Root::Page)?;
+    Ok(xml)
 }
 
 /// Directly Convert from a [`BufRead`](std::io::BufRead) over XML data to a streamed Manuscript.
@@ -102,9 +222,23 @@ pub fn page_to_xml(
 /// Can only be [`Stream`](ConversionError::Stream), [`Norm`](ConversionError::Norm) and
 /// [`DeSer`](ConversionError::DeSer) variants.
 pub fn from_xml(buf_reader: impl std::io::BufRead) -> Result<Manuscript, ConversionError> {
+    from_xml_with_options(buf_reader, &Options::default())
+}
+
+/// Like [`from_xml`], but with the given [`Options`].
+///
+/// # Errors
+/// Can only be [`Stream`](ConversionError::Stream), [`Norm`](ConversionError::Norm),
+/// [`DeSer`](ConversionError::DeSer) and [`Limit`](ConversionError::Limit) variants.
+pub fn from_xml_with_options(
+    buf_reader: impl std::io::BufRead,
+    options: &Options,
+) -> Result<Manuscript, ConversionError> {
     let ds: crate::schema::Tei =
-        quick_xml::de::from_reader(buf_reader).map_err(ConversionError::DeSer)?;
-    let normalized: crate::normalized::Manuscript = ds.try_into().map_err(ConversionError::Norm)?;
+        limits::deserialize(buf_reader, options.limits.as_ref(), Root::Tei)?;
+    let normalized: crate::normalized::Manuscript = (ds, options.vocabulary.as_ref())
+        .try_into()
+        .map_err(ConversionError::Norm)?;
     normalized.try_into().map_err(ConversionError::Stream)
 }
 
@@ -116,7 +250,8 @@ pub fn from_xml(buf_reader: impl std::io::BufRead) -> Result<Manuscript, Convers
 /// [`Page`](normalized::Page).
 ///
 /// Note that a leading pagrebreak giving this pages name is stripped and the pagename returned
-/// separately.
+/// separately. The workflow status and any `@facs` on the page, its columns and lines are
+/// discarded; use [`page_from_xml_with_options`] to keep them.
 ///
 /// # Errors
 /// Can only be [`Stream`](ConversionError::Stream), [`Norm`](ConversionError::Norm) and
@@ -125,20 +260,39 @@ pub fn page_from_xml(
     buf_reader: impl std::io::BufRead,
     default_language: &str,
 ) -> Result<(Vec<streamed::Block>, String), ConversionError> {
+    page_from_xml_with_options(buf_reader, default_language, &Options::default())
+        .map(|page| (page.blocks, page.name))
+}
+
+/// Like [`page_from_xml`], but return the whole [`Page`](streamed::Page), including its workflow
+/// status and links to the facsimile, and use the given [`Options`].
+///
+/// # Errors
+/// Can only be [`Stream`](ConversionError::Stream), [`Norm`](ConversionError::Norm),
+/// [`DeSer`](ConversionError::DeSer) and [`Limit`](ConversionError::Limit) variants.
+pub fn page_from_xml_with_options(
+    buf_reader: impl std::io::BufRead,
+    default_language: &str,
+    options: &Options,
+) -> Result<streamed::Page, ConversionError> {
     let ds: crate::schema::Page =
-        quick_xml::de::from_reader(buf_reader).map_err(ConversionError::DeSer)?;
-    let normalized: crate::normalized::Page = ds.try_into().map_err(ConversionError::Norm)?;
-    let mut streamed = normalized.into_streamed(default_language);
-    let Some(Ok(streamed::Block::Break(streamed::BreakType::Page(pagename)))) = streamed.next()
-    else {
-        unreachable!("into_streamed always returns at least the initial page break without error");
-    };
-    Ok((
-        streamed
+        limits::deserialize(buf_reader, options.limits.as_ref(), Root::Page)?;
+    let normalized: crate::normalized::Page = (ds, options.vocabulary.as_ref())
+        .try_into()
+        .map_err(ConversionError::Norm)?;
+    options
+        .check_hands(core::slice::from_ref(&normalized))
+        .map_err(ConversionError::Norm)?;
+    let facs = destream::facs_links(core::slice::from_ref(&normalized));
+    let ((name, status), streamed) = normalized.into_streamed_content(default_language);
+    Ok(streamed::Page {
+        name,
+        status,
+        blocks: streamed
             .collect::<Result<Vec<_>, _>>()
             .map_err(ConversionError::Stream)?,
-        pagename,
-    ))
+        facs,
+    })
 }
 
 #[cfg(test)]
@@ -164,6 +318,326 @@ mod test {
         assert_eq!(ms, ms_again);
     }
 
+    #[test]
+    fn missing_pages_from_to_xml() {
+        let xml = std::fs::File::open("examples/09_missing_pages.xml").unwrap();
+        let ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        assert_eq!(ms.missing_pages(), vec!["01_v", "02_r", "03_r"]);
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+    }
+
+    #[test]
+    fn derived_contents_to_xml() {
+        let xml = std::fs::File::open("examples/07_multi-page.xml").unwrap();
+        let mut ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        ms.derive_contents();
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        assert!(xml_again.contains(
+            r#"<msContents><msItem><locus from="34_v" to="34_v"/><title type="Masoretic">1Kg 3:4</title></msItem>"#
+        ));
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms_again.meta.contents.len(), 2);
+        assert_eq!(ms, ms_again);
+    }
+
+    #[test]
+    fn replace_page_records_change() {
+        let xml = std::fs::File::open("examples/07_multi-page.xml").unwrap();
+        let mut ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        let page = r#"<div type="page" n="35_r"><div type="column"><div type="line"><p>new text</p></div></div></div>"#;
+        let page =
+            super::page_from_xml_with_options(page.as_bytes(), "grc", &super::Options::default())
+                .unwrap();
+        let blocks = page.blocks.clone();
+        let change = crate::streamed::Change {
+            who: Some("jdoe".to_string()),
+            when: "2025-03-01".parse().ok(),
+            status: Some(crate::streamed::PageStatus::Transcribed),
+            page: None,
+            description: None,
+        };
+        let old = ms.replace_page(page.clone(), change.clone());
+        assert!(old.is_ok_and(|o| !o.blocks.is_empty()));
+        let missing = crate::streamed::Page {
+            name: "99_r".to_string(),
+            ..page.clone()
+        };
+        assert_eq!(
+            ms.replace_page(missing, change.clone()),
+            Err(crate::streamed::ReplacePageError::NoSuchPage(
+                "99_r".to_string()
+            ))
+        );
+        let with_break = vec![
+            blocks[0].clone(),
+            crate::streamed::Block::Break(crate::streamed::BreakType::Page(
+                "35_v".to_string(),
+                None,
+            )),
+        ];
+        let with_break = crate::streamed::Page {
+            blocks: with_break,
+            ..page
+        };
+        assert_eq!(
+            ms.replace_page(with_break, change),
+            Err(crate::streamed::ReplacePageError::PageBreak(1))
+        );
+        assert_eq!(ms.content.last(), blocks.last());
+
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+        let history = ms_again.meta.changes_for_page("35_r").collect::<Vec<_>>();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].who.as_deref(), Some("jdoe"));
+        assert_eq!(ms_again.meta.changes_for_page("34_v").count(), 0);
+    }
+
+    #[test]
+    fn page_status_from_to_xml() {
+        let xml = r#"<div type="page" n="page1" status="reviewed"><div type="column"><div type="line"><p>text</p></div></div></div>"#;
+        let page =
+            super::page_from_xml_with_options(xml.as_bytes(), "grc", &super::Options::default())
+                .unwrap();
+        assert_eq!(page.status, Some(crate::streamed::PageStatus::Reviewed));
+        let xml_again = super::page_to_xml_with_options(page, &super::Options::default()).unwrap();
+        assert!(xml_again.contains(r#"n="page1" status="reviewed""#));
+        let invalid = xml.replace("reviewed", "finished");
+        assert!(matches!(
+            super::page_from_xml(invalid.as_bytes(), "grc"),
+            Err(super::ConversionError::DeSer(_))
+        ));
+    }
+
+    #[test]
+    fn status_counts() {
+        let xml = include_str!("../examples/09_missing_pages.xml")
+            .replacen(r#"n="01_r""#, r#"n="01_r" status="approved""#, 1)
+            .replacen(r#"n="02_v""#, r#"n="02_v" status="draft""#, 1);
+        let mut ms = super::from_xml(xml.as_bytes()).unwrap();
+        let counts = ms.status_counts();
+        assert_eq!(counts.approved, 1);
+        assert_eq!(counts.draft, 1);
+        assert_eq!(counts.unspecified, 3);
+        assert_eq!(counts.total(), 5);
+
+        assert!(ms.set_page_status("02_v", Some(crate::streamed::PageStatus::Transcribed)));
+        assert!(!ms.set_page_status("99_r", None));
+        let normalized: crate::normalized::Manuscript = ms.clone().try_into().unwrap();
+        assert_eq!(normalized.status_counts(), ms.status_counts());
+        assert_eq!(normalized.status_counts().transcribed, 1);
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+    }
+
+    #[test]
+    fn missing_page_from_to_xml() {
+        let xml = r#"<div type="page" n="page1"/>"#;
+        let (blocks, pagename) = super::page_from_xml(xml.as_bytes(), "grc").unwrap();
+        assert!(blocks.is_empty());
+        assert_eq!(pagename, "page1");
+        let xml_again = super::page_to_xml(blocks, pagename).unwrap();
+        assert_eq!(xml_again, xml);
+    }
+
+    #[test]
+    fn page_vocabulary() {
+        let xml = r#"<div type="page" n="page1"><div type="column"><div type="line"><p><damage agent="smeared diacritica">d</damage></p><gap reason="eaten by dog" unit="character" n="2"/></div></div></div>"#;
+        assert!(matches!(
+            super::page_from_xml(xml.as_bytes(), "grc"),
+            Err(super::ConversionError::Norm(
+                crate::denorm::NormalizationError::UnknownGapReason(_)
+            ))
+        ));
+        let lenient = super::Options {
+            vocabulary: None,
+            ..Default::default()
+        };
+        assert!(super::page_from_xml_with_options(xml.as_bytes(), "grc", &lenient).is_ok());
+
+        let options = super::Options {
+            vocabulary: Some(super::Vocabulary {
+                gap_reasons: vec!["eaten-by-dog".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let page = super::page_from_xml_with_options(xml.as_bytes(), "grc", &options).unwrap();
+        assert_eq!(
+            page.blocks,
+            vec![
+                crate::streamed::Block::Uncertain(crate::streamed::Uncertain {
+                    lang: "grc".to_string(),
+                    cert: None,
+                    agent: "smeared-diacritica".to_string(),
+                    content: "d".to_string(),
+                }),
+                crate::streamed::Block::Lacuna(crate::streamed::Lacuna {
+                    reason: "eaten-by-dog".to_string(),
+                    unit: crate::streamed::ExtentUnit::Character,
+                    n: 2,
+                    cert: None,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn vocabulary_to_xml() {
+        let xml = std::fs::read_to_string("examples/07_multi-page.xml")
+            .unwrap()
+            .replace(r#"reason="smeared""#, r#"reason="smeared ink""#);
+        assert!(matches!(
+            super::from_xml(xml.as_bytes()),
+            Err(super::ConversionError::Norm(
+                crate::denorm::NormalizationError::UnknownGapReason(reason)
+            )) if reason == "smeared ink"
+        ));
+        let lenient = super::Options {
+            vocabulary: None,
+            ..Default::default()
+        };
+        let ms = super::from_xml_with_options(xml.as_bytes(), &lenient).unwrap();
+        assert!(super::to_xml_with_options(ms.clone(), &lenient).is_ok());
+        assert!(matches!(
+            super::to_xml(ms),
+            Err(super::ConversionError::DeNorm(
+                crate::denorm::NormalizationError::UnknownGapReason(reason)
+            )) if reason == "smeared-ink"
+        ));
+    }
+
+    #[test]
+    fn undeclared_hand_to_xml() {
+        let xml = std::fs::File::open("examples/10_hand_notes.xml").unwrap();
+        let mut ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        let version = ms
+            .content
+            .iter_mut()
+            .find_map(|b| match b {
+                crate::streamed::Block::Correction(c) => c.versions.first_mut(),
+                _ => None,
+            })
+            .unwrap();
+        version.hand = Some("hand3".to_string());
+        assert!(matches!(
+            super::to_xml(ms),
+            Err(super::ConversionError::DeNorm(
+                crate::denorm::NormalizationError::UndeclaredHand(hand)
+            )) if hand == "hand3"
+        ));
+    }
+
+    #[test]
+    fn page_hands() {
+        let xml = r##"<div type="page" n="1r"><div type="column"><div type="line"><app><rdg hand="#hand1" varSeq="1">a</rdg><rdg hand="#hand2" varSeq="2">b</rdg></app></div></div></div>"##;
+        let declared = |hands: &[&str]| super::Options {
+            hands: Some(hands.iter().map(std::string::ToString::to_string).collect()),
+            ..Default::default()
+        };
+        let page = super::page_from_xml_with_options(
+            xml.as_bytes(),
+            "grc",
+            &declared(&["hand1", "hand2"]),
+        )
+        .unwrap();
+        assert!(matches!(
+            super::page_from_xml_with_options(xml.as_bytes(), "grc", &declared(&["hand1"])),
+            Err(super::ConversionError::Norm(
+                crate::denorm::NormalizationError::UndeclaredHand(hand)
+            )) if hand == "#hand2"
+        ));
+        assert!(
+            super::page_to_xml_with_options(page.clone(), &declared(&["hand1", "hand2"])).is_ok()
+        );
+        assert!(matches!(
+            super::page_to_xml_with_options(page, &declared(&["hand2"])),
+            Err(super::ConversionError::DeNorm(
+                crate::denorm::NormalizationError::UndeclaredHand(hand)
+            )) if hand == "#hand1"
+        ));
+    }
+
+    #[test]
+    fn facs_from_to_xml() {
+        let xml = std::fs::File::open("examples/14_facsimile.xml").unwrap();
+        let ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        // page break, text, lacuna, text, page break, text
+        assert_eq!(
+            crate::streamed::position_of(&ms.content, 3),
+            Some(crate::streamed::Position {
+                page: "01_r".to_string(),
+                column: 1,
+                line: 3,
+            })
+        );
+        assert_eq!(ms.facs_at(1), Some("#z_01_r_c1_l1"));
+        assert_eq!(ms.facs_at(2), Some("#z_01_r_c1_l1"));
+        assert_eq!(ms.facs_at(3), Some("#z_01_r_c1_l3"));
+        assert_eq!(ms.facs_at(5), Some("#s_01_v"));
+        assert_eq!(ms.facs_at(6), None);
+        let (_, zone) = ms.facsimile.zone(ms.facs_at(3).unwrap()).unwrap();
+        assert_eq!(zone.bounding_box(), Some((100, 480, 910, 600)));
+
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+
+        let mut dangling = ms;
+        dangling.facs[0].page = "99_r".to_string();
+        assert!(matches!(
+            super::to_xml(dangling),
+            Err(super::ConversionError::DeStream(
+                crate::destream::StreamError::FacsPositionMissing(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn replace_page_keeps_facs() {
+        let xml = std::fs::File::open("examples/14_facsimile.xml").unwrap();
+        let mut ms = super::from_xml(std::io::BufReader::new(xml)).unwrap();
+        let xml = r##"<div type="page" n="01_r" facs="#s_01_r"><div type="column"><div type="line" facs="#z_01_r_c1_l1"><p>new text</p></div></div></div>"##;
+        let page = super::page_from_xml_with_options(
+            xml.as_bytes(),
+            "hbo-Hebr",
+            &super::Options::default(),
+        )
+        .unwrap();
+        assert_eq!(page.facs.len(), 2);
+        let change = crate::streamed::Change {
+            who: None,
+            when: None,
+            status: None,
+            page: None,
+            description: None,
+        };
+        let xml_again =
+            super::page_to_xml_with_options(page.clone(), &super::Options::default()).unwrap();
+        assert!(xml_again.contains(r##"facs="#z_01_r_c1_l1""##));
+
+        let mut dangling = page.clone();
+        dangling.facs[1].line = Some(3);
+        assert!(matches!(
+            ms.clone().replace_page(dangling, change.clone()),
+            Err(crate::streamed::ReplacePageError::Invalid(
+                crate::destream::StreamError::FacsPositionMissing(_)
+            ))
+        ));
+
+        let old = ms.replace_page(page, change).unwrap();
+        assert_eq!(old.facs.len(), 4);
+        assert_eq!(ms.facs.iter().filter(|l| l.page == "01_r").count(), 2);
+        assert_eq!(ms.facs_at(1), Some("#z_01_r_c1_l1"));
+        let xml_again = super::to_xml(ms.clone()).unwrap();
+        let ms_again = super::from_xml(xml_again.as_bytes()).unwrap();
+        assert_eq!(ms, ms_again);
+    }
+
     #[test]
     fn page_to_xml() {
         let blocks = vec![crate::streamed::Block::Text(crate::streamed::Paragraph {
@@ -183,4 +657,62 @@ mod test {
         let res = page_from_xml(xml.as_bytes(), "hbo");
         assert!(dbg!(res).is_ok());
     }
+
+    /// Random block sequences are converted to XML and back, located and exported to IIIF
+    /// without panicking.
+    #[test]
+    fn fuzz_blocks_to_from_xml() {
+        let xml = std::fs::read_to_string("examples/02_lines_consistent.xml").unwrap();
+        let template = super::from_xml(xml.as_bytes()).unwrap();
+        let mut rng = crate::fuzz::Rng(0x00c0_ffee);
+        for _ in 0..500 {
+            let blocks = rng.blocks(30);
+            for idx in 0..=blocks.len() {
+                let _ = crate::streamed::position_of(&blocks, idx);
+            }
+            // most random sequences do not start with a page and are never normalized
+            let mut ms = template.clone();
+            ms.content = core::iter::once(crate::streamed::Block::Break(
+                crate::streamed::BreakType::Page(rng.string(), None),
+            ))
+            .chain(blocks.iter().cloned())
+            .collect();
+            if let Ok(mut normalized) = crate::normalized::Manuscript::try_from(ms) {
+                normalized.facsimile = rng.facsimile();
+                for page in &mut normalized.text.pages {
+                    page.facs = rng.facs(&normalized.facsimile);
+                    for line in page.columns.iter_mut().flat_map(|c| c.lines.iter_mut()) {
+                        line.facs = rng.facs(&normalized.facsimile);
+                    }
+                }
+                let _ = crate::iiif::manifest(&normalized, &rng.string());
+            }
+            if let Ok(page) = super::page_to_xml(blocks.clone(), rng.string()) {
+                let _ = page_from_xml(page.as_bytes(), "grc");
+            }
+            let mut ms = template.clone();
+            ms.content = blocks;
+            if let Ok(xml) = super::to_xml(ms) {
+                let _ = super::from_xml(xml.as_bytes());
+            }
+        }
+    }
+
+    /// Corrupted XML is rejected or read, but never panics.
+    #[test]
+    fn fuzz_corrupted_xml() {
+        let mut rng = crate::fuzz::Rng(0xdead_beef);
+        for file in ["examples/08_single_page.xml", "examples/14_facsimile.xml"] {
+            let xml = std::fs::read(file).unwrap();
+            for _ in 0..300 {
+                let mut corrupted = xml.clone();
+                for _ in 0..=rng.below(4) {
+                    let i = rng.below(corrupted.len() as u64) as usize;
+                    corrupted[i] = b"<>/\"1a- "[rng.below(8) as usize];
+                }
+                let _ = super::from_xml(corrupted.as_slice());
+                let _ = page_from_xml(corrupted.as_slice(), "grc");
+            }
+        }
+    }
 }
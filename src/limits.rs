@@ -0,0 +1,519 @@
+//! Limits on the size of untrusted input.
+//!
+//! [`from_xml`](crate::from_xml) allocates for as many pages, blocks and readings as the file
+//! contains. When reading uploads, set [`Options::limits`](crate::Options::limits): the functions
+//! reading XML then read at most [`Limits::max_bytes`] and check the structure of the XML in a
+//! single pass over its events, before anything is deserialized. This includes
+//! [`recovery::from_xml_with_options`](crate::recovery::from_xml_with_options).
+//!
+//! The functions writing XML check their output against the same limits, so that everything they
+//! write can be read again with the same [`Options`](crate::Options).
+
+use quick_xml::events::{BytesRef, BytesStart, Event};
+use serde::de::DeserializeOwned;
+
+/// The maximum sizes accepted when reading XML.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Limits {
+    /// The size of the whole file
+    pub max_bytes: usize,
+    /// The number of pages in a manuscript
+    pub max_pages: usize,
+    /// The number of blocks (`<p>`, `<gap/>`, `<app>`, ...) directly in one line
+    pub max_blocks_per_line: usize,
+    /// The number of `<rdg>` in one `<app>`
+    pub max_versions_per_correction: usize,
+    /// The length of the text between two tags, or of a single attribute value, in bytes
+    pub max_string_length: usize,
+    /// The extent of a `<gap>` in `@n` or of a `<space>` in `@quantity`
+    pub max_extent: usize,
+}
+impl Default for Limits {
+    /// Generous limits for manuscripts transcribed by hand.
+    fn default() -> Self {
+        Self {
+            max_bytes: 32 * 1024 * 1024,
+            max_pages: 10_000,
+            max_blocks_per_line: 1_000,
+            max_versions_per_correction: 100,
+            max_string_length: 64 * 1024,
+            max_extent: 10_000,
+        }
+    }
+}
+impl Limits {
+    /// No limits at all.
+    pub const UNLIMITED: Self = Self {
+        max_bytes: usize::MAX,
+        max_pages: usize::MAX,
+        max_blocks_per_line: usize::MAX,
+        max_versions_per_correction: usize::MAX,
+        max_string_length: usize::MAX,
+        max_extent: usize::MAX,
+    };
+}
+
+/// A limit that was exceeded. Each variant contains the limit.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LimitError {
+    /// The file is larger than [`Limits::max_bytes`]
+    TooManyBytes(usize),
+    /// There are more pages than [`Limits::max_pages`]
+    TooManyPages(usize),
+    /// A line has more blocks than [`Limits::max_blocks_per_line`]
+    TooManyBlocks(usize),
+    /// A correction has more versions than [`Limits::max_versions_per_correction`]
+    TooManyVersions(usize),
+    /// A text or attribute value is longer than [`Limits::max_string_length`]
+    StringTooLong(usize),
+    /// A gap or space is larger than [`Limits::max_extent`]
+    ExtentTooLarge(usize),
+}
+impl core::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::TooManyBytes(x) => {
+                write!(f, "The file is larger than {x} bytes.")
+            }
+            Self::TooManyPages(x) => {
+                write!(f, "There are more than {x} pages.")
+            }
+            Self::TooManyBlocks(x) => {
+                write!(f, "A line contains more than {x} blocks.")
+            }
+            Self::TooManyVersions(x) => {
+                write!(f, "A correction contains more than {x} versions.")
+            }
+            Self::StringTooLong(x) => {
+                write!(f, "A text or attribute is longer than {x} bytes.")
+            }
+            Self::ExtentTooLarge(x) => {
+                write!(f, "A gap or space is larger than {x}.")
+            }
+        }
+    }
+}
+impl core::error::Error for LimitError {}
+
+/// Errors while checking the limits.
+#[derive(Debug)]
+pub(crate) enum CheckError {
+    Limit(LimitError),
+    Io(std::io::Error),
+    Xml(quick_xml::Error),
+}
+impl From<CheckError> for crate::ConversionError {
+    fn from(value: CheckError) -> Self {
+        match value {
+            CheckError::Limit(e) => Self::Limit(e),
+            CheckError::Io(e) => Self::DeSer(quick_xml::Error::from(e).into()),
+            CheckError::Xml(e) => Self::DeSer(e.into()),
+        }
+    }
+}
+
+/// Where the root element is in the structure of the text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Root {
+    /// A whole `<TEI>` document: pages are the `<div>` children of `<body>`
+    Tei,
+    /// A single page `<div>`
+    Page,
+}
+
+/// Deserialize `T` from `buf_reader`, checking the XML against `limits` first if there are any.
+pub(crate) fn deserialize<T: DeserializeOwned>(
+    buf_reader: impl std::io::BufRead,
+    limits: Option<&Limits>,
+    root: Root,
+) -> Result<T, crate::ConversionError> {
+    match limits {
+        Some(limits) => {
+            let bytes = read_checked(buf_reader, limits, root)?;
+            quick_xml::de::from_reader(bytes.as_slice())
+        }
+        None => quick_xml::de::from_reader(buf_reader),
+    }
+    .map_err(crate::ConversionError::DeSer)
+}
+
+/// Check the written `xml` against `limits`, if there are any.
+pub(crate) fn check_written(
+    xml: &str,
+    limits: Option<&Limits>,
+    root: Root,
+) -> Result<(), crate::ConversionError> {
+    match limits {
+        Some(limits) => {
+            if xml.len() > limits.max_bytes {
+                return Err(crate::ConversionError::Limit(LimitError::TooManyBytes(
+                    limits.max_bytes,
+                )));
+            }
+            Ok(check(xml.as_bytes(), limits, root)?)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Read at most [`Limits::max_bytes`] from `buf_reader` and check the XML against `limits`.
+///
+/// Returns the bytes read, ready to be deserialized.
+pub(crate) fn read_checked(
+    buf_reader: impl std::io::BufRead,
+    limits: &Limits,
+    root: Root,
+) -> Result<Vec<u8>, CheckError> {
+    let mut bytes = Vec::new();
+    std::io::Read::read_to_end(
+        &mut std::io::Read::take(
+            buf_reader,
+            u64::try_from(limits.max_bytes)
+                .unwrap_or(u64::MAX)
+                .saturating_add(1),
+        ),
+        &mut bytes,
+    )
+    .map_err(CheckError::Io)?;
+    if bytes.len() > limits.max_bytes {
+        return Err(CheckError::Limit(LimitError::TooManyBytes(
+            limits.max_bytes,
+        )));
+    }
+    check(&bytes, limits, root)?;
+    Ok(bytes)
+}
+
+/// Check the events in `xml` against `limits`.
+fn check(xml: &[u8], limits: &Limits, root: Root) -> Result<(), CheckError> {
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    reader.config_mut().expand_empty_elements = true;
+
+    // the depth of the pages, once known
+    let mut page_depth = match root {
+        Root::Tei => None,
+        Root::Page => Some(1),
+    };
+    // the number of children of every open element
+    let mut children = Vec::<usize>::new();
+    // whether every open element is an <app>
+    let mut is_app = Vec::<bool>::new();
+    let mut pages = 0;
+    // the length of the text since the last tag, which may be split into several events
+    let mut string_length = 0_usize;
+    let exceeded = |e: LimitError| Err(CheckError::Limit(e));
+    loop {
+        match reader.read_event().map_err(CheckError::Xml)? {
+            Event::Start(e) => {
+                string_length = 0;
+                check_attributes(&e, limits)?;
+                let depth = children.len() + 1;
+                if let Some(siblings) = children.last_mut() {
+                    *siblings += 1;
+                    // the children of lines are blocks, the children of <app> are versions
+                    if page_depth.is_some_and(|p| depth == p + 3)
+                        && *siblings > limits.max_blocks_per_line
+                    {
+                        return exceeded(LimitError::TooManyBlocks(limits.max_blocks_per_line));
+                    }
+                    if is_app.last() == Some(&true)
+                        && *siblings > limits.max_versions_per_correction
+                    {
+                        return exceeded(LimitError::TooManyVersions(
+                            limits.max_versions_per_correction,
+                        ));
+                    }
+                }
+                if page_depth == Some(depth) {
+                    pages += 1;
+                    if pages > limits.max_pages {
+                        return exceeded(LimitError::TooManyPages(limits.max_pages));
+                    }
+                }
+                if page_depth.is_none() && e.local_name().as_ref() == b"body" {
+                    page_depth = Some(depth + 1);
+                }
+                children.push(0);
+                is_app.push(e.local_name().as_ref() == b"app");
+            }
+            Event::End(_) => {
+                string_length = 0;
+                children.pop();
+                is_app.pop();
+            }
+            Event::Text(e) => string_length = longer(string_length, e.len(), limits)?,
+            Event::CData(e) => string_length = longer(string_length, e.len(), limits)?,
+            Event::GeneralRef(e) => {
+                string_length = longer(string_length, reference_length(&e), limits)?;
+            }
+            Event::Eof => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// Add `length` bytes to the text of `string_length` bytes, checking the new length.
+fn longer(string_length: usize, length: usize, limits: &Limits) -> Result<usize, CheckError> {
+    let string_length = string_length.saturating_add(length);
+    if string_length > limits.max_string_length {
+        return Err(CheckError::Limit(LimitError::StringTooLong(
+            limits.max_string_length,
+        )));
+    }
+    Ok(string_length)
+}
+
+/// The length in bytes of the text `e` refers to.
+///
+/// Unknown entities are counted with the length of their name; they are rejected when
+/// deserializing anyway.
+fn reference_length(e: &BytesRef) -> usize {
+    if let Ok(Some(c)) = e.resolve_char_ref() {
+        return c.len_utf8();
+    }
+    e.decode()
+        .ok()
+        .and_then(|name| quick_xml::escape::resolve_predefined_entity(&name))
+        .map_or(e.len(), str::len)
+}
+
+/// Check the length of all attribute values of `e`, and the extent if `e` is a gap or space.
+fn check_attributes(e: &BytesStart, limits: &Limits) -> Result<(), CheckError> {
+    let extent: &[u8] = match e.local_name().as_ref() {
+        b"gap" => b"n",
+        b"space" => b"quantity",
+        _ => b"",
+    };
+    for attribute in e.attributes() {
+        let attribute = attribute.map_err(|e| CheckError::Xml(e.into()))?;
+        if attribute.value.len() > limits.max_string_length {
+            return Err(CheckError::Limit(LimitError::StringTooLong(
+                limits.max_string_length,
+            )));
+        }
+        // values that are not numbers are rejected when deserializing anyway
+        if attribute.key.local_name().as_ref() == extent
+            && let Ok(value) = core::str::from_utf8(&attribute.value)
+            && let Ok(n) = value.trim().parse::<usize>()
+            && n > limits.max_extent
+        {
+            return Err(CheckError::Limit(LimitError::ExtentTooLarge(
+                limits.max_extent,
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ConversionError, Options, from_xml, from_xml_with_options, page_from_xml_with_options,
+        page_to_xml_with_options, recovery, to_xml_with_options,
+    };
+
+    fn with(limits: Limits) -> Options {
+        Options {
+            limits: Some(limits),
+            ..Options::default()
+        }
+    }
+
+    fn limited(xml: &str, limits: Limits) -> Result<(), ConversionError> {
+        from_xml_with_options(xml.as_bytes(), &with(limits)).map(|_| ())
+    }
+
+    #[test]
+    fn examples_within_default_limits() {
+        for file in [
+            "examples/10_hand_notes.xml",
+            "examples/02_lines_consistent.xml",
+            "examples/07_multi-page.xml",
+            "examples/14_facsimile.xml",
+        ] {
+            let xml = std::fs::read_to_string(file).unwrap();
+            let ms = from_xml_with_options(xml.as_bytes(), &with(Limits::default())).unwrap();
+            assert_eq!(ms, from_xml(xml.as_bytes()).unwrap(), "{file}");
+            assert!(to_xml_with_options(ms, &with(Limits::default())).is_ok());
+        }
+    }
+
+    #[test]
+    fn exceeded_limits() {
+        let xml = std::fs::read_to_string("examples/07_multi-page.xml").unwrap();
+        let exceeded = |limits: Limits| match limited(&xml, limits) {
+            Err(ConversionError::Limit(e)) => e,
+            x => panic!("expected an exceeded limit, got {x:?}"),
+        };
+        assert_eq!(
+            exceeded(Limits {
+                max_bytes: 100,
+                ..Limits::default()
+            }),
+            LimitError::TooManyBytes(100)
+        );
+        assert_eq!(
+            exceeded(Limits {
+                max_pages: 1,
+                ..Limits::default()
+            }),
+            LimitError::TooManyPages(1)
+        );
+        assert_eq!(
+            exceeded(Limits {
+                max_string_length: 10,
+                ..Limits::default()
+            }),
+            LimitError::StringTooLong(10)
+        );
+
+        let xml = std::fs::read_to_string("examples/10_hand_notes.xml").unwrap();
+        assert!(limited(&xml, Limits::default()).is_ok());
+        assert!(matches!(
+            limited(
+                &xml,
+                Limits {
+                    max_blocks_per_line: 1,
+                    ..Limits::default()
+                }
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyBlocks(1)))
+        ));
+        assert!(matches!(
+            limited(
+                &xml,
+                Limits {
+                    max_versions_per_correction: 1,
+                    ..Limits::default()
+                }
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyVersions(1)))
+        ));
+    }
+
+    #[test]
+    fn single_page() {
+        let xml = std::fs::read_to_string("examples/08_single_page.xml").unwrap();
+        assert_eq!(
+            page_from_xml_with_options(xml.as_bytes(), "grc", &with(Limits::default())).unwrap(),
+            page_from_xml_with_options(xml.as_bytes(), "grc", &Options::default()).unwrap()
+        );
+        assert!(matches!(
+            page_from_xml_with_options(
+                xml.as_bytes(),
+                "grc",
+                &with(Limits {
+                    max_blocks_per_line: 1,
+                    ..Limits::default()
+                })
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyBlocks(1)))
+        ));
+    }
+
+    /// Text split by references is checked as a whole.
+    #[test]
+    fn text_with_references() {
+        let xml = r#"<div type="page" n="1r"><div type="column" n="1"><div type="line" n="1"><p>abcd&amp;abcd&#x3b1;abcd<![CDATA[abcd]]></p></div></div></div>"#;
+        let page = |max_string_length| {
+            page_from_xml_with_options(
+                xml.as_bytes(),
+                "grc",
+                &with(Limits {
+                    max_string_length,
+                    ..Limits::default()
+                }),
+            )
+        };
+        assert!(page(19).is_ok());
+        assert!(matches!(
+            page(18),
+            Err(ConversionError::Limit(LimitError::StringTooLong(18)))
+        ));
+    }
+
+    #[test]
+    fn extents() {
+        let page = |attributes: &str| {
+            let xml = format!(
+                r#"<div type="page" n="1r"><div type="column" n="1"><div type="line" n="1">{attributes}</div></div></div>"#
+            );
+            page_from_xml_with_options(xml.as_bytes(), "grc", &with(Limits::default()))
+        };
+        assert!(page(r#"<gap reason="lost" unit="character" n="10000"/>"#).is_ok());
+        assert!(page(r#"<space unit="character" quantity="10000"/>"#).is_ok());
+        for block in [
+            r#"<gap reason="lost" unit="character" n="2000000000"/>"#,
+            r#"<space unit="character" quantity="10001"/>"#,
+        ] {
+            assert!(
+                matches!(
+                    page(block),
+                    Err(ConversionError::Limit(LimitError::ExtentTooLarge(10_000)))
+                ),
+                "{block}"
+            );
+        }
+    }
+
+    #[test]
+    fn written() {
+        let ms = from_xml(
+            std::fs::read_to_string("examples/07_multi-page.xml")
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+        assert!(matches!(
+            to_xml_with_options(
+                ms,
+                &with(Limits {
+                    max_pages: 1,
+                    ..Limits::default()
+                })
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyPages(1)))
+        ));
+
+        let xml = std::fs::read_to_string("examples/08_single_page.xml").unwrap();
+        let page = page_from_xml_with_options(xml.as_bytes(), "grc", &Options::default()).unwrap();
+        assert!(matches!(
+            page_to_xml_with_options(
+                page,
+                &with(Limits {
+                    max_blocks_per_line: 1,
+                    ..Limits::default()
+                })
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyBlocks(1)))
+        ));
+    }
+
+    #[test]
+    fn recovered() {
+        let xml = std::fs::read_to_string("examples/07_multi-page.xml").unwrap();
+        assert_eq!(
+            recovery::from_xml_with_options(xml.as_bytes(), &with(Limits::default())).unwrap(),
+            recovery::from_xml(xml.as_bytes()).unwrap()
+        );
+        assert!(matches!(
+            recovery::from_xml_with_options(
+                xml.as_bytes(),
+                &with(Limits {
+                    max_pages: 1,
+                    ..Limits::default()
+                })
+            ),
+            Err(ConversionError::Limit(LimitError::TooManyPages(1)))
+        ));
+    }
+
+    #[test]
+    fn malformed_xml() {
+        assert!(matches!(
+            limited("<TEI><body></TEI>", Limits::default()),
+            Err(ConversionError::DeSer(_))
+        ));
+    }
+}
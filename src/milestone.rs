@@ -0,0 +1,1074 @@
+//! Conversion between [`streamed::Block`]s and conventional TEI milestone markup.
+//!
+//! Most TEI projects do not nest the text in `div`s for pages, columns and lines. They mark the
+//! layout with the empty milestones `<pb/>`, `<cb/>` and `<lb/>` inside the logical structure of
+//! the text instead. These map directly onto [`BreakType`](streamed::BreakType):
+//!
+//! - `<pb n="…"/>` is a [`Page`](streamed::BreakType::Page) break
+//! - `<cb/>` is a [`Column`](streamed::BreakType::Column) break
+//! - `<lb/>` is a [`Line`](streamed::BreakType::Line) break
+//!
+//! The first `<cb/>` and `<lb/>` after a `<pb/>`, the first `<lb/>` after a `<cb/>` and the
+//! milestones directly after a `<gap/>` or `<space/>` spanning lines or columns only mark where
+//! the text continues, and do not produce a break.
+
+use quick_xml::events::{BytesStart, Event};
+use serde::Serialize;
+
+use crate::{schema, streamed};
+
+/// The problems that can occur when converting from or to milestone markup.
+#[derive(Debug)]
+pub enum MilestoneError {
+    /// The input is not well-formed XML
+    Xml(quick_xml::Error),
+    /// A block could not be serialized
+    Ser(quick_xml::SeError),
+    /// There is text, or a `<cb/>` or `<lb/>`, before the first `<pb/>`, so it is not on any page
+    TextBeforeFirstPage,
+    /// The number of the column or line after the block at this index does not fit into an `i32`
+    PositionOverflow(usize),
+    /// There is a `<pb/>`, `<cb/>` or `<lb/>` inside this element, which cannot be split into one
+    /// block before and one after the break
+    BreakInside(String),
+    /// The names of two pages are not in lexical order, even with their leading numbers padded
+    ///
+    /// last page - this page
+    PagesNotOrdered(String, String),
+}
+impl core::fmt::Display for MilestoneError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Xml(e) => {
+                write!(f, "Unable to read milestone markup: {e}")
+            }
+            Self::Ser(e) => {
+                write!(f, "Unable to write milestone markup: {e}")
+            }
+            Self::TextBeforeFirstPage => {
+                write!(
+                    f,
+                    "There is text or a break before the first <pb/>. Every text must start with a page break."
+                )
+            }
+            Self::PositionOverflow(x) => {
+                write!(
+                    f,
+                    "The column or line number after block {x} is too large to be written."
+                )
+            }
+            Self::BreakInside(x) => {
+                write!(
+                    f,
+                    "There is a page, column or line break inside <{x}>. Move it before or after the element."
+                )
+            }
+            Self::PagesNotOrdered(last, this) => {
+                write!(
+                    f,
+                    "The pages {last} and {this} are not in lexical order. Rename them so that they sort in reading order."
+                )
+            }
+        }
+    }
+}
+impl core::error::Error for MilestoneError {}
+
+// import
+
+/// Elements whose content is not part of the transcribed text.
+const SKIPPED_ELEMENTS: [&[u8]; 6] = [
+    b"teiHeader",
+    b"facsimile",
+    b"front",
+    b"back",
+    b"note",
+    b"fw",
+];
+
+/// The value of the attribute `name` of `e`, if present and readable.
+pub(crate) fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|v| v.trim().to_string())
+}
+
+/// The unit of a `<gap/>` or `<space/>`.
+///
+/// TEI does not restrict `@unit`, so the common spellings are accepted. Anything else is taken
+/// to count characters.
+pub(crate) fn extent_unit(e: &BytesStart) -> streamed::ExtentUnit {
+    match attribute(e, "unit").as_deref() {
+        Some("line" | "lines") => streamed::ExtentUnit::Line,
+        Some("column" | "columns") => streamed::ExtentUnit::Column,
+        _ => streamed::ExtentUnit::Character,
+    }
+}
+
+/// The extent of a `<gap/>` or `<space/>`, in `@quantity` or `@extent` (TEI) or `@n` (this crate).
+pub(crate) fn extent_quantity(e: &BytesStart) -> i32 {
+    attribute(e, "quantity")
+        .or_else(|| attribute(e, "extent"))
+        .or_else(|| attribute(e, "n"))
+        .and_then(|q| q.parse().ok())
+        .unwrap_or(1)
+}
+
+/// Tracks which `<cb/>` and `<lb/>` only mark where the text continues.
+#[derive(Default)]
+struct ImplicitMilestones {
+    /// The next `<cb/>` does not produce a break
+    skip_next_column_break: bool,
+    /// The next `<lb/>` does not produce a break
+    skip_next_line_break: bool,
+}
+impl ImplicitMilestones {
+    /// Record that `block` was pushed.
+    fn record(&mut self, block: &streamed::Block) {
+        let (column, line) = match block {
+            streamed::Block::Break(streamed::BreakType::Page(..))
+            | streamed::Block::Lacuna(streamed::Lacuna {
+                unit: streamed::ExtentUnit::Column,
+                ..
+            })
+            | streamed::Block::Space(streamed::Space {
+                unit: streamed::ExtentUnit::Column,
+                ..
+            }) => (true, true),
+            streamed::Block::Break(streamed::BreakType::Column)
+            | streamed::Block::Lacuna(streamed::Lacuna {
+                unit: streamed::ExtentUnit::Line,
+                ..
+            })
+            | streamed::Block::Space(streamed::Space {
+                unit: streamed::ExtentUnit::Line,
+                ..
+            }) => (false, true),
+            _ => (false, false),
+        };
+        self.skip_next_column_break = column;
+        self.skip_next_line_break = line;
+    }
+
+    /// Record that text continues the current line.
+    fn record_text(&mut self) {
+        self.skip_next_column_break = false;
+        self.skip_next_line_break = false;
+    }
+
+    /// Whether the `<cb/>` just read does not produce a break.
+    fn column_break_is_implicit(&mut self) -> bool {
+        core::mem::take(&mut self.skip_next_column_break)
+    }
+
+    /// Whether the `<lb/>` just read does not produce a break.
+    fn line_break_is_implicit(&mut self) -> bool {
+        self.skip_next_column_break = false;
+        core::mem::take(&mut self.skip_next_line_break)
+    }
+}
+
+/// The problems found by [`ImportState`], common to all importers.
+pub(crate) enum ImportError {
+    /// Text or a block other than a page break was read before the first `<pb/>`
+    BeforeFirstPage,
+    /// The page names are not in lexical order
+    ///
+    /// last page - this page
+    PagesNotOrdered(String, String),
+}
+impl From<ImportError> for MilestoneError {
+    fn from(value: ImportError) -> Self {
+        match value {
+            ImportError::BeforeFirstPage => Self::TextBeforeFirstPage,
+            ImportError::PagesNotOrdered(last, this) => Self::PagesNotOrdered(last, this),
+        }
+    }
+}
+
+/// The names of all pages in `blocks`.
+fn page_names(blocks: &mut [streamed::Block]) -> impl Iterator<Item = &mut String> {
+    blocks.iter_mut().filter_map(|b| match b {
+        streamed::Block::Break(streamed::BreakType::Page(n, _)) => Some(n),
+        _ => None,
+    })
+}
+
+/// `n` with its leading number, if any, padded with zeros to `width` digits.
+pub(crate) fn padded(n: &str, width: usize) -> String {
+    let digits = n.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return n.to_string();
+    }
+    format!("{}{n}", "0".repeat(width.saturating_sub(digits)))
+}
+
+/// The state shared by all importers of milestone markup.
+pub(crate) struct ImportState {
+    default_language: String,
+    pub(crate) blocks: Vec<streamed::Block>,
+    /// The local name and `@xml:lang` of all open elements
+    pub(crate) open: Vec<(Vec<u8>, Option<String>)>,
+    /// The depth of the outermost skipped element, while in one
+    skipping: Option<usize>,
+    /// Text not yet pushed as a paragraph, with its language
+    text: Option<(String, String)>,
+    milestones: ImplicitMilestones,
+    /// The number of `<pb/>` seen so far
+    pages: usize,
+    /// The name of the current page
+    pub(crate) page: Option<String>,
+}
+impl ImportState {
+    pub(crate) fn new(default_language: &str) -> Self {
+        Self {
+            default_language: default_language.to_string(),
+            blocks: vec![],
+            open: vec![],
+            skipping: None,
+            text: None,
+            milestones: ImplicitMilestones::default(),
+            pages: 0,
+            page: None,
+        }
+    }
+
+    /// The language of the innermost open element that has one.
+    pub(crate) fn language(&self) -> String {
+        self.open
+            .iter()
+            .rev()
+            .find_map(|(_, lang)| lang.clone())
+            .unwrap_or_else(|| self.default_language.clone())
+    }
+
+    /// Open the element `e`, returning its local name unless its content is skipped.
+    pub(crate) fn enter(&mut self, e: &BytesStart) -> Option<Vec<u8>> {
+        let name = e.local_name().as_ref().to_vec();
+        self.open.push((name.clone(), attribute(e, "xml:lang")));
+        if self.skipping.is_some() {
+            return None;
+        }
+        if SKIPPED_ELEMENTS.contains(&name.as_slice()) {
+            self.skipping = Some(self.open.len());
+            return None;
+        }
+        Some(name)
+    }
+
+    /// Close the innermost open element, returning its local name and depth unless its content
+    /// is skipped.
+    pub(crate) fn leave(&mut self) -> Option<(Vec<u8>, usize)> {
+        let depth = self.open.len();
+        let (name, _) = self.open.pop()?;
+        if let Some(d) = self.skipping {
+            if d == depth {
+                self.skipping = None;
+            }
+            return None;
+        }
+        Some((name, depth))
+    }
+
+    /// Push the pending text as a paragraph, with normalized whitespace.
+    pub(crate) fn flush_text(&mut self) {
+        if let Some((lang, content)) = self.text.take() {
+            let content = content.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !content.is_empty() {
+                self.blocks
+                    .push(streamed::Block::Text(streamed::Paragraph { lang, content }));
+            }
+        }
+    }
+
+    /// Push any block other than text.
+    pub(crate) fn push(&mut self, block: streamed::Block) -> Result<(), ImportError> {
+        self.flush_text();
+        if self.blocks.is_empty()
+            && !matches!(block, streamed::Block::Break(streamed::BreakType::Page(..)))
+        {
+            return Err(ImportError::BeforeFirstPage);
+        }
+        self.milestones.record(&block);
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// The pending text in the current language, to append text to.
+    pub(crate) fn text(&mut self) -> Result<&mut String, ImportError> {
+        if self.blocks.is_empty() {
+            return Err(ImportError::BeforeFirstPage);
+        }
+        let lang = self.language();
+        if self.text.as_ref().is_none_or(|(l, _)| *l != lang) {
+            self.flush_text();
+            self.milestones.record_text();
+        }
+        let (_, text) = self.text.get_or_insert_with(|| (lang, String::new()));
+        Ok(text)
+    }
+
+    /// Push the break for the milestone `e` with the local name `name` (`<pb/>`, `<cb/>` or
+    /// `<lb/>`), unless it is implicit.
+    pub(crate) fn milestone(&mut self, e: &BytesStart, name: &[u8]) -> Result<(), ImportError> {
+        match name {
+            b"pb" => {
+                self.pages += 1;
+                let n = attribute(e, "n").unwrap_or_else(|| format!("{:04}", self.pages));
+                self.page = Some(n.clone());
+                self.push(streamed::Block::Break(streamed::BreakType::Page(n, None)))?;
+            }
+            b"cb" => {
+                if self.milestones.column_break_is_implicit() {
+                    self.flush_text();
+                } else {
+                    self.push(streamed::Block::Break(streamed::BreakType::Column))?;
+                }
+            }
+            b"lb" => {
+                if self.milestones.line_break_is_implicit() {
+                    self.flush_text();
+                } else {
+                    self.push(streamed::Block::Break(streamed::BreakType::Line))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Make the page names sort in reading order.
+    ///
+    /// Page names MUST be in lexical order, but folios are commonly numbered `1r`, `1v`, …, `10r`.
+    /// If the names are not in order as given, their leading numbers are padded with zeros to the
+    /// same width, so `1r` becomes `01r`. Returns the width if the names were padded.
+    pub(crate) fn sort_pages(&mut self) -> Result<Option<usize>, ImportError> {
+        if page_names(&mut self.blocks).is_sorted_by(|a, b| a < b) {
+            return Ok(None);
+        }
+        let width = page_names(&mut self.blocks)
+            .map(|n| n.bytes().take_while(u8::is_ascii_digit).count())
+            .max()
+            .unwrap_or_default();
+        let mut last: Option<String> = None;
+        for n in page_names(&mut self.blocks) {
+            *n = padded(n, width);
+            if let Some(l) = last.take_if(|l| *l >= *n) {
+                return Err(ImportError::PagesNotOrdered(l, n.clone()));
+            }
+            last = Some(n.clone());
+        }
+        Ok(Some(width))
+    }
+
+    /// The local name of the innermost open element named in `names`.
+    pub(crate) fn innermost(&self, names: &[&[u8]]) -> String {
+        self.open
+            .iter()
+            .rev()
+            .find(|(n, _)| names.contains(&n.as_slice()))
+            .map(|(n, _)| String::from_utf8_lossy(n).into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// An importer for a flavour of milestone markup, driven by [`import`].
+pub(crate) trait Importer {
+    type Error: From<ImportError>;
+
+    fn state(&mut self) -> &mut ImportState;
+    fn xml_error(e: quick_xml::Error) -> Self::Error;
+    /// Handle the start of an element.
+    fn start(&mut self, e: &BytesStart) -> Result<(), Self::Error>;
+    /// Handle the end of the innermost open element.
+    fn end(&mut self) -> Result<(), Self::Error>;
+    /// Handle text outside of skipped elements.
+    fn push_text(&mut self, content: &str) -> Result<(), Self::Error>;
+}
+
+/// Read all of `buf_reader` into `importer`.
+pub(crate) fn import<I: Importer>(
+    buf_reader: impl std::io::BufRead,
+    importer: &mut I,
+) -> Result<(), I::Error> {
+    let mut reader = quick_xml::Reader::from_reader(buf_reader);
+    reader.config_mut().expand_empty_elements = true;
+    let mut buf = vec![];
+    loop {
+        let event = reader.read_event_into(&mut buf).map_err(I::xml_error)?;
+        let skipping = importer.state().skipping.is_some();
+        match event {
+            Event::Start(e) => importer.start(&e)?,
+            Event::End(_) => importer.end()?,
+            Event::Text(e) if !skipping => {
+                let text = e.decode().map_err(|e| I::xml_error(e.into()))?;
+                importer.push_text(&text)?;
+            }
+            Event::CData(e) if !skipping => {
+                let text = e.decode().map_err(|e| I::xml_error(e.into()))?;
+                importer.push_text(&text)?;
+            }
+            Event::GeneralRef(e) if !skipping => {
+                let text = match e.resolve_char_ref().map_err(I::xml_error)? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = e.decode().map_err(|e| I::xml_error(e.into()))?;
+                        quick_xml::escape::resolve_predefined_entity(&name)
+                            .unwrap_or_default()
+                            .to_string()
+                    }
+                };
+                importer.push_text(&text)?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    importer.state().flush_text();
+    Ok(())
+}
+
+/// An element whose text is collected into a single block.
+///
+/// A `<damage>` or `<unclear>` spanning a break becomes one block before and one after the break,
+/// all other captures must not contain breaks.
+enum Capture {
+    /// `<damage>` or `<unclear>`
+    Uncertain(streamed::Uncertain),
+    /// `<choice>` with its surface form (`<abbr>`, `<sic>`, `<orig>`) and expansion (`<expan>`,
+    /// `<corr>`, `<reg>`)
+    Abbreviation(streamed::Abbreviation),
+    /// `<app>` with its `<lem>`/`<rdg>`, or `<subst>` with its `<del>`/`<add>`
+    Correction(streamed::Correction),
+}
+
+/// The state while reading milestone markup.
+struct TeiImporter {
+    state: ImportState,
+    /// The element currently collecting text, and the name of the child text goes to
+    capture: Option<(Capture, Option<Vec<u8>>)>,
+}
+impl TeiImporter {
+    /// Handle the milestone `e` inside the current capture.
+    ///
+    /// An uncertain block is split at the break, everything else is rejected.
+    fn milestone_in_capture(&mut self, e: &BytesStart, name: &[u8]) -> Result<(), MilestoneError> {
+        let Some((Capture::Uncertain(x), target)) = self.capture.take() else {
+            return Err(MilestoneError::BreakInside(
+                self.state.innermost(&[b"choice", b"app", b"subst"]),
+            ));
+        };
+        let rest = streamed::Uncertain {
+            content: String::new(),
+            ..x.clone()
+        };
+        if let Some(block) = finish(Capture::Uncertain(x)) {
+            self.state.push(block)?;
+        }
+        self.state.milestone(e, name)?;
+        self.capture = Some((Capture::Uncertain(rest), target));
+        Ok(())
+    }
+}
+impl Importer for TeiImporter {
+    type Error = MilestoneError;
+
+    fn state(&mut self) -> &mut ImportState {
+        &mut self.state
+    }
+
+    fn xml_error(e: quick_xml::Error) -> Self::Error {
+        MilestoneError::Xml(e)
+    }
+
+    fn push_text(&mut self, content: &str) -> Result<(), MilestoneError> {
+        let lang = self.state.language();
+        if let Some((capture, target)) = self.capture.as_mut() {
+            let text = match (capture, target.as_deref()) {
+                (Capture::Uncertain(x), _) => &mut x.content,
+                (Capture::Abbreviation(x), Some(b"abbr" | b"sic" | b"orig")) => {
+                    x.surface_lang = lang;
+                    &mut x.surface
+                }
+                (Capture::Abbreviation(x), Some(b"expan" | b"corr" | b"reg")) => {
+                    x.expansion_lang = lang;
+                    &mut x.expansion
+                }
+                (Capture::Correction(x), Some(_)) => match x.versions.last_mut() {
+                    Some(v) => &mut v.content,
+                    None => return Ok(()),
+                },
+                // text directly in <choice> or <app> is not part of any form
+                _ => return Ok(()),
+            };
+            text.push_str(content);
+            return Ok(());
+        }
+        if content.trim().is_empty() && self.state.text.is_none() {
+            return Ok(());
+        }
+        self.state.text()?.push_str(content);
+        Ok(())
+    }
+
+    fn start(&mut self, e: &BytesStart) -> Result<(), MilestoneError> {
+        let Some(name) = self.state.enter(e) else {
+            return Ok(());
+        };
+        // includes the `@xml:lang` of this element
+        let lang = self.state.language();
+        if self.capture.is_some() && matches!(name.as_slice(), b"pb" | b"cb" | b"lb") {
+            return self.milestone_in_capture(e, &name);
+        }
+        if let Some((capture, target)) = self.capture.as_mut() {
+            if let Capture::Correction(x) = capture
+                && matches!(name.as_slice(), b"lem" | b"rdg" | b"del" | b"add")
+            {
+                x.versions.push(streamed::Version {
+                    lang: lang.clone(),
+                    hand: attribute(e, "hand"),
+                    content: String::new(),
+                });
+            }
+            if target.is_none() {
+                *target = Some(name);
+            }
+            return Ok(());
+        }
+        match name.as_slice() {
+            b"pb" | b"cb" | b"lb" => self.state.milestone(e, &name)?,
+            b"gap" => self.state.push(streamed::Block::Lacuna(streamed::Lacuna {
+                reason: attribute(e, "reason").unwrap_or_default(),
+                unit: extent_unit(e),
+                n: extent_quantity(e),
+                cert: attribute(e, "cert").and_then(|c| c.parse().ok()),
+            }))?,
+            b"space" => self.state.push(streamed::Block::Space(streamed::Space {
+                quantity: extent_quantity(e),
+                unit: extent_unit(e),
+            }))?,
+            b"anchor" => self.state.push(streamed::Block::Anchor(streamed::Anchor {
+                anchor_id: attribute(e, "xml:id").unwrap_or_default(),
+                anchor_type: attribute(e, "type").unwrap_or_default(),
+            }))?,
+            b"damage" | b"unclear" => {
+                self.capture = Some((
+                    Capture::Uncertain(streamed::Uncertain {
+                        lang,
+                        cert: attribute(e, "cert").and_then(|c| c.parse().ok()),
+                        agent: attribute(e, "agent")
+                            .or_else(|| attribute(e, "reason"))
+                            .unwrap_or_default(),
+                        content: String::new(),
+                    }),
+                    Some(name),
+                ));
+            }
+            b"choice" => {
+                self.capture = Some((
+                    Capture::Abbreviation(streamed::Abbreviation {
+                        surface_lang: lang.clone(),
+                        surface: String::new(),
+                        expansion_lang: lang,
+                        expansion: String::new(),
+                    }),
+                    None,
+                ));
+            }
+            b"app" | b"subst" => {
+                self.capture = Some((
+                    Capture::Correction(streamed::Correction { versions: vec![] }),
+                    None,
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), MilestoneError> {
+        let Some((name, _)) = self.state.leave() else {
+            return Ok(());
+        };
+        let Some((_, target)) = self.capture.as_mut() else {
+            return Ok(());
+        };
+        if target.as_ref() == Some(&name) {
+            *target = None;
+        }
+        // the capturing element itself has ended
+        let ended = match self.capture.as_ref() {
+            Some((Capture::Uncertain(_), None)) => name == b"damage" || name == b"unclear",
+            Some((Capture::Abbreviation(_), None)) => name == b"choice",
+            Some((Capture::Correction(_), None)) => name == b"app" || name == b"subst",
+            _ => false,
+        };
+        if ended
+            && let Some((capture, _)) = self.capture.take()
+            && let Some(block) = finish(capture)
+        {
+            self.state.push(block)?;
+        }
+        Ok(())
+    }
+}
+
+/// The block collected by `capture`, with normalized whitespace.
+///
+/// An empty `<damage>` or `<unclear>`, e.g. the part after a break at its end, is dropped.
+fn finish(capture: Capture) -> Option<streamed::Block> {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ");
+    match capture {
+        Capture::Uncertain(mut x) => {
+            x.content = normalize(&x.content);
+            (!x.content.is_empty()).then_some(streamed::Block::Uncertain(x))
+        }
+        Capture::Abbreviation(mut x) => {
+            x.surface = normalize(&x.surface);
+            x.expansion = normalize(&x.expansion);
+            Some(streamed::Block::Abbreviation(x))
+        }
+        Capture::Correction(mut x) => {
+            for v in &mut x.versions {
+                v.content = normalize(&v.content);
+            }
+            Some(streamed::Block::Correction(x))
+        }
+    }
+}
+
+/// Read TEI with milestone markup from `buf_reader` and convert it to blocks.
+///
+/// The text may be a complete TEI document or just a `<body>`, in any logical structure. The
+/// header, facsimile, front and back matter, notes and forme work are ignored, all other elements
+/// are read for their text. A `<pb/>` without `@n` is named by its position (`0001`, `0002`, …).
+/// If the page names are not in lexical order, their leading numbers are padded with zeros, so
+/// `9` and `10` become `09` and `10`. `@facs` is not converted. Whitespace in text is normalized. A
+/// `<damage>` or `<unclear>` spanning a break is split into one block before and one after it.
+///
+/// # Errors
+/// If the input is not well-formed, has text or breaks before the first `<pb/>`, has a break
+/// inside a `<choice>`, `<app>` or `<subst>`, or has page names that are not in order even when
+/// padded.
+pub fn from_milestones(
+    buf_reader: impl std::io::BufRead,
+    default_language: &str,
+) -> Result<Vec<streamed::Block>, MilestoneError> {
+    let mut importer = TeiImporter {
+        state: ImportState::new(default_language),
+        capture: None,
+    };
+    import(buf_reader, &mut importer)?;
+    importer.state.sort_pages()?;
+    Ok(importer.state.blocks)
+}
+
+// export
+
+/// `lang` if it differs from the default language.
+fn other_language(lang: &str, default_language: &str) -> Option<String> {
+    (lang != default_language).then(|| lang.to_string())
+}
+
+/// A `<gap/>` as written to milestone markup, with its extent in `@quantity` as TEI has it.
+#[derive(Serialize)]
+struct QuantifiedGap<'a> {
+    #[serde(rename = "@reason")]
+    reason: &'a str,
+    #[serde(rename = "@unit")]
+    unit: streamed::ExtentUnit,
+    #[serde(rename = "@quantity")]
+    quantity: i32,
+    #[serde(rename = "@cert", skip_serializing_if = "Option::is_none")]
+    cert: Option<streamed::Certainty>,
+}
+
+/// Write a single content block.
+fn write_block(
+    block: &streamed::Block,
+    default_language: &str,
+) -> Result<String, quick_xml::SeError> {
+    let lang = |l: &str| other_language(l, default_language);
+    match block {
+        streamed::Block::Text(x) => Ok(match lang(&x.lang) {
+            Some(l) => format!(
+                "<seg xml:lang=\"{}\">{}</seg>",
+                quick_xml::escape::escape(l),
+                quick_xml::escape::escape(&x.content)
+            ),
+            None => quick_xml::escape::escape(&x.content).into_owned(),
+        }),
+        streamed::Block::Uncertain(x) => quick_xml::se::to_string_with_root(
+            "damage",
+            &schema::Damage {
+                lang: lang(&x.lang),
+                cert: x.cert,
+                agent: x.agent.clone(),
+                content: x.content.clone(),
+            },
+        ),
+        streamed::Block::Abbreviation(x) => quick_xml::se::to_string_with_root(
+            "choice",
+            &schema::Choice {
+                lang: None,
+                surface: schema::AbbrSurface {
+                    lang: lang(&x.surface_lang),
+                    content: x.surface.clone(),
+                },
+                expansion: schema::AbbrExpansion {
+                    lang: lang(&x.expansion_lang),
+                    content: x.expansion.clone(),
+                },
+            },
+        ),
+        streamed::Block::Correction(x) => quick_xml::se::to_string_with_root(
+            "app",
+            &schema::App {
+                lang: None,
+                rdg: x
+                    .versions
+                    .iter()
+                    .zip(1..)
+                    .map(|(v, var_seq)| schema::Rdg {
+                        lang: lang(&v.lang),
+                        hand: v.hand.clone(),
+                        var_seq,
+                        content: v.content.clone(),
+                    })
+                    .collect(),
+            },
+        ),
+        streamed::Block::Lacuna(x) => quick_xml::se::to_string_with_root(
+            "gap",
+            &QuantifiedGap {
+                reason: &x.reason,
+                unit: x.unit,
+                quantity: x.n,
+                cert: x.cert,
+            },
+        ),
+        streamed::Block::Space(x) => quick_xml::se::to_string_with_root("space", x),
+        streamed::Block::Anchor(x) => quick_xml::se::to_string_with_root(
+            "anchor",
+            &schema::Anchor {
+                xml_id: x.anchor_id.clone(),
+                anchor_type: x.anchor_type.clone(),
+            },
+        ),
+        streamed::Block::Break(_) => Ok(String::new()),
+    }
+}
+
+/// Write `blocks` as a TEI `<body>` with milestone markup.
+///
+/// The whole text is a single `<p>` in a single `<div>`, with one milestone per break. Columns and
+/// lines are numbered in `@n` as in [`normalized`](crate::normalized), counting the columns and
+/// lines skipped by lacunae. The extent of a `<gap/>` or `<space/>` is in `@quantity`. Text in a
+/// language other than `default_language` is wrapped in `<seg>`. The workflow status of pages is
+/// not written.
+///
+/// # Errors
+/// If a block cannot be serialized or a column or line number overflows.
+pub fn to_milestones(
+    blocks: &[streamed::Block],
+    default_language: &str,
+) -> Result<String, MilestoneError> {
+    let mut res = format!(
+        "<body xml:lang=\"{}\"><div><p>",
+        quick_xml::escape::escape(default_language)
+    );
+    let mut position: Option<streamed::Position> = None;
+    for (idx, block) in blocks.iter().enumerate() {
+        let next_is_on_page = blocks
+            .get(idx + 1)
+            .is_some_and(|b| !matches!(b, streamed::Block::Break(streamed::BreakType::Page(..))));
+        let next = match position.as_ref() {
+            Some(p) => Some(
+                p.after(block)
+                    .ok_or(MilestoneError::PositionOverflow(idx))?,
+            ),
+            None => streamed::Position::start_of(block),
+        };
+        match block {
+            streamed::Block::Break(streamed::BreakType::Page(n, _)) => {
+                res.push_str(&format!("\n<pb n=\"{}\"/>", quick_xml::escape::escape(n)));
+                if next_is_on_page {
+                    res.push_str("<cb n=\"1\"/><lb n=\"1\"/>");
+                }
+            }
+            _ => {
+                res.push_str(&write_block(block, default_language).map_err(MilestoneError::Ser)?);
+                let ends_column = matches!(
+                    block,
+                    streamed::Block::Break(streamed::BreakType::Column)
+                        | streamed::Block::Lacuna(streamed::Lacuna {
+                            unit: streamed::ExtentUnit::Column,
+                            ..
+                        })
+                        | streamed::Block::Space(streamed::Space {
+                            unit: streamed::ExtentUnit::Column,
+                            ..
+                        })
+                );
+                let ends_line = ends_column
+                    || matches!(
+                        block,
+                        streamed::Block::Break(streamed::BreakType::Line)
+                            | streamed::Block::Lacuna(streamed::Lacuna {
+                                unit: streamed::ExtentUnit::Line,
+                                ..
+                            })
+                            | streamed::Block::Space(streamed::Space {
+                                unit: streamed::ExtentUnit::Line,
+                                ..
+                            })
+                    );
+                let is_break = matches!(block, streamed::Block::Break(_));
+                // breaks are always written, the end of a spanning lacuna only if the page goes on
+                if let Some(next) = next.as_ref()
+                    && ends_line
+                    && (is_break || next_is_on_page)
+                {
+                    if ends_column {
+                        res.push_str(&format!("\n<cb n=\"{}\"/>", next.column));
+                    } else {
+                        res.push('\n');
+                    }
+                    res.push_str(&format!("<lb n=\"{}\"/>", next.line));
+                } else if !is_break
+                    && blocks
+                        .get(idx + 1)
+                        .is_some_and(|b| !matches!(b, streamed::Block::Break(_)))
+                {
+                    res.push(' ');
+                }
+            }
+        }
+        position = next;
+    }
+    res.push_str("\n</p></div></body>");
+    Ok(res)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::streamed::{Block, BreakType};
+
+    fn text(lang: &str, content: &str) -> Block {
+        Block::Text(streamed::Paragraph {
+            lang: lang.to_string(),
+            content: content.to_string(),
+        })
+    }
+
+    #[test]
+    fn import_fixture() {
+        let xml = std::fs::File::open("examples/15_milestones.xml").unwrap();
+        let blocks = from_milestones(std::io::BufReader::new(xml), "en").unwrap();
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Break(BreakType::Page("01_r".to_string(), None)),
+                text("hbo-Hebr", "In the beginning"),
+                Block::Break(BreakType::Line),
+                text("hbo-Hebr", "God"),
+                Block::Uncertain(streamed::Uncertain {
+                    lang: "hbo-Hebr".to_string(),
+                    cert: Some(schema::Certainty::Low),
+                    agent: "faded".to_string(),
+                    content: "created".to_string(),
+                }),
+                Block::Abbreviation(streamed::Abbreviation {
+                    surface_lang: "hbo-Hebr".to_string(),
+                    surface: "hvn".to_string(),
+                    expansion_lang: "hbo-Hebr".to_string(),
+                    expansion: "heaven".to_string(),
+                }),
+                Block::Lacuna(streamed::Lacuna {
+                    reason: "lost".to_string(),
+                    unit: streamed::ExtentUnit::Line,
+                    n: 2,
+                    cert: None,
+                }),
+                text("hbo-Hebr", "and the"),
+                Block::Correction(streamed::Correction {
+                    versions: vec![
+                        streamed::Version {
+                            lang: "hbo-Hebr".to_string(),
+                            hand: None,
+                            content: "earth".to_string(),
+                        },
+                        streamed::Version {
+                            lang: "hbo-Hebr".to_string(),
+                            hand: Some("hand2".to_string()),
+                            content: "erth".to_string(),
+                        },
+                    ],
+                }),
+                Block::Break(BreakType::Column),
+                text("grc", "γῆ"),
+                Block::Break(BreakType::Page("01_v".to_string(), None)),
+                Block::Break(BreakType::Page("02_r".to_string(), None)),
+                Block::Space(streamed::Space {
+                    quantity: 3,
+                    unit: streamed::ExtentUnit::Character,
+                }),
+                Block::Anchor(streamed::Anchor {
+                    anchor_id: "A_V_GN_1_2".to_string(),
+                    anchor_type: "verse".to_string(),
+                }),
+                text("hbo-Hebr", "And"),
+            ]
+        );
+    }
+
+    #[test]
+    fn text_before_first_page() {
+        assert!(matches!(
+            from_milestones("<body><p>text<pb n=\"1\"/></p></body>".as_bytes(), "en"),
+            Err(MilestoneError::TextBeforeFirstPage)
+        ));
+    }
+
+    #[test]
+    fn breaks_inside_captures() {
+        let blocks = from_milestones(
+            r#"<body><pb n="1"/><unclear reason="faded">ab<lb/>cd<lb/></unclear>e</body>"#
+                .as_bytes(),
+            "en",
+        )
+        .unwrap();
+        let uncertain = |content: &str| {
+            Block::Uncertain(streamed::Uncertain {
+                lang: "en".to_string(),
+                cert: None,
+                agent: "faded".to_string(),
+                content: content.to_string(),
+            })
+        };
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Break(BreakType::Page("1".to_string(), None)),
+                uncertain("ab"),
+                Block::Break(BreakType::Line),
+                uncertain("cd"),
+                Block::Break(BreakType::Line),
+                text("en", "e"),
+            ]
+        );
+        assert!(matches!(
+            from_milestones(
+                r#"<body><pb n="1"/><choice><abbr>a<lb/>b</abbr><expan>ab</expan></choice></body>"#
+                    .as_bytes(),
+                "en"
+            ),
+            Err(MilestoneError::BreakInside(x)) if x == "choice"
+        ));
+        assert!(matches!(
+            from_milestones(r#"<body><cb/><pb n="1"/>a</body>"#.as_bytes(), "en"),
+            Err(MilestoneError::TextBeforeFirstPage)
+        ));
+    }
+
+    /// Pages numbered `9`, `10`, … are renamed to sort in reading order.
+    #[test]
+    fn page_order() {
+        let xml = (1..=10).fold(String::from("<body>"), |xml, n| {
+            format!("{xml}<pb n=\"{n}\"/>page {n}")
+        }) + "</body>";
+        let blocks = from_milestones(xml.as_bytes(), "grc").unwrap();
+        let names = blocks
+            .iter()
+            .filter_map(|b| match b {
+                Block::Break(BreakType::Page(n, _)) => Some(n.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["01", "02", "03", "04", "05", "06", "07", "08", "09", "10"]
+        );
+
+        let template = std::fs::File::open("examples/02_lines_consistent.xml").unwrap();
+        let mut ms = crate::from_xml(std::io::BufReader::new(template)).unwrap();
+        ms.content = blocks;
+        let xml = crate::to_xml(ms.clone()).unwrap();
+        assert_eq!(crate::from_xml(xml.as_bytes()).unwrap(), ms);
+
+        assert!(matches!(
+            from_milestones(r#"<body><pb n="2"/>a<pb n="1"/>b</body>"#.as_bytes(), "grc"),
+            Err(MilestoneError::PagesNotOrdered(last, this)) if last == "2" && this == "1"
+        ));
+    }
+
+    /// Breaks, lacunae and line numbers survive the way through milestones.
+    #[test]
+    fn roundtrip() {
+        for file in [
+            "examples/02_lines_consistent.xml",
+            "examples/05_with_nontrivial_space.xml",
+            "examples/07_multi-page.xml",
+            "examples/09_missing_pages.xml",
+        ] {
+            let xml = std::fs::File::open(file).unwrap();
+            let ms = crate::from_xml(std::io::BufReader::new(xml)).unwrap();
+            let milestones = to_milestones(&ms.content, "hbo-Hebr").unwrap();
+            let blocks = from_milestones(milestones.as_bytes(), "hbo-Hebr").unwrap();
+            assert_eq!(blocks, ms.content, "{file}");
+        }
+    }
+
+    #[test]
+    fn export_overflow() {
+        let blocks = vec![
+            Block::Break(BreakType::Page("1r".to_string(), None)),
+            text("grc", "a"),
+            Block::Lacuna(streamed::Lacuna {
+                reason: "lost".to_string(),
+                unit: streamed::ExtentUnit::Line,
+                n: i32::MAX,
+                cert: None,
+            }),
+            text("grc", "b"),
+        ];
+        assert!(matches!(
+            to_milestones(&blocks, "grc"),
+            Err(MilestoneError::PositionOverflow(2))
+        ));
+        assert_eq!(streamed::position_of(&blocks, 3), None);
+    }
+
+    #[test]
+    fn export_numbering() {
+        let blocks = vec![
+            Block::Break(BreakType::Page("1r".to_string(), None)),
+            text("grc", "a"),
+            Block::Lacuna(streamed::Lacuna {
+                reason: "lost".to_string(),
+                unit: streamed::ExtentUnit::Line,
+                n: 1,
+                cert: None,
+            }),
+            text("grc", "b & c"),
+            Block::Break(BreakType::Column),
+            text("la", "d"),
+            Block::Break(BreakType::Page("1v".to_string(), None)),
+        ];
+        assert_eq!(
+            to_milestones(&blocks, "grc").unwrap(),
+            concat!(
+                "<body xml:lang=\"grc\"><div><p>\n",
+                "<pb n=\"1r\"/><cb n=\"1\"/><lb n=\"1\"/>a ",
+                "<gap reason=\"lost\" unit=\"line\" quantity=\"1\"/>\n<lb n=\"3\"/>b &amp; c\n",
+                "<cb n=\"2\"/><lb n=\"1\"/><seg xml:lang=\"la\">d</seg>\n",
+                "<pb n=\"1v\"/>\n",
+                "</p></div></body>",
+            )
+        );
+    }
+}
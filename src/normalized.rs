@@ -12,6 +12,92 @@ pub struct Manuscript {
     pub meta: Meta,
     /// the actual text
     pub text: Text,
+    /// The images the text is linked to with the `facs` of pages, columns and lines
+    pub facsimile: Facsimile,
+}
+impl Manuscript {
+    /// The names of all pages that are missing from this manuscript.
+    ///
+    /// See [`Page::is_missing`].
+    #[must_use]
+    pub fn missing_pages(&self) -> Vec<&str> {
+        self.text
+            .pages
+            .iter()
+            .filter(|p| p.is_missing())
+            .map(|p| p.n.as_str())
+            .collect()
+    }
+
+    /// The aggregated certainty of each page in this manuscript, in order.
+    #[must_use]
+    pub fn certainty_by_page(&self) -> Vec<(&str, CertaintySummary)> {
+        self.text
+            .pages
+            .iter()
+            .map(|p| (p.n.as_str(), p.certainty()))
+            .collect()
+    }
+
+    /// The number of pages in each [`PageStatus`].
+    #[must_use]
+    pub fn status_counts(&self) -> StatusCounts {
+        let mut res = StatusCounts::default();
+        for page in &self.text.pages {
+            res.add(page.status);
+        }
+        res
+    }
+
+    /// All places where the transcribed text does not fit the [`layouts`](Meta::layouts) declared in
+    /// the header.
+    ///
+    /// Missing pages are not checked.
+    #[must_use]
+    pub fn layout_deviations(&self) -> Vec<LayoutDeviation> {
+        let mut res = vec![];
+        for page in self.text.pages.iter().filter(|p| !p.is_missing()) {
+            let columns = page.column_count();
+            if !self.meta.allows_columns(columns) {
+                res.push(LayoutDeviation::Columns {
+                    page: page.n.clone(),
+                    actual: columns,
+                });
+            }
+            for column in &page.columns {
+                let lines = column.line_count();
+                if !self.meta.allows_lines(lines) {
+                    res.push(LayoutDeviation::Lines {
+                        page: page.n.clone(),
+                        column: column.n,
+                        actual: lines,
+                    });
+                }
+            }
+        }
+        res
+    }
+}
+
+/// A place where the transcribed text does not fit the declared layout.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum LayoutDeviation {
+    /// A page has a number of columns not allowed by any layout
+    Columns {
+        /// The name of the page
+        page: String,
+        /// The number of columns on this page, see [`Page::column_count`]
+        actual: usize,
+    },
+    /// A column has a number of lines not allowed by any layout
+    Lines {
+        /// The name of the page
+        page: String,
+        /// The number of the column
+        column: i32,
+        /// The number of lines in this column, see [`Column::line_count`]
+        actual: usize,
+    },
 }
 
 /// TEI fileDesc element - describes this file.
@@ -19,16 +105,394 @@ pub struct Manuscript {
 pub struct Meta {
     /// Name of this manuscript
     pub title: String,
+    /// the country in which this MS is held
+    pub country: Option<String>,
+    /// the city or town in which this MS is held
+    pub settlement: Option<String>,
     /// the institution holding this MS
     pub institution: Option<String>,
+    /// the repository inside the institution holding this MS
+    pub repository: Option<String>,
     /// the collection to which this MS belongs
     pub collection: Option<String>,
+    /// the shelfmark of this MS in its repository
+    pub shelfmark: Option<String>,
     /// A description of the scribal hands that are present in this MS
     pub hand_desc: Option<String>,
+    /// The individual scribal hands that are present in this MS
+    pub hands: Vec<Hand>,
     /// A description of the script in use in this MS
     pub script_desc: Option<String>,
     /// Other names for this MS
-    pub alt_identifier: Vec<String>,
+    pub alt_identifier: Vec<Identifier>,
+    /// When this MS was written
+    pub orig_date: Option<DateRange>,
+    /// A human readable form of [`orig_date`](Self::orig_date)
+    pub orig_date_desc: Option<String>,
+    /// Where this MS was written
+    pub orig_place: Option<String>,
+    /// The places this MS has been at since it was written
+    pub provenance: Vec<String>,
+    /// The material this MS is written on
+    pub material: Option<Material>,
+    /// A human readable description of the material
+    pub support: Option<String>,
+    /// The number of leaves and size of this MS
+    pub extent: Option<Extent>,
+    /// The layouts used in this MS
+    pub layouts: Vec<Layout>,
+    /// The passages contained in this MS
+    ///
+    /// These can be derived from the verse anchors with
+    /// [`streamed::Manuscript::derive_contents`](crate::streamed::Manuscript::derive_contents).
+    pub contents: Vec<ContentItem>,
+    /// The changes made to this file, oldest first
+    pub changes: Vec<Change>,
+}
+impl Meta {
+    /// All changes made to the page `page`, oldest first.
+    pub fn changes_for_page<'a>(&'a self, page: &str) -> impl Iterator<Item = &'a Change> {
+        self.changes
+            .iter()
+            .filter(move |c| c.page.as_deref() == Some(page))
+    }
+
+    /// Whether a page may have `columns` columns.
+    ///
+    /// This is the case if any of the [`layouts`](Self::layouts) allows it, or if no layout
+    /// restricts the number of columns.
+    #[must_use]
+    pub fn allows_columns(&self, columns: usize) -> bool {
+        self.layouts.iter().all(|l| l.columns.is_none())
+            || self
+                .layouts
+                .iter()
+                .any(|l| l.columns.is_some_and(|c| c.contains(columns)))
+    }
+
+    /// Whether a column may have `lines` lines.
+    ///
+    /// This is the case if any of the [`layouts`](Self::layouts) allows it, or if no layout
+    /// restricts the number of lines.
+    #[must_use]
+    pub fn allows_lines(&self, lines: usize) -> bool {
+        self.layouts.iter().all(|l| l.written_lines.is_none())
+            || self
+                .layouts
+                .iter()
+                .any(|l| l.written_lines.is_some_and(|c| c.contains(lines)))
+    }
+
+    /// Whether this MS was certainly written before `date`.
+    ///
+    /// Undated manuscripts are never before any date.
+    #[must_use]
+    pub fn written_before(&self, date: Date) -> bool {
+        self.orig_date.is_some_and(|d| d.is_before(date))
+    }
+
+    /// Whether this MS was certainly written after `date`.
+    ///
+    /// Undated manuscripts are never after any date.
+    #[must_use]
+    pub fn written_after(&self, date: Date) -> bool {
+        self.orig_date.is_some_and(|d| d.is_after(date))
+    }
+
+    /// Whether this MS may have been written within `range`.
+    ///
+    /// Undated manuscripts are never within any range.
+    #[must_use]
+    pub fn possibly_written_within(&self, range: &DateRange) -> bool {
+        self.orig_date.is_some_and(|d| d.overlaps(range))
+    }
+
+    /// Get the first alternative identifier from the catalogue `id_type`.
+    ///
+    /// `id_type` is the value used in `@type`, e.g. `meta.identifier("Rahlfs")`.
+    #[must_use]
+    pub fn identifier<'a>(&'a self, id_type: &str) -> Option<&'a str> {
+        self.identifiers(id_type).next()
+    }
+
+    /// Get all alternative identifiers from the catalogue `id_type`.
+    pub fn identifiers<'a>(&'a self, id_type: &str) -> impl Iterator<Item = &'a str> {
+        self.alt_identifier
+            .iter()
+            .filter(move |a| a.id_type.as_ref().is_some_and(|t| t.as_str() == id_type))
+            .map(|a| a.idno.as_str())
+    }
+
+    /// Get the declared hand with this id.
+    ///
+    /// `id` may be given as a pointer (`#hand1`) or as the plain id (`hand1`).
+    #[must_use]
+    pub fn hand(&self, id: &str) -> Option<&Hand> {
+        let id = id.strip_prefix('#').unwrap_or(id);
+        self.hands.iter().find(|h| h.id == id)
+    }
+
+    /// Whether a [`Version`] may name `hand` as its scribal hand.
+    ///
+    /// This is the case if `hand` is declared, or if no hands are declared at all. The latter
+    /// keeps manuscripts that only describe their hands in the summary valid.
+    #[must_use]
+    pub fn allows_hand(&self, hand: &str) -> bool {
+        self.hands.is_empty() || self.hand(hand).is_some()
+    }
+}
+
+/// A single change made to a file, e.g. the transcription or review of a page.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Change {
+    /// The person responsible for this change
+    pub who: Option<String>,
+    /// The date of this change
+    pub when: Option<Date>,
+    /// The state of the changed text after this change, e.g. [`PageStatus::Transcribed`]
+    pub status: Option<PageStatus>,
+    /// The name of the changed page, if this change concerns a single page
+    pub page: Option<String>,
+    /// A human readable description of this change
+    pub description: Option<String>,
+}
+
+/// An alternative identifier of a manuscript, e.g. its number in a catalogue.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Identifier {
+    /// The catalogue this identifier belongs to
+    pub id_type: Option<IdNoType>,
+    /// The actual identifier
+    pub idno: String,
+}
+
+pub type IdNoType = crate::schema::IdNoType;
+pub type Material = crate::schema::Material;
+pub type Extent = crate::schema::Extent;
+pub type Layout = crate::schema::Layout;
+pub type Count = crate::schema::Count;
+pub type ContentItem = crate::schema::MsItem;
+pub type PageStatus = crate::schema::PageStatus;
+pub type Facsimile = crate::schema::Facsimile;
+pub type Surface = crate::schema::Surface;
+pub type Graphic = crate::schema::Graphic;
+pub type Zone = crate::schema::Zone;
+pub type Point = crate::schema::Point;
+pub type Points = crate::schema::Points;
+
+/// A single scribal hand present in a manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Hand {
+    /// The name by which this hand is referred to in [`Version::hand`]
+    pub id: String,
+    /// A human readable description of this hand
+    pub description: Option<String>,
+    /// The scribe this hand belongs to
+    pub scribe: Option<String>,
+    /// The script this hand is written in
+    pub script: Option<String>,
+    /// The earliest possible date of this hand
+    pub not_before: Option<Date>,
+    /// The latest possible date of this hand
+    pub not_after: Option<Date>,
+}
+
+/// A date as used in TEI dating attributes (`@when`, `@notBefore`, `@notAfter`).
+///
+/// These are ISO dates with optional month and day, i.e. `YYYY`, `YYYY-MM` or `YYYY-MM-DD`. Years
+/// BCE are negative (`-0200`). Dates order chronologically, where a date without month (day)
+/// comes before all dates in the same year (month).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Date {
+    pub year: i32,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+impl Date {
+    /// The date consisting only of `year`.
+    #[must_use]
+    pub fn year(year: i32) -> Self {
+        Self {
+            year,
+            month: None,
+            day: None,
+        }
+    }
+
+    /// The first day this date may refer to, as `(year, month, day)`.
+    fn first_day(self) -> (i32, u8, u8) {
+        (self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+    }
+
+    /// The last day this date may refer to, as `(year, month, day)`.
+    fn last_day(self) -> (i32, u8, u8) {
+        (self.year, self.month.unwrap_or(12), self.day.unwrap_or(31))
+    }
+}
+/// The ways a [`Date`] can be invalid.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DateError {
+    /// The date is not of the form `YYYY`, `YYYY-MM` or `YYYY-MM-DD`
+    Malformed,
+    /// The month is not between 1 and 12
+    InvalidMonth(u8),
+    /// The day does not exist in its month, e.g. `1008-02-31`
+    InvalidDay(u8),
+}
+impl core::fmt::Display for DateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Malformed => {
+                write!(
+                    f,
+                    "The date is not of the form YYYY, YYYY-MM or YYYY-MM-DD."
+                )
+            }
+            Self::InvalidMonth(x) => {
+                write!(f, "There is no month {x}.")
+            }
+            Self::InvalidDay(x) => {
+                write!(f, "There is no day {x} in the given month.")
+            }
+        }
+    }
+}
+impl core::error::Error for DateError {}
+
+/// The number of days in `month` of `year` in the proleptic Gregorian calendar.
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl core::str::FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let mut parts = unsigned.split('-');
+        let year_str = parts.next().ok_or(DateError::Malformed)?;
+        if year_str.len() < 4 || !year_str.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(DateError::Malformed);
+        }
+        let year: i32 = year_str.parse().map_err(|_| DateError::Malformed)?;
+        let year = if negative { -year } else { year };
+        let mut part = || -> Result<Option<u8>, DateError> {
+            parts
+                .next()
+                .map(|p| {
+                    if p.len() != 2 || !p.bytes().all(|b| b.is_ascii_digit()) {
+                        return Err(DateError::Malformed);
+                    }
+                    p.parse::<u8>().map_err(|_| DateError::Malformed)
+                })
+                .transpose()
+        };
+        let month = part()?;
+        let day = part()?;
+        if parts.next().is_some() {
+            return Err(DateError::Malformed);
+        }
+        if let Some(month) = month
+            && !(1..=12).contains(&month)
+        {
+            return Err(DateError::InvalidMonth(month));
+        }
+        if let (Some(month), Some(day)) = (month, day)
+            && !(1..=days_in_month(year, month)).contains(&day)
+        {
+            return Err(DateError::InvalidDay(day));
+        }
+        Ok(Self { year, month, day })
+    }
+}
+impl core::fmt::Display for Date {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.year < 0 {
+            write!(f, "-")?;
+        }
+        write!(f, "{:04}", self.year.unsigned_abs())?;
+        if let Some(month) = self.month {
+            write!(f, "-{month:02}")?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{day:02}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A range of possible dates, both ends inclusive.
+///
+/// An end that is `None` is open. A partial date covers its entire year (month), so the range from
+/// `0900` to `0999` includes `0999-12-31`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DateRange {
+    /// The earliest possible date
+    pub not_before: Option<Date>,
+    /// The latest possible date
+    pub not_after: Option<Date>,
+}
+impl DateRange {
+    /// The range containing only `date`.
+    #[must_use]
+    pub fn exact(date: Date) -> Self {
+        Self {
+            not_before: Some(date),
+            not_after: Some(date),
+        }
+    }
+
+    /// Whether this range is a single date.
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        self.not_before.is_some() && self.not_before == self.not_after
+    }
+
+    /// Whether no date can be in this range, because [`Self::not_before`] is after
+    /// [`Self::not_after`].
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match (self.not_before, self.not_after) {
+            (Some(not_before), Some(not_after)) => not_before.first_day() > not_after.last_day(),
+            _ => false,
+        }
+    }
+
+    /// Whether every date in this range is before `date`.
+    #[must_use]
+    pub fn is_before(&self, date: Date) -> bool {
+        self.not_after
+            .is_some_and(|d| d.last_day() < date.first_day())
+    }
+
+    /// Whether every date in this range is after `date`.
+    #[must_use]
+    pub fn is_after(&self, date: Date) -> bool {
+        self.not_before
+            .is_some_and(|d| d.first_day() > date.last_day())
+    }
+
+    /// Whether `date` may be in this range.
+    #[must_use]
+    pub fn contains(&self, date: Date) -> bool {
+        !self.is_before(date) && !self.is_after(date)
+    }
+
+    /// Whether this range and `other` have at least one date in common.
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !other.not_before.is_some_and(|d| self.is_before(d))
+            && !other.not_after.is_some_and(|d| self.is_after(d))
+    }
 }
 
 /// The entire transcribed text body
@@ -47,9 +511,56 @@ pub struct Page {
     pub lang: Option<String>,
     /// the page number
     pub n: String,
+    /// How far the transcription of this page has progressed
+    pub status: Option<PageStatus>,
+    /// Pointer to the [`Surface`] showing this page
+    pub facs: Option<String>,
     /// The columns present in this page
+    ///
+    /// This is empty IFF the page is missing from the manuscript.
     pub columns: Vec<Column>,
 }
+impl Page {
+    /// A page is missing if it is known to have existed but is now lost entirely.
+    ///
+    /// These pages are encoded as an empty `<div type="page" n="{pagename}"/>`.
+    #[must_use]
+    pub fn is_missing(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Aggregate the certainty of all [`Uncertain`] and [`Lacuna`] blocks on this page.
+    #[must_use]
+    pub fn certainty(&self) -> CertaintySummary {
+        let mut res = CertaintySummary::default();
+        for block in self
+            .columns
+            .iter()
+            .flat_map(|c| &c.lines)
+            .flat_map(|l| &l.blocks)
+        {
+            match block {
+                InlineBlock::Uncertain(x) => res.add(x.cert),
+                InlineBlock::Lacuna(x) => res.add(x.cert),
+                _ => {}
+            }
+        }
+        res
+    }
+
+    /// The number of columns on this page, including those missing at its end.
+    ///
+    /// This is the number of the last column plus the columns in gaps and spaces at its end.
+    #[must_use]
+    pub fn column_count(&self) -> usize {
+        self.columns.last().map_or(0, |c| {
+            count(
+                c.n,
+                c.lines.last().map_or(0, |l| l.extent(ExtentUnit::Column)),
+            )
+        })
+    }
+}
 
 /// A complete column in the manuscript.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -58,9 +569,22 @@ pub struct Column {
     pub lang: Option<String>,
     /// the column number
     pub n: i32,
+    /// Pointer to the [`Zone`] showing this column
+    pub facs: Option<String>,
     /// The lines in this column
     pub lines: Vec<Line>,
 }
+impl Column {
+    /// The number of lines in this column, including those missing at its end.
+    ///
+    /// This is the number of the last line plus the lines in gaps and spaces at its end.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.lines
+            .last()
+            .map_or(0, |l| count(l.n, l.extent(ExtentUnit::Line)))
+    }
+}
 
 /// A complete line in the manuscript.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -69,9 +593,31 @@ pub struct Line {
     pub lang: Option<String>,
     /// the line number
     pub n: i32,
+    /// Pointer to the [`Zone`] showing this line
+    pub facs: Option<String>,
     /// The actual text elements contained in this line
     pub blocks: Vec<InlineBlock>,
 }
+impl Line {
+    /// The total extent of the gaps and spaces in this line measured in `unit`.
+    fn extent(&self, unit: ExtentUnit) -> usize {
+        self.blocks
+            .iter()
+            .filter_map(|b| match b {
+                InlineBlock::Lacuna(x) if x.unit == unit => Some(x.n),
+                InlineBlock::Space(x) if x.unit == unit => Some(x.quantity),
+                _ => None,
+            })
+            .map(|n| usize::try_from(n).unwrap_or(0))
+            .fold(0, usize::saturating_add)
+    }
+}
+
+/// The number of columns or lines, given the number `n` of the last one and the `extent` missing
+/// after it.
+fn count(n: i32, extent: usize) -> usize {
+    usize::try_from(n).unwrap_or(0).saturating_add(extent)
+}
 
 /// A block of text or marked up text.
 ///
@@ -153,7 +699,7 @@ pub struct Version {
     pub lang: Option<String>,
     /// The scribal hand responsible for this reading
     ///
-    /// The different hands should be explained in the [`<handDesc>`](crate::schema::HandDesc) in the header.
+    /// This should refer to one of the [`hands`](Meta::hands) in the header.
     pub hand: Option<String>,
     /// The actual text of this reading
     pub content: String,
@@ -162,3 +708,161 @@ pub struct Version {
 pub type Lacuna = crate::schema::Gap;
 pub type Space = crate::schema::Space;
 pub type ExtentUnit = crate::schema::ExtentUnit;
+pub type Certainty = crate::schema::Certainty;
+
+/// The number of pages in each [`PageStatus`].
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct StatusCounts {
+    /// Number of pages with [`PageStatus::Draft`]
+    pub draft: usize,
+    /// Number of pages with [`PageStatus::Transcribed`]
+    pub transcribed: usize,
+    /// Number of pages with [`PageStatus::Reviewed`]
+    pub reviewed: usize,
+    /// Number of pages with [`PageStatus::Approved`]
+    pub approved: usize,
+    /// Number of pages without `@status`
+    pub unspecified: usize,
+}
+impl StatusCounts {
+    /// Add a single page with the given status.
+    pub fn add(&mut self, status: Option<PageStatus>) {
+        match status {
+            None => self.unspecified += 1,
+            Some(PageStatus::Draft) => self.draft += 1,
+            Some(PageStatus::Transcribed) => self.transcribed += 1,
+            Some(PageStatus::Reviewed) => self.reviewed += 1,
+            Some(PageStatus::Approved) => self.approved += 1,
+        }
+    }
+
+    /// The total number of pages.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.draft + self.transcribed + self.reviewed + self.approved + self.unspecified
+    }
+}
+
+/// The certainty of a number of [`Uncertain`] and [`Lacuna`] blocks, aggregated.
+///
+/// This is intended for quality reports and contains the number of blocks for each level of
+/// [`Certainty`], as well as the range and mean of all numeric probabilities.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy)]
+pub struct CertaintySummary {
+    /// Number of blocks with [`Certainty::High`]
+    pub high: usize,
+    /// Number of blocks with [`Certainty::Medium`]
+    pub medium: usize,
+    /// Number of blocks with [`Certainty::Low`]
+    pub low: usize,
+    /// Number of blocks with [`Certainty::Unknown`]
+    pub unknown: usize,
+    /// Number of blocks without `@cert`
+    pub unspecified: usize,
+    /// Number of blocks with a [`Certainty::Probability`]
+    pub probabilities: usize,
+    /// The sum of all numeric probabilities
+    pub probability_sum: f64,
+    /// The lowest numeric probability
+    pub min_probability: Option<f64>,
+    /// The highest numeric probability
+    pub max_probability: Option<f64>,
+}
+impl CertaintySummary {
+    /// Add a single block with the given certainty to this summary.
+    pub fn add(&mut self, cert: Option<Certainty>) {
+        match cert {
+            None => self.unspecified += 1,
+            Some(Certainty::High) => self.high += 1,
+            Some(Certainty::Medium) => self.medium += 1,
+            Some(Certainty::Low) => self.low += 1,
+            Some(Certainty::Unknown) => self.unknown += 1,
+            Some(Certainty::Probability(p)) => {
+                let p = p.value();
+                self.probabilities += 1;
+                self.probability_sum += p;
+                self.min_probability = Some(self.min_probability.map_or(p, |m| m.min(p)));
+                self.max_probability = Some(self.max_probability.map_or(p, |m| m.max(p)));
+            }
+        }
+    }
+
+    /// The total number of blocks in this summary.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.high + self.medium + self.low + self.unknown + self.unspecified + self.probabilities
+    }
+
+    /// The mean of all numeric probabilities, if there are any.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mean_probability(&self) -> Option<f64> {
+        (self.probabilities > 0).then(|| self.probability_sum / self.probabilities as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn date_parse() {
+        assert_eq!("0900".parse(), Ok(Date::year(900)));
+        assert_eq!(
+            "1008-03-14".parse(),
+            Ok(Date {
+                year: 1008,
+                month: Some(3),
+                day: Some(14),
+            })
+        );
+        assert_eq!("-0200".parse(), Ok(Date::year(-200)));
+        assert_eq!("900".parse::<Date>(), Err(DateError::Malformed));
+        assert_eq!("1008-13".parse::<Date>(), Err(DateError::InvalidMonth(13)));
+        assert_eq!("1008-1-1".parse::<Date>(), Err(DateError::Malformed));
+        assert_eq!("10th century".parse::<Date>(), Err(DateError::Malformed));
+        assert_eq!("1008-02-31".parse::<Date>(), Err(DateError::InvalidDay(31)));
+        assert_eq!("1008-04-00".parse::<Date>(), Err(DateError::InvalidDay(0)));
+        assert!("1008-02-29".parse::<Date>().is_ok());
+        assert_eq!("1100-02-29".parse::<Date>(), Err(DateError::InvalidDay(29)));
+        assert!("-0004-02-29".parse::<Date>().is_ok());
+        for x in ["0900", "1008-03", "1008-03-14", "-0200"] {
+            assert_eq!(x.parse::<Date>().unwrap().to_string(), x);
+        }
+    }
+
+    #[test]
+    fn date_range() {
+        let tenth = DateRange {
+            not_before: Some(Date::year(900)),
+            not_after: Some(Date::year(999)),
+        };
+        assert!(tenth.is_before(Date::year(1000)));
+        assert!(!tenth.is_before(Date::year(999)));
+        assert!(tenth.is_after(Date::year(899)));
+        assert!(tenth.contains(Date::year(950)));
+        assert!(!tenth.contains(Date::year(1000)));
+        let open = DateRange {
+            not_before: Some(Date::year(990)),
+            not_after: None,
+        };
+        assert!(tenth.overlaps(&open));
+        assert!(open.overlaps(&tenth));
+        assert!(!open.is_before(Date::year(3000)));
+        let later = DateRange::exact(Date::year(1008));
+        assert!(!tenth.overlaps(&later));
+        assert!(open.overlaps(&later));
+        assert!(later.is_exact());
+        assert!(!tenth.is_empty() && !open.is_empty());
+        let inverted = DateRange {
+            not_before: Some(Date::year(1000)),
+            not_after: Some(Date::year(999)),
+        };
+        assert!(inverted.is_empty());
+        assert!(tenth.contains(Date {
+            year: 999,
+            month: Some(12),
+            day: Some(31),
+        }));
+    }
+}
@@ -0,0 +1,928 @@
+//! Best-effort reading of broken files.
+//!
+//! [`from_xml`](crate::from_xml) fails on the first problem in a file, so critic cannot open it at
+//! all. The functions in this module instead read everything they can and report every [`Issue`]
+//! they had to work around, so the transcriber can fix the problems in the editor:
+//!
+//! - pages, columns and lines that cannot be read are left out, and their XML is returned in the
+//!   [`Issue::Dropped`]
+//! - pages that are not in order are left out
+//! - column and line numbers that do not fit the text are renumbered
+//! - unknown damage agents and gap reasons are kept as they are
+//! - hands used but not declared are declared in the header
+//! - `@facs` pointing nowhere are removed
+//! - invalid dates in the header are removed
+//!
+//! The header itself has to be readable, otherwise the file is rejected as usual.
+
+use std::ops::Range;
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::{
+    ConversionError, Options,
+    denorm::{NormalizationError, PUBLICATION_STATEMENT},
+    limits,
+    milestone::attribute,
+    normalized, schema, streamed,
+    vocabulary::{Vocabulary, normalize_term},
+};
+
+/// The language used if the body does not declare one (BCP 47 for "undetermined").
+pub const UNDETERMINED_LANGUAGE: &str = "und";
+
+/// A place in the text.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Location {
+    /// The name of the page, if it could be read
+    pub page: Option<String>,
+    pub column: Option<i32>,
+    pub line: Option<i32>,
+}
+impl Location {
+    fn page(page: &str) -> Self {
+        Self {
+            page: Some(page.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn column(page: &str, column: Option<i32>) -> Self {
+        Self {
+            column,
+            ..Self::page(page)
+        }
+    }
+
+    fn line(page: &str, column: Option<i32>, line: Option<i32>) -> Self {
+        Self {
+            line,
+            ..Self::column(page, column)
+        }
+    }
+}
+impl core::fmt::Display for Location {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match &self.page {
+            Some(page) => write!(f, "page {page}")?,
+            None => write!(f, "an unnamed page")?,
+        }
+        if let Some(column) = self.column {
+            write!(f, ", column {column}")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, ", line {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Something that was dropped or changed while recovering a file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Issue {
+    /// A part of the text was left out
+    Dropped {
+        location: Location,
+        /// The XML that was left out, if there is any
+        xml: Option<String>,
+        /// Why it was left out
+        reason: String,
+    },
+    /// A column or line got a different number
+    Renumbered {
+        /// The location with the original number
+        location: Location,
+        /// The new number
+        to: i32,
+    },
+    /// A column or line `<div>` had a different `@type`
+    IncorrectDivType { location: Location, found: String },
+    /// A damage agent or gap reason is not in the vocabulary, but was kept
+    UnknownTerm { location: Location, term: String },
+    /// A hand was used in a correction but not declared, so a declaration was added
+    UndeclaredHand(String),
+    /// A `@facs` did not point into the facsimile and was removed
+    UnknownFacs { location: Location, facs: String },
+    /// A date in the header was not valid, or conflicted with another date, and was removed
+    InvalidDate(String),
+    /// The publication statement was replaced by [`PUBLICATION_STATEMENT`]
+    PublicationStmtIncorrect,
+    /// The body had no `@xml:lang`, so [`UNDETERMINED_LANGUAGE`] is used
+    NoDefaultLanguage,
+}
+impl core::fmt::Display for Issue {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Dropped {
+                location, reason, ..
+            } => {
+                write!(f, "Left out a part of {location}: {reason}")
+            }
+            Self::Renumbered { location, to } => {
+                write!(f, "Renumbered {location} to {to}.")
+            }
+            Self::IncorrectDivType { location, found } => {
+                write!(
+                    f,
+                    "The div of {location} has the incorrect type \"{found}\"."
+                )
+            }
+            Self::UnknownTerm { location, term } => {
+                write!(
+                    f,
+                    "Kept \"{term}\" in {location}, although it is not in the vocabulary."
+                )
+            }
+            Self::UndeclaredHand(x) => {
+                write!(
+                    f,
+                    "Declared the hand \"{x}\", which was used but not declared."
+                )
+            }
+            Self::UnknownFacs { location, facs } => {
+                write!(
+                    f,
+                    "Removed the facs \"{facs}\" of {location}, which does not point into the facsimile."
+                )
+            }
+            Self::InvalidDate(x) => {
+                write!(f, "Removed the invalid date \"{x}\" from the header.")
+            }
+            Self::PublicationStmtIncorrect => {
+                write!(f, "Replaced the incorrect publication statement.")
+            }
+            Self::NoDefaultLanguage => {
+                write!(
+                    f,
+                    "The text has no default language, used \"{UNDETERMINED_LANGUAGE}\" instead."
+                )
+            }
+        }
+    }
+}
+
+/// A manuscript read from a broken file, together with everything that had to be changed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Recovered {
+    pub manuscript: streamed::Manuscript,
+    /// Everything that was dropped or changed, empty if the file is correct
+    pub issues: Vec<Issue>,
+}
+
+/// Like [`crate::from_xml`], but recover from problems in the text.
+///
+/// # Errors
+/// Only if the XML is not well-formed or the header cannot be read.
+pub fn from_xml(buf_reader: impl std::io::BufRead) -> Result<Recovered, ConversionError> {
+    from_xml_with_options(buf_reader, &Options::default())
+}
+
+/// Like [`from_xml`], but with the given [`Options`].
+///
+/// Terms not in [`Options::vocabulary`] are kept and reported as [`Issue::UnknownTerm`]. Input
+/// exceeding the [`Options::limits`] is rejected, not recovered.
+///
+/// # Errors
+/// Only if the XML is not well-formed, the header cannot be read or the input exceeds the
+/// [`Options::limits`].
+pub fn from_xml_with_options(
+    mut buf_reader: impl std::io::BufRead,
+    options: &Options,
+) -> Result<Recovered, ConversionError> {
+    let read_error = |e: std::io::Error| ConversionError::DeSer(quick_xml::Error::from(e).into());
+    let mut xml = String::new();
+    match &options.limits {
+        Some(limits) => {
+            let bytes = limits::read_checked(buf_reader, limits, limits::Root::Tei)?;
+            std::io::Read::read_to_string(&mut bytes.as_slice(), &mut xml).map_err(read_error)?;
+        }
+        None => {
+            buf_reader.read_to_string(&mut xml).map_err(read_error)?;
+        }
+    }
+    let invalid_xml = |e: quick_xml::Error| ConversionError::DeSer(e.into());
+
+    let mut issues = vec![];
+    let Content {
+        range: body,
+        divs: pages,
+    } = div_children(&xml, b"body")
+        .map_err(invalid_xml)?
+        .ok_or_else(|| {
+            invalid_xml(quick_xml::Error::Syntax(
+                quick_xml::errors::SyntaxError::UnclosedTag,
+            ))
+        })?;
+
+    // read everything but the pages, with a placeholder page because the body may not be empty
+    let skeleton = format!(
+        "{}<div type=\"page\" n=\"\"/>{}",
+        &xml[..body.start],
+        &xml[body.end..]
+    );
+    let mut tei: schema::Tei =
+        quick_xml::de::from_str(&skeleton).map_err(ConversionError::DeSer)?;
+    tei.text.body.pages.clear();
+    let tei = tei.trim();
+    let pages = pages
+        .into_iter()
+        .filter_map(|range| read_page(&xml[range], &mut issues))
+        .map(schema::Page::trim)
+        .collect::<Vec<_>>();
+
+    let meta: normalized::Meta = repair_header(tei.tei_header, &mut issues)
+        .try_into()
+        .map_err(ConversionError::Norm)?;
+    let lang = tei.text.body.lang.unwrap_or_else(|| {
+        issues.push(Issue::NoDefaultLanguage);
+        UNDETERMINED_LANGUAGE.to_string()
+    });
+    let mut normalizer = Normalizer {
+        vocabulary: options.vocabulary.clone(),
+        issues,
+    };
+    let mut ms = normalized::Manuscript {
+        meta,
+        text: normalized::Text {
+            lang,
+            pages: normalizer.pages(pages),
+        },
+        facsimile: tei.facsimile.unwrap_or_default(),
+    };
+    let mut issues = normalizer.issues;
+    repair_facs(&mut ms, &mut issues);
+    repair_hands(&mut ms, &mut issues);
+
+    Ok(Recovered {
+        manuscript: ms.try_into().map_err(ConversionError::Stream)?,
+        issues,
+    })
+}
+
+/// Where the content of an element is in the XML.
+struct Content {
+    /// The range of the whole content
+    range: Range<usize>,
+    /// The ranges of the `<div>` children
+    divs: Vec<Range<usize>>,
+}
+
+/// Find the content of the first element named `name` in `xml`.
+fn div_children(xml: &str, name: &[u8]) -> Result<Option<Content>, quick_xml::Error> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    reader.config_mut().expand_empty_elements = true;
+    let position = |reader: &quick_xml::Reader<&[u8]>| {
+        usize::try_from(reader.buffer_position()).unwrap_or(usize::MAX)
+    };
+
+    // the depth of the element, once found
+    let mut element_depth = None;
+    let mut depth = 0_usize;
+    let mut content_start = 0;
+    let mut child_start = 0;
+    let mut children = vec![];
+    loop {
+        let before = position(&reader);
+        match reader.read_event()? {
+            Event::Start(e) => {
+                depth += 1;
+                match element_depth {
+                    None if e.local_name().as_ref() == name => {
+                        element_depth = Some(depth);
+                        content_start = position(&reader);
+                    }
+                    Some(d) if depth == d + 1 && e.local_name().as_ref() == b"div" => {
+                        child_start = before;
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                match element_depth {
+                    Some(d) if depth == d => {
+                        return Ok(Some(Content {
+                            range: content_start..before,
+                            divs: children,
+                        }));
+                    }
+                    Some(d) if depth == d + 1 && e.local_name().as_ref() == b"div" => {
+                        children.push(child_start..position(&reader));
+                    }
+                    _ => {}
+                }
+                depth -= 1;
+            }
+            Event::Eof => return Ok(None),
+            _ => {}
+        }
+    }
+}
+
+/// The XML in `xml[content]` that is not in one of the `children`.
+fn outside_children(xml: &str, content: &Range<usize>, children: &[Range<usize>]) -> String {
+    let mut res = String::new();
+    let mut start = content.start;
+    for child in children {
+        res.push_str(xml[start..child.start].trim());
+        start = child.end;
+    }
+    res.push_str(xml[start..content.end].trim());
+    res
+}
+
+/// The start tag of the element in `xml`.
+fn start_tag(xml: &str) -> Option<BytesStart<'static>> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(e) | Event::Empty(e) => return Some(e.into_owned()),
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Read the page in `xml`, leaving out the columns or lines that cannot be read.
+fn read_page(xml: &str, issues: &mut Vec<Issue>) -> Option<schema::Page> {
+    let error = match quick_xml::de::from_str::<schema::Page>(xml) {
+        Ok(page) => return Some(page),
+        Err(e) => e.to_string(),
+    };
+    let name = start_tag(xml).and_then(|e| attribute(&e, "n"));
+    let drop_page = |issues: &mut Vec<Issue>| {
+        issues.push(Issue::Dropped {
+            location: Location {
+                page: name.clone(),
+                ..Default::default()
+            },
+            xml: Some(xml.to_string()),
+            reason: error.clone(),
+        });
+        None
+    };
+    let Ok(Some(Content {
+        range: content,
+        divs: columns,
+    })) = div_children(xml, b"div")
+    else {
+        return drop_page(issues);
+    };
+    // the page without its columns
+    let Ok(mut page) =
+        quick_xml::de::from_str::<schema::Page>(&format!("{}</div>", &xml[..content.start]))
+    else {
+        return drop_page(issues);
+    };
+    let issues_before = issues.len();
+    for range in &columns {
+        if let Some(column) = read_column(&xml[range.clone()], &page.n, issues) {
+            page.columns.push(column);
+        }
+    }
+    let outside = outside_children(xml, &content, &columns);
+    if issues.len() == issues_before || !outside.is_empty() {
+        issues.push(Issue::Dropped {
+            location: Location::page(&page.n),
+            xml: (!outside.is_empty()).then_some(outside),
+            reason: error,
+        });
+    }
+    Some(page)
+}
+
+/// Read the column in `xml`, leaving out the lines that cannot be read.
+fn read_column(xml: &str, page: &str, issues: &mut Vec<Issue>) -> Option<schema::Column> {
+    let error = match quick_xml::de::from_str::<schema::Column>(xml) {
+        Ok(column) => return Some(column),
+        Err(e) => e.to_string(),
+    };
+    let start = start_tag(xml);
+    let n = start
+        .as_ref()
+        .and_then(|e| attribute(e, "n"))
+        .and_then(|n| n.parse().ok());
+    let (
+        Some(start),
+        Ok(Some(Content {
+            range: content,
+            divs: lines,
+        })),
+    ) = (start, div_children(xml, b"div"))
+    else {
+        issues.push(Issue::Dropped {
+            location: Location::column(page, n),
+            xml: Some(xml.to_string()),
+            reason: error,
+        });
+        return None;
+    };
+    let mut column = schema::Column {
+        lang: attribute(&start, "xml:lang"),
+        div_type: attribute(&start, "type").unwrap_or_default(),
+        n,
+        facs: attribute(&start, "facs"),
+        lines: vec![],
+    };
+    let issues_before = issues.len();
+    for range in &lines {
+        let line = &xml[range.clone()];
+        match quick_xml::de::from_str::<schema::Line>(line) {
+            Ok(line) => column.lines.push(line),
+            Err(e) => issues.push(Issue::Dropped {
+                location: Location::line(
+                    page,
+                    n,
+                    start_tag(line)
+                        .and_then(|e| attribute(&e, "n"))
+                        .and_then(|n| n.parse().ok()),
+                ),
+                xml: Some(line.to_string()),
+                reason: e.to_string(),
+            }),
+        }
+    }
+    let outside = outside_children(xml, &content, &lines);
+    if issues.len() == issues_before || !outside.is_empty() {
+        issues.push(Issue::Dropped {
+            location: Location::column(page, n),
+            xml: (!outside.is_empty()).then_some(outside),
+            reason: error,
+        });
+    }
+    Some(column)
+}
+
+/// Replace the parts of the header that would fail normalization.
+fn repair_header(mut header: schema::TeiHeader, issues: &mut Vec<Issue>) -> schema::TeiHeader {
+    if header.file_desc.publication_stmt.p != PUBLICATION_STATEMENT {
+        header.file_desc.publication_stmt.p = PUBLICATION_STATEMENT.to_string();
+        issues.push(Issue::PublicationStmtIncorrect);
+    }
+    let ms_desc = &mut header.file_desc.source_desc.ms_desc;
+    if let Some(orig_date) = ms_desc
+        .history
+        .as_mut()
+        .and_then(|h| h.origin.as_mut())
+        .and_then(|o| o.orig_date.as_mut())
+    {
+        repair_date(&mut orig_date.when, issues);
+        if orig_date.when.is_some() {
+            // @when excludes the range
+            issues.extend(orig_date.not_before.take().map(Issue::InvalidDate));
+            issues.extend(orig_date.not_after.take().map(Issue::InvalidDate));
+        }
+        repair_date_range(&mut orig_date.not_before, &mut orig_date.not_after, issues);
+    }
+    for hand in ms_desc
+        .phys_desc
+        .hand_desc
+        .iter_mut()
+        .flat_map(|d| &mut d.hand_notes)
+    {
+        repair_date_range(&mut hand.not_before, &mut hand.not_after, issues);
+    }
+    for change in header.revision_desc.iter_mut().flat_map(|r| &mut r.change) {
+        repair_date(&mut change.when, issues);
+    }
+    header
+}
+
+/// Remove `date` if it is not valid.
+fn repair_date(date: &mut Option<String>, issues: &mut Vec<Issue>) {
+    if let Some(d) = date
+        && d.parse::<normalized::Date>().is_err()
+    {
+        issues.push(Issue::InvalidDate(d.clone()));
+        *date = None;
+    }
+}
+
+/// Remove invalid dates from a range, and the whole range if it is empty.
+fn repair_date_range(
+    not_before: &mut Option<String>,
+    not_after: &mut Option<String>,
+    issues: &mut Vec<Issue>,
+) {
+    repair_date(not_before, issues);
+    repair_date(not_after, issues);
+    let range = normalized::DateRange {
+        not_before: not_before.as_ref().and_then(|d| d.parse().ok()),
+        not_after: not_after.as_ref().and_then(|d| d.parse().ok()),
+    };
+    if range.is_empty() {
+        issues.extend(not_before.take().map(Issue::InvalidDate));
+        issues.extend(not_after.take().map(Issue::InvalidDate));
+    }
+}
+
+/// Normalizes the text, numbering columns and lines as they are streamed.
+struct Normalizer {
+    /// The vocabulary, if any, extended by the unknown terms found
+    vocabulary: Option<Vocabulary>,
+    issues: Vec<Issue>,
+}
+impl Normalizer {
+    fn pages(&mut self, pages: Vec<schema::Page>) -> Vec<normalized::Page> {
+        let mut res = Vec::<normalized::Page>::with_capacity(pages.len());
+        for page in pages {
+            if let Some(last) = res.last()
+                && last.n >= page.n
+            {
+                self.issues.push(Issue::Dropped {
+                    location: Location::page(&page.n),
+                    xml: quick_xml::se::to_string_with_root("div", &page).ok(),
+                    reason: NormalizationError::PageNumbersNotOrdered(last.n.clone(), page.n)
+                        .to_string(),
+                });
+                continue;
+            }
+            let columns = self.columns(&page.n, page.columns);
+            res.push(normalized::Page {
+                lang: page.lang,
+                n: page.n,
+                status: page.status,
+                facs: page.facs,
+                columns,
+            });
+        }
+        res
+    }
+
+    fn columns(&mut self, page: &str, columns: Vec<schema::Column>) -> Vec<normalized::Column> {
+        let mut res = Vec::with_capacity(columns.len());
+        let mut next_column_nr = 1;
+        for column in columns {
+            let location = Location::column(page, column.n);
+            if column.lines.is_empty() {
+                self.issues.push(Issue::Dropped {
+                    location,
+                    xml: None,
+                    reason: "The column has no lines.".to_string(),
+                });
+                continue;
+            }
+            if column.div_type != "column" {
+                self.issues.push(Issue::IncorrectDivType {
+                    location: location.clone(),
+                    found: column.div_type,
+                });
+            }
+            if column.n.is_some_and(|n| n != next_column_nr) {
+                self.issues.push(Issue::Renumbered {
+                    location,
+                    to: next_column_nr,
+                });
+            }
+            let lines = self.lines(page, next_column_nr, column.lines);
+            // column-spanning lacunae and spaces take up the following column numbers
+            let skipped: i32 = lines
+                .iter()
+                .flat_map(|l| &l.blocks)
+                .map(|b| extent(b, normalized::ExtentUnit::Column))
+                .fold(0, i32::saturating_add);
+            res.push(normalized::Column {
+                lang: column.lang,
+                n: next_column_nr,
+                facs: column.facs,
+                lines,
+            });
+            next_column_nr = next_column_nr.saturating_add(1).saturating_add(skipped);
+        }
+        res
+    }
+
+    fn lines(
+        &mut self,
+        page: &str,
+        column: i32,
+        lines: Vec<schema::Line>,
+    ) -> Vec<normalized::Line> {
+        let mut res = Vec::with_capacity(lines.len());
+        let mut next_line_nr = 1;
+        for line in lines {
+            let location = Location::line(page, Some(column), line.n);
+            if line.div_type != "line" {
+                self.issues.push(Issue::IncorrectDivType {
+                    location: location.clone(),
+                    found: line.div_type,
+                });
+            }
+            if line.n.is_some_and(|n| n != next_line_nr) {
+                self.issues.push(Issue::Renumbered {
+                    location,
+                    to: next_line_nr,
+                });
+            }
+            let location = Location::line(page, Some(column), Some(next_line_nr));
+            let blocks = line
+                .blocks
+                .into_iter()
+                .filter_map(|b| self.block(b, &location))
+                .collect::<Vec<_>>();
+            // line-spanning lacunae and spaces take up the following line numbers
+            let skipped: i32 = blocks
+                .iter()
+                .map(|b| extent(b, normalized::ExtentUnit::Line))
+                .fold(0, i32::saturating_add);
+            res.push(normalized::Line {
+                lang: line.lang,
+                n: next_line_nr,
+                facs: line.facs,
+                blocks,
+            });
+            next_line_nr = next_line_nr.saturating_add(1).saturating_add(skipped);
+        }
+        res
+    }
+
+    /// Normalize `block`, or leave it out if that is not possible.
+    fn block(
+        &mut self,
+        block: schema::InlineBlock,
+        location: &Location,
+    ) -> Option<normalized::InlineBlock> {
+        match (block.clone(), self.vocabulary.as_ref()).try_into() {
+            Ok(x) => Some(x),
+            Err(NormalizationError::UnknownDamageAgent(term))
+                if let Some(vocabulary) = &mut self.vocabulary
+                    && !vocabulary.damage_agents.contains(&normalize_term(&term)) =>
+            {
+                vocabulary.damage_agents.push(normalize_term(&term));
+                self.unknown_term(block, location, term)
+            }
+            Err(NormalizationError::UnknownGapReason(term))
+                if let Some(vocabulary) = &mut self.vocabulary
+                    && !vocabulary.gap_reasons.contains(&normalize_term(&term)) =>
+            {
+                vocabulary.gap_reasons.push(normalize_term(&term));
+                self.unknown_term(block, location, term)
+            }
+            Err(e) => {
+                self.issues.push(Issue::Dropped {
+                    location: location.clone(),
+                    xml: None,
+                    reason: e.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Report `term`, which was just added to the vocabulary, and normalize `block` again.
+    fn unknown_term(
+        &mut self,
+        block: schema::InlineBlock,
+        location: &Location,
+        term: String,
+    ) -> Option<normalized::InlineBlock> {
+        self.issues.push(Issue::UnknownTerm {
+            location: location.clone(),
+            term,
+        });
+        self.block(block, location)
+    }
+}
+
+/// The number of `unit`s taken up by `block`, if it is a lacuna or space.
+fn extent(block: &normalized::InlineBlock, unit: normalized::ExtentUnit) -> i32 {
+    match block {
+        normalized::InlineBlock::Lacuna(x) if x.unit == unit => x.n,
+        normalized::InlineBlock::Space(x) if x.unit == unit => x.quantity,
+        _ => 0,
+    }
+}
+
+/// Remove the local `@facs` that do not point into the facsimile.
+fn repair_facs(ms: &mut normalized::Manuscript, issues: &mut Vec<Issue>) {
+    let facsimile = &ms.facsimile;
+    let mut repair = |facs: &mut Option<String>, location: Location| {
+        if let Some(f) = facs
+            && f.starts_with('#')
+            && !facsimile.contains(f)
+        {
+            issues.push(Issue::UnknownFacs {
+                location,
+                facs: f.clone(),
+            });
+            *facs = None;
+        }
+    };
+    for page in &mut ms.text.pages {
+        repair(&mut page.facs, Location::page(&page.n));
+        for column in &mut page.columns {
+            repair(&mut column.facs, Location::column(&page.n, Some(column.n)));
+            for line in &mut column.lines {
+                repair(
+                    &mut line.facs,
+                    Location::line(&page.n, Some(column.n), Some(line.n)),
+                );
+            }
+        }
+    }
+}
+
+/// Declare the hands that are used in corrections but not declared.
+fn repair_hands(ms: &mut normalized::Manuscript, issues: &mut Vec<Issue>) {
+    let used_hands = ms
+        .text
+        .pages
+        .iter()
+        .flat_map(|p| &p.columns)
+        .flat_map(|c| &c.lines)
+        .flat_map(|l| &l.blocks)
+        .filter_map(|b| match b {
+            normalized::InlineBlock::Correction(c) => Some(&c.versions),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|v| v.hand.clone())
+        .collect::<Vec<_>>();
+    for hand in used_hands {
+        if !ms.meta.allows_hand(&hand) {
+            issues.push(Issue::UndeclaredHand(hand.clone()));
+            ms.meta.hands.push(normalized::Hand {
+                id: hand,
+                description: None,
+                scribe: None,
+                script: None,
+                not_before: None,
+                not_after: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn correct_files_are_unchanged() {
+        for file in [
+            "examples/02_lines_consistent.xml",
+            "examples/07_multi-page.xml",
+            "examples/09_missing_pages.xml",
+            "examples/10_hand_notes.xml",
+            "examples/14_facsimile.xml",
+        ] {
+            let xml = std::fs::read_to_string(file).unwrap();
+            let recovered = from_xml(xml.as_bytes()).unwrap();
+            assert_eq!(recovered.issues, vec![], "{file}");
+            assert_eq!(
+                recovered.manuscript,
+                crate::from_xml(xml.as_bytes()).unwrap(),
+                "{file}"
+            );
+        }
+    }
+
+    #[test]
+    fn broken_file() {
+        let xml = std::fs::read_to_string("examples/16_broken.xml").unwrap();
+        assert!(crate::from_xml(xml.as_bytes()).is_err());
+        let recovered = from_xml(xml.as_bytes()).unwrap();
+
+        let page = |n: &str, column: i32, line: Option<i32>| Location {
+            page: Some(n.to_string()),
+            column: Some(column),
+            line,
+        };
+        let issues = recovered.issues;
+        assert!(matches!(
+            &issues[0],
+            Issue::Dropped { location, xml: Some(xml), .. }
+                if *location == page("1v", 1, Some(2)) && xml.contains("many")
+        ));
+        assert_eq!(
+            issues[1..4],
+            [
+                Issue::InvalidDate("yesterday".to_string()),
+                Issue::UnknownTerm {
+                    location: page("1r", 1, Some(1)),
+                    term: "mould".to_string()
+                },
+                Issue::Renumbered {
+                    location: page("1r", 1, Some(1)),
+                    to: 2
+                },
+            ]
+        );
+        assert_eq!(
+            issues[4],
+            Issue::Renumbered {
+                location: page("1v", 1, Some(3)),
+                to: 2
+            }
+        );
+        assert!(matches!(
+            &issues[5],
+            Issue::Dropped { location, xml: Some(xml), .. }
+                if *location == Location::page("1a") && xml.contains("out of order")
+        ));
+        assert_eq!(
+            issues[6..],
+            [
+                Issue::IncorrectDivType {
+                    location: Location::column("2r", Some(1)),
+                    found: "colum".to_string()
+                },
+                Issue::UnknownFacs {
+                    location: Location::page("1r"),
+                    facs: "#nowhere".to_string()
+                },
+                Issue::UndeclaredHand("hand9".to_string()),
+            ]
+        );
+
+        let ms = recovered.manuscript;
+        assert!(ms.meta.allows_hand("hand9"));
+        assert_eq!(ms.meta.changes[0].when, None);
+        let pages = ms
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                streamed::Block::Break(streamed::BreakType::Page(n, _)) => Some(n.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(pages, ["1r", "1v", "2r"]);
+        // once saved without the vocabulary, only the kept term remains to be fixed
+        let lenient = Options {
+            vocabulary: None,
+            ..Default::default()
+        };
+        let saved = crate::to_xml_with_options(ms, &lenient).unwrap();
+        assert_eq!(
+            from_xml(saved.as_bytes()).unwrap().issues,
+            [Issue::UnknownTerm {
+                location: page("1r", 1, Some(1)),
+                term: "mould".to_string()
+            }]
+        );
+        assert!(
+            from_xml_with_options(saved.as_bytes(), &lenient)
+                .unwrap()
+                .issues
+                .is_empty()
+        );
+    }
+
+    /// A damage agent is not a gap reason, even if the words are the same.
+    #[test]
+    fn damage_agent_as_gap_reason() {
+        let xml = std::fs::read_to_string("examples/02_lines_consistent.xml")
+            .unwrap()
+            .replace(r#"reason="lost""#, r#"reason="smeared-diacritica""#);
+        let recovered = from_xml(xml.as_bytes()).unwrap();
+        assert_eq!(
+            recovered.issues,
+            [Issue::UnknownTerm {
+                location: Location::line("page1", Some(1), Some(1)),
+                term: "smeared-diacritica".to_string()
+            }]
+        );
+        let lenient = Options {
+            vocabulary: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            recovered.manuscript,
+            crate::from_xml_with_options(xml.as_bytes(), &lenient).unwrap()
+        );
+    }
+
+    #[test]
+    fn conflicting_dates() {
+        let xml = std::fs::read_to_string("examples/12_history.xml")
+            .unwrap()
+            .replace(r#"notAfter="0950""#, r#"notAfter="0850""#);
+        assert!(crate::from_xml(xml.as_bytes()).is_err());
+        let recovered = from_xml(xml.as_bytes()).unwrap();
+        assert_eq!(
+            recovered.issues,
+            [
+                Issue::InvalidDate("0900".to_string()),
+                Issue::InvalidDate("0850".to_string())
+            ]
+        );
+        assert_eq!(recovered.manuscript.meta.orig_date, None);
+
+        let xml = xml.replace(r#"notAfter="0850""#, r#"when="0920""#);
+        let recovered = from_xml(xml.as_bytes()).unwrap();
+        assert_eq!(recovered.issues, [Issue::InvalidDate("0900".to_string())]);
+        assert_eq!(
+            recovered.manuscript.meta.orig_date,
+            Some(normalized::DateRange::exact(normalized::Date::year(920)))
+        );
+    }
+
+    #[test]
+    fn malformed_xml() {
+        let xml = std::fs::read_to_string("examples/02_lines_consistent.xml").unwrap();
+        let broken = xml.replace("</body>", "");
+        assert!(matches!(
+            from_xml(broken.as_bytes()),
+            Err(ConversionError::DeSer(_))
+        ));
+    }
+}
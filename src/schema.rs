@@ -27,6 +27,9 @@ pub struct Tei {
     /// The header with any meta-information
     #[serde(rename = "teiHeader")]
     pub tei_header: TeiHeader,
+    /// The images of this manuscript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facsimile: Option<Facsimile>,
     /// the actual text
     pub text: Text,
 }
@@ -38,18 +41,248 @@ impl Tei {
             xmlns: self.xmlns,
             tei_header: TeiHeader {
                 file_desc: self.tei_header.file_desc.trim(),
+                revision_desc: self.tei_header.revision_desc.map(RevisionDesc::trim),
             },
+            facsimile: self.facsimile,
             text: self.text.trim(),
         }
     }
 }
 
+/// The images of a manuscript.
+///
+/// Pages, columns and lines point to the [`Surface`]s and [`Zone`]s in here with `@facs`.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct Facsimile {
+    /// One surface per image
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub surface: Vec<Surface>,
+}
+impl Facsimile {
+    /// Get the surface with this id.
+    ///
+    /// `facs` may be given as a pointer (`#s1`) or as the plain id (`s1`).
+    #[must_use]
+    pub fn surface(&self, facs: &str) -> Option<&Surface> {
+        let id = facs.strip_prefix('#').unwrap_or(facs);
+        self.surface.iter().find(|s| s.xml_id == id)
+    }
+
+    /// Get the zone with this id, together with the surface it is on.
+    ///
+    /// `facs` may be given as a pointer (`#z1`) or as the plain id (`z1`).
+    #[must_use]
+    pub fn zone(&self, facs: &str) -> Option<(&Surface, &Zone)> {
+        let id = facs.strip_prefix('#').unwrap_or(facs);
+        self.surface
+            .iter()
+            .find_map(|s| s.zone.iter().find(|z| z.xml_id == id).map(|z| (s, z)))
+    }
+
+    /// Whether `facs` points to a surface or zone in here.
+    #[must_use]
+    pub fn contains(&self, facs: &str) -> bool {
+        self.surface(facs).is_some() || self.zone(facs).is_some()
+    }
+}
+
+/// A single image, usually showing one page.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Surface {
+    /// The id pages point to in `@facs`
+    #[serde(rename = "@xml:id")]
+    pub xml_id: String,
+    /// The image file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphic: Option<Graphic>,
+    /// The regions on this image
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub zone: Vec<Zone>,
+}
+
+/// An image file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Graphic {
+    /// Where the image can be found
+    #[serde(rename = "@url")]
+    pub url: String,
+    /// Width of the image in pixels
+    #[serde(rename = "@width", skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    /// Height of the image in pixels
+    #[serde(rename = "@height", skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+}
+
+/// A region on an image, given as a bounding box or a polygon in pixel coordinates.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Zone {
+    /// The id columns and lines point to in `@facs`
+    #[serde(rename = "@xml:id")]
+    pub xml_id: String,
+    /// x coordinate of the upper left corner
+    #[serde(rename = "@ulx", skip_serializing_if = "Option::is_none")]
+    pub ulx: Option<i32>,
+    /// y coordinate of the upper left corner
+    #[serde(rename = "@uly", skip_serializing_if = "Option::is_none")]
+    pub uly: Option<i32>,
+    /// x coordinate of the lower right corner
+    #[serde(rename = "@lrx", skip_serializing_if = "Option::is_none")]
+    pub lrx: Option<i32>,
+    /// y coordinate of the lower right corner
+    #[serde(rename = "@lry", skip_serializing_if = "Option::is_none")]
+    pub lry: Option<i32>,
+    /// The outline of this zone
+    #[serde(rename = "@points", skip_serializing_if = "Option::is_none")]
+    pub points: Option<Points>,
+}
+impl Zone {
+    /// The smallest rectangle containing this zone, as `(ulx, uly, lrx, lry)`.
+    ///
+    /// This is taken from the corners if all four are given, and computed from the polygon
+    /// otherwise.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<(i32, i32, i32, i32)> {
+        if let (Some(ulx), Some(uly), Some(lrx), Some(lry)) =
+            (self.ulx, self.uly, self.lrx, self.lry)
+        {
+            return Some((ulx, uly, lrx, lry));
+        }
+        let points = &self.points.as_ref()?.0;
+        Some((
+            points.iter().map(|p| p.x).min()?,
+            points.iter().map(|p| p.y).min()?,
+            points.iter().map(|p| p.x).max()?,
+            points.iter().map(|p| p.y).max()?,
+        ))
+    }
+}
+
+/// A point on an image in pixel coordinates.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// A polygon as used in `@points`: `x,y` pairs separated by whitespace.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct Points(pub Vec<Point>);
+impl core::str::FromStr for Points {
+    type Err = InvalidPoints;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace()
+            .map(|pair| {
+                let (x, y) = pair.split_once(',')?;
+                Some(Point {
+                    x: x.parse().ok()?,
+                    y: y.parse().ok()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+            .ok_or_else(|| InvalidPoints(s.to_string()))
+    }
+}
+impl TryFrom<String> for Points {
+    type Error = InvalidPoints;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+/// The canonical form used when serializing.
+impl core::fmt::Display for Points {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for (idx, p) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{},{}", p.x, p.y)?;
+        }
+        Ok(())
+    }
+}
+impl From<Points> for String {
+    fn from(value: Points) -> Self {
+        value.to_string()
+    }
+}
+
+/// A string that is not a valid [`Points`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InvalidPoints(pub String);
+impl core::fmt::Display for InvalidPoints {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a valid list of points. Must be x,y pairs separated by whitespace.",
+            self.0
+        )
+    }
+}
+impl core::error::Error for InvalidPoints {}
+
 /// TEI Header with metainformation about a folio.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct TeiHeader {
     /// TEI fileDesc element - describes this file
     #[serde(rename = "fileDesc")]
     pub file_desc: FileDesc,
+    /// The changes made to this file
+    #[serde(
+        rename = "revisionDesc",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub revision_desc: Option<RevisionDesc>,
+}
+
+/// The changes made to this file, oldest first.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct RevisionDesc {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub change: Vec<Change>,
+}
+impl RevisionDesc {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            change: self.change.into_iter().map(Change::trim).collect(),
+        }
+    }
+}
+
+/// A single change made to this file.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Change {
+    /// The person responsible for this change
+    #[serde(rename = "@who", skip_serializing_if = "Option::is_none")]
+    pub who: Option<String>,
+    /// The ISO date of this change
+    #[serde(rename = "@when", skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    /// The state of the changed text after this change, e.g. `transcribed`
+    #[serde(rename = "@status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<PageStatus>,
+    /// The name of the changed page
+    #[serde(rename = "@target", skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// A human readable description of this change
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+impl Change {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            who: self.who,
+            when: self.when,
+            status: self.status,
+            target: self.target,
+            description: self.description.map(trim_if_required),
+        }
+    }
 }
 
 /// TEI fileDesc element - descripbes this file.
@@ -76,10 +309,7 @@ impl FileDesc {
                 p: trim_if_required(self.publication_stmt.p),
             },
             source_desc: SourceDesc {
-                ms_desc: MsDesc {
-                    ms_identifier: self.source_desc.ms_desc.ms_identifier.trim(),
-                    phys_desc: self.source_desc.ms_desc.phys_desc.trim(),
-                },
+                ms_desc: self.source_desc.ms_desc.trim(),
             },
         }
     }
@@ -117,16 +347,161 @@ pub struct MsDesc {
     /// Information that can identify this manuscript
     #[serde(rename = "msIdentifier")]
     pub ms_identifier: MsIdentifier,
+    /// The biblical text contained in this manuscript
+    #[serde(
+        rename = "msContents",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ms_contents: Option<MsContents>,
     /// Description of the physical properties of this manuscript
     #[serde(rename = "physDesc")]
     pub phys_desc: PhysDesc,
+    /// Where and when this manuscript was written and where it has been since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<History>,
 }
 impl MsDesc {
     #[must_use]
     pub fn trim(self) -> Self {
         Self {
             ms_identifier: self.ms_identifier.trim(),
+            ms_contents: self.ms_contents.map(MsContents::trim),
             phys_desc: self.phys_desc.trim(),
+            history: self.history.map(History::trim),
+        }
+    }
+}
+
+/// The text contained in this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MsContents {
+    /// The individual contiguous passages
+    #[serde(rename = "msItem", default, skip_serializing_if = "Vec::is_empty")]
+    pub ms_item: Vec<MsItem>,
+}
+impl MsContents {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            ms_item: self.ms_item.into_iter().map(MsItem::trim).collect(),
+        }
+    }
+}
+
+/// A contiguous passage contained in this manuscript.
+///
+/// e.g. `<msItem><locus from="01_r" to="03_v"/><title type="Masoretic">Ps 26:10–31:4</title></msItem>`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MsItem {
+    /// The pages this passage is found on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locus: Option<Locus>,
+    /// The passage itself
+    pub title: MsItemTitle,
+}
+impl MsItem {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            locus: self.locus,
+            title: MsItemTitle {
+                title_type: self.title.title_type,
+                name: trim_if_required(self.title.name),
+            },
+        }
+    }
+}
+
+/// A range of pages.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Locus {
+    /// The first page
+    #[serde(rename = "@from")]
+    pub from: String,
+    /// The last page
+    #[serde(rename = "@to")]
+    pub to: String,
+}
+
+/// The name of a passage.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MsItemTitle {
+    /// The versification scheme (long form) used in [`name`](Self::name)
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    pub title_type: Option<String>,
+    /// The passage, e.g. `Ps 26:10–31:4`
+    #[serde(rename = "$text")]
+    pub name: String,
+}
+
+/// The history of this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct History {
+    /// Where and when this manuscript was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Origin>,
+    /// The places this manuscript has been at since it was written, one entry per place
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provenance: Vec<String>,
+}
+impl History {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            origin: self.origin.map(Origin::trim),
+            provenance: self.provenance.into_iter().map(trim_if_required).collect(),
+        }
+    }
+}
+
+/// Where and when this manuscript was written.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Origin {
+    /// When this manuscript was written
+    #[serde(rename = "origDate", default, skip_serializing_if = "Option::is_none")]
+    pub orig_date: Option<OrigDate>,
+    /// Where this manuscript was written
+    #[serde(rename = "origPlace", default, skip_serializing_if = "Option::is_none")]
+    pub orig_place: Option<String>,
+}
+impl Origin {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            orig_date: self.orig_date.map(OrigDate::trim),
+            orig_place: self.orig_place.map(trim_if_required),
+        }
+    }
+}
+
+/// The date at which this manuscript was written.
+///
+/// The attributes contain ISO dates (`YYYY`, `YYYY-MM` or `YYYY-MM-DD`), the text a human readable
+/// form of the same date.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct OrigDate {
+    /// The exact date
+    #[serde(rename = "@when", skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    /// The earliest possible date
+    #[serde(rename = "@notBefore", skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    /// The latest possible date
+    #[serde(rename = "@notAfter", skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<String>,
+    /// A human readable form of the date
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+impl OrigDate {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            when: self.when,
+            not_before: self.not_before,
+            not_after: self.not_after,
+            description: self.description.map(trim_if_required),
         }
     }
 }
@@ -134,10 +509,24 @@ impl MsDesc {
 /// Information that can identify this manuscript.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct MsIdentifier {
+    /// The country in which this manuscript is held
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// The city or town in which this manuscript is held
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settlement: Option<String>,
     /// The institution holding this manuscript
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub institution: Option<String>,
+    /// The repository (library, archive, ...) inside the institution holding this manuscript
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
     /// The collection this manuscript is a part of
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub collection: Option<String>,
+    /// The shelfmark of this manuscript in its repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idno: Option<IdNo>,
     /// Alternative identifiers (other then the main MS name, which is in the `<title>`
     #[serde(
         rename = "altIdentifier",
@@ -150,8 +539,12 @@ impl MsIdentifier {
     #[must_use]
     pub fn trim(self) -> Self {
         Self {
+            country: self.country.map(trim_if_required),
+            settlement: self.settlement.map(trim_if_required),
             institution: self.institution.map(trim_if_required),
+            repository: self.repository.map(trim_if_required),
             collection: self.collection.map(trim_if_required),
+            idno: self.idno.map(IdNo::trim),
             alt_identifier: self
                 .alt_identifier
                 .into_iter()
@@ -177,6 +570,9 @@ impl AltIdentifier {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct IdNo {
+    /// The catalogue or numbering system this identifier belongs to
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    pub id_type: Option<IdNoType>,
     /// The actual identifier
     #[serde(rename = "$text")]
     pub name: String,
@@ -185,14 +581,83 @@ impl IdNo {
     #[must_use]
     pub fn trim(self) -> Self {
         Self {
+            id_type: self.id_type,
             name: trim_if_required(self.name),
         }
     }
 }
 
+/// The catalogue or numbering system an [`IdNo`] belongs to.
+///
+/// The catalogues commonly used for biblical manuscripts have their own variants, all others are
+/// kept as given.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum IdNoType {
+    /// Gregory-Aland numbers for NT manuscripts, e.g. `01` or `P46`
+    GregoryAland,
+    /// Rahlfs numbers for LXX manuscripts, e.g. `B` or `967`
+    Rahlfs,
+    /// Kennicott numbers for Hebrew manuscripts
+    Kennicott,
+    /// de Rossi numbers for Hebrew manuscripts
+    DeRossi,
+    /// Any other catalogue
+    Other(String),
+}
+impl IdNoType {
+    /// The value used in `@type`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::GregoryAland => "Gregory-Aland",
+            Self::Rahlfs => "Rahlfs",
+            Self::Kennicott => "Kennicott",
+            Self::DeRossi => "de-Rossi",
+            Self::Other(x) => x,
+        }
+    }
+}
+impl From<&str> for IdNoType {
+    fn from(value: &str) -> Self {
+        match value {
+            "Gregory-Aland" => Self::GregoryAland,
+            "Rahlfs" => Self::Rahlfs,
+            "Kennicott" => Self::Kennicott,
+            "de-Rossi" => Self::DeRossi,
+            x => Self::Other(x.to_string()),
+        }
+    }
+}
+impl From<String> for IdNoType {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+impl From<IdNoType> for String {
+    fn from(value: IdNoType) -> Self {
+        match value {
+            IdNoType::Other(x) => x,
+            x => x.as_str().to_string(),
+        }
+    }
+}
+impl core::fmt::Display for IdNoType {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Description of the physical properties of this manuscript.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct PhysDesc {
+    /// Description of the physical object: its material, size and layout
+    #[serde(
+        rename = "objectDesc",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub object_desc: Option<ObjectDesc>,
     /// Description of the scribal hands present in this manuscript
     #[serde(rename = "handDesc", skip_serializing_if = "Option::is_none")]
     pub hand_desc: Option<HandDesc>,
@@ -204,9 +669,8 @@ impl PhysDesc {
     #[must_use]
     pub fn trim(self) -> Self {
         Self {
-            hand_desc: self.hand_desc.map(|d| HandDesc {
-                summary: trim_if_required(d.summary),
-            }),
+            object_desc: self.object_desc.map(ObjectDesc::trim),
+            hand_desc: self.hand_desc.map(HandDesc::trim),
             script_desc: self.script_desc.map(|d| ScriptDesc {
                 summary: trim_if_required(d.summary),
             }),
@@ -218,10 +682,58 @@ impl PhysDesc {
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct HandDesc {
     /// A human readable summary of the hands present in this manuscript
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// The individual hands present in this manuscript
     ///
-    /// Different hands should be listed and given names by which they can be referred to in
-    /// corrections in the [`Text`]
-    pub summary: String,
+    /// Their `@xml:id` is used to refer to them in corrections in the [`Text`]
+    #[serde(rename = "handNote", default, skip_serializing_if = "Vec::is_empty")]
+    pub hand_notes: Vec<HandNote>,
+}
+impl HandDesc {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            summary: self.summary.map(trim_if_required),
+            hand_notes: self.hand_notes.into_iter().map(HandNote::trim).collect(),
+        }
+    }
+}
+
+/// A single scribal hand.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct HandNote {
+    /// The name by which this hand is referred to in [`Rdg::hand`]
+    #[serde(rename = "@xml:id")]
+    pub xml_id: String,
+    /// The scribe this hand belongs to
+    #[serde(rename = "@scribe", skip_serializing_if = "Option::is_none")]
+    pub scribe: Option<String>,
+    /// The script this hand is written in
+    #[serde(rename = "@script", skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// The earliest possible date of this hand
+    #[serde(rename = "@notBefore", skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+    /// The latest possible date of this hand
+    #[serde(rename = "@notAfter", skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<String>,
+    /// A human readable description of this hand
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+impl HandNote {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            xml_id: self.xml_id,
+            scribe: self.scribe,
+            script: self.script,
+            not_before: self.not_before,
+            not_after: self.not_after,
+            description: self.description.map(trim_if_required),
+        }
+    }
 }
 
 /// Description of the scripts present in this manuscript.
@@ -234,6 +746,273 @@ pub struct ScriptDesc {
     pub summary: String,
 }
 
+/// Description of the physical object.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ObjectDesc {
+    /// The material and extent of this manuscript
+    #[serde(
+        rename = "supportDesc",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub support_desc: Option<SupportDesc>,
+    /// The layout of the pages in this manuscript
+    #[serde(
+        rename = "layoutDesc",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub layout_desc: Option<LayoutDesc>,
+}
+impl ObjectDesc {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            support_desc: self.support_desc.map(SupportDesc::trim),
+            layout_desc: self.layout_desc.map(LayoutDesc::trim),
+        }
+    }
+}
+
+/// The material and extent of this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SupportDesc {
+    /// The material this manuscript is written on
+    #[serde(rename = "@material", skip_serializing_if = "Option::is_none")]
+    pub material: Option<Material>,
+    /// A human readable description of the material
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub support: Option<String>,
+    /// The number of leaves and the size of this manuscript
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extent: Option<Extent>,
+}
+impl SupportDesc {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            material: self.material,
+            support: self.support.map(trim_if_required),
+            extent: self.extent.map(Extent::trim),
+        }
+    }
+}
+
+/// The material a manuscript is written on.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum Material {
+    Parchment,
+    Papyrus,
+    Paper,
+    /// Any other material, e.g. `mixed`
+    Other(String),
+}
+impl Material {
+    /// The value used in `@material`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Parchment => "parchment",
+            Self::Papyrus => "papyrus",
+            Self::Paper => "paper",
+            Self::Other(x) => x,
+        }
+    }
+}
+impl From<String> for Material {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "parchment" => Self::Parchment,
+            "papyrus" => Self::Papyrus,
+            "paper" => Self::Paper,
+            _ => Self::Other(value),
+        }
+    }
+}
+impl From<Material> for String {
+    fn from(value: Material) -> Self {
+        match value {
+            Material::Other(x) => x,
+            x => x.as_str().to_string(),
+        }
+    }
+}
+
+/// The extent of this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Extent {
+    /// Countable parts of this manuscript, e.g. `<measure unit="leaf" quantity="491"/>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub measure: Vec<Measure>,
+    /// The size of leaves or of the written area
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dimensions: Vec<Dimensions>,
+}
+impl Extent {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            measure: self.measure.into_iter().map(Measure::trim).collect(),
+            dimensions: self.dimensions,
+        }
+    }
+
+    /// The number of leaves, if given.
+    #[must_use]
+    pub fn leaves(&self) -> Option<u32> {
+        self.measure
+            .iter()
+            .find(|m| m.unit == "leaf")
+            .map(|m| m.quantity)
+    }
+}
+
+/// A countable part of a manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Measure {
+    /// What is counted, e.g. `leaf` or `quire`
+    #[serde(rename = "@unit")]
+    pub unit: String,
+    /// How many there are
+    #[serde(rename = "@quantity")]
+    pub quantity: u32,
+    /// A human readable form of this measure
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+impl Measure {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            unit: self.unit,
+            quantity: self.quantity,
+            description: self.description.map(trim_if_required),
+        }
+    }
+}
+
+/// The size of a part of a manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Dimensions {
+    /// What is measured, e.g. `leaf` or `written`
+    #[serde(rename = "@type", skip_serializing_if = "Option::is_none")]
+    pub dim_type: Option<String>,
+    /// The unit of all measurements, e.g. `mm`
+    #[serde(rename = "@unit", skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+}
+
+/// The layout of the pages in this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct LayoutDesc {
+    /// The layouts in use, one for each distinct layout
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layout: Vec<Layout>,
+}
+impl LayoutDesc {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            layout: self.layout.into_iter().map(Layout::trim).collect(),
+        }
+    }
+}
+
+/// A single layout used in this manuscript.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Layout {
+    /// The number of columns per page
+    #[serde(rename = "@columns", skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Count>,
+    /// The number of written lines per column
+    #[serde(rename = "@writtenLines", skip_serializing_if = "Option::is_none")]
+    pub written_lines: Option<Count>,
+    /// A human readable description of this layout
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+impl Layout {
+    #[must_use]
+    pub fn trim(self) -> Self {
+        Self {
+            columns: self.columns,
+            written_lines: self.written_lines,
+            description: self.description.map(trim_if_required),
+        }
+    }
+}
+
+/// A number or range of numbers as used in `@columns` and `@writtenLines`.
+///
+/// TEI allows either a single number (`"3"`) or a minimum and maximum (`"27 29"`).
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub struct Count {
+    pub min: u32,
+    pub max: u32,
+}
+impl Count {
+    /// Whether `n` is within this count.
+    #[must_use]
+    pub fn contains(&self, n: usize) -> bool {
+        usize::try_from(self.min).is_ok_and(|min| min <= n)
+            && usize::try_from(self.max).is_ok_and(|max| n <= max)
+    }
+}
+impl core::str::FromStr for Count {
+    type Err = InvalidCount;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || InvalidCount(s.to_string());
+        let mut parts = s.split_whitespace().map(str::parse::<u32>);
+        let min = parts.next().ok_or_else(err)?.map_err(|_| err())?;
+        let max = parts.next().transpose().map_err(|_| err())?.unwrap_or(min);
+        if parts.next().is_some() || max < min {
+            return Err(err());
+        }
+        Ok(Self { min, max })
+    }
+}
+impl TryFrom<String> for Count {
+    type Error = InvalidCount;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+/// The canonical form used when serializing.
+impl core::fmt::Display for Count {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{} {}", self.min, self.max)
+        }
+    }
+}
+impl From<Count> for String {
+    fn from(value: Count) -> Self {
+        value.to_string()
+    }
+}
+
+/// A string that is not a valid [`Count`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InvalidCount(pub String);
+impl core::fmt::Display for InvalidCount {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a valid count. Must be a number or a minimum and maximum separated by whitespace.",
+            self.0
+        )
+    }
+}
+impl core::error::Error for InvalidCount {}
+
 /// The entire transcribed text.
 ///
 /// This struct is just a trivial wrapper around [`<body>`](Body), because the TEI spec requires that.
@@ -286,8 +1065,16 @@ pub struct Page {
     /// the page name
     #[serde(rename = "@n")]
     pub n: String,
+    /// How far the transcription of this page has progressed
+    #[serde(rename = "@status", skip_serializing_if = "Option::is_none")]
+    pub status: Option<PageStatus>,
+    /// Pointer to the [`Surface`] showing this page
+    #[serde(rename = "@facs", skip_serializing_if = "Option::is_none")]
+    pub facs: Option<String>,
     /// The columns present in this page
-    #[serde(rename = "div")]
+    ///
+    /// This is empty for pages that are missing from the manuscript.
+    #[serde(rename = "div", default)]
     pub columns: Vec<Column>,
 }
 impl Page {
@@ -298,11 +1085,65 @@ impl Page {
             lang: self.lang,
             div_type: self.div_type,
             n: self.n,
+            status: self.status,
+            facs: self.facs,
             columns: self.columns.into_iter().map(Column::trim).collect(),
         }
     }
 }
 
+/// The stage of the transcription workflow a page is in.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum PageStatus {
+    /// The transcription has been started
+    Draft,
+    /// The page has been transcribed entirely
+    Transcribed,
+    /// The transcription has been checked by a second person
+    Reviewed,
+    /// The transcription is final
+    Approved,
+}
+impl core::str::FromStr for PageStatus {
+    type Err = UnknownPageStatus;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(Self::Draft),
+            "transcribed" => Ok(Self::Transcribed),
+            "reviewed" => Ok(Self::Reviewed),
+            "approved" => Ok(Self::Approved),
+            _ => Err(UnknownPageStatus(s.to_string())),
+        }
+    }
+}
+
+/// A `@status` that is not the name of a [`PageStatus`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnknownPageStatus(pub String);
+impl core::fmt::Display for UnknownPageStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a page status. Use draft, transcribed, reviewed or approved.",
+            self.0
+        )
+    }
+}
+impl core::error::Error for UnknownPageStatus {}
+impl PageStatus {
+    /// The value used in `@status`.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Transcribed => "transcribed",
+            Self::Reviewed => "reviewed",
+            Self::Approved => "approved",
+        }
+    }
+}
+
 /// A complete column in the manuscript.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct Column {
@@ -316,6 +1157,9 @@ pub struct Column {
     /// the column number
     #[serde(rename = "@n", skip_serializing_if = "Option::is_none")]
     pub n: Option<i32>,
+    /// Pointer to the [`Zone`] showing this column
+    #[serde(rename = "@facs", skip_serializing_if = "Option::is_none")]
+    pub facs: Option<String>,
     /// The lines in this column
     #[serde(rename = "div")]
     pub lines: Vec<Line>,
@@ -328,6 +1172,7 @@ impl Column {
             lang: self.lang,
             div_type: self.div_type,
             n: self.n,
+            facs: self.facs,
             lines: self.lines.into_iter().map(Line::trim).collect(),
         }
     }
@@ -346,6 +1191,9 @@ pub struct Line {
     /// the line number
     #[serde(rename = "@n", skip_serializing_if = "Option::is_none")]
     pub n: Option<i32>,
+    /// Pointer to the [`Zone`] showing this line
+    #[serde(rename = "@facs", skip_serializing_if = "Option::is_none")]
+    pub facs: Option<String>,
     /// The actual text elements contained in this line
     #[serde(rename = "$value")]
     pub blocks: Vec<InlineBlock>,
@@ -358,6 +1206,7 @@ impl Line {
             lang: self.lang,
             div_type: self.div_type,
             n: self.n,
+            facs: self.facs,
             blocks: self.blocks.into_iter().map(InlineBlock::trim).collect(),
         }
     }
@@ -481,7 +1330,7 @@ pub struct Damage {
     pub lang: Option<String>,
     /// The certainty the transcriber assigns to the reconstruction of the damaged text
     #[serde(rename = "@cert", skip_serializing_if = "Option::is_none")]
-    pub cert: Option<String>,
+    pub cert: Option<Certainty>,
     /// The cause of damage
     #[serde(rename = "@agent")]
     pub agent: String,
@@ -630,7 +1479,7 @@ pub struct Gap {
     ///
     /// If not content is proposed, the certainty for the approximate extent
     #[serde(rename = "@cert", skip_serializing_if = "Option::is_none")]
-    pub cert: Option<String>,
+    pub cert: Option<Certainty>,
 }
 impl Default for Gap {
     fn default() -> Self {
@@ -703,6 +1552,97 @@ impl ExtentUnit {
     }
 }
 
+/// The certainty a transcriber assigns to a reading or reconstruction.
+///
+/// This is the TEI `teidata.probCert` datatype: either one of the levels `high`, `medium`, `low`
+/// and `unknown`, or a probability between 0 and 1.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub enum Certainty {
+    /// `high`
+    High,
+    /// `medium`
+    Medium,
+    /// `low`
+    Low,
+    /// `unknown`
+    Unknown,
+    /// A numeric probability, e.g. `0.75`
+    Probability(Probability),
+}
+impl core::str::FromStr for Certainty {
+    type Err = InvalidCertainty;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "high" => Ok(Self::High),
+            "medium" => Ok(Self::Medium),
+            "low" => Ok(Self::Low),
+            "unknown" => Ok(Self::Unknown),
+            x => x
+                .parse::<f64>()
+                .ok()
+                .and_then(Probability::new)
+                .map(Self::Probability)
+                .ok_or_else(|| InvalidCertainty(s.to_string())),
+        }
+    }
+}
+impl TryFrom<String> for Certainty {
+    type Error = InvalidCertainty;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+/// The canonical form used when serializing.
+impl core::fmt::Display for Certainty {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::High => write!(f, "high"),
+            Self::Medium => write!(f, "medium"),
+            Self::Low => write!(f, "low"),
+            Self::Unknown => write!(f, "unknown"),
+            Self::Probability(p) => write!(f, "{}", p.value()),
+        }
+    }
+}
+impl From<Certainty> for String {
+    fn from(value: Certainty) -> Self {
+        value.to_string()
+    }
+}
+
+/// A probability, guaranteed to be a finite number in `[0, 1]`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Probability(f64);
+/// [`Probability`] can never be NaN.
+impl Eq for Probability {}
+impl Probability {
+    /// Create a new probability, returning None if `value` is not in `[0, 1]`.
+    #[must_use]
+    pub fn new(value: f64) -> Option<Self> {
+        (0.0..=1.0).contains(&value).then_some(Self(value))
+    }
+
+    #[must_use]
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
+/// A string that is not a valid [`Certainty`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct InvalidCertainty(pub String);
+impl core::fmt::Display for InvalidCertainty {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a valid certainty. Must be one of high, medium, low, unknown or a number between 0 and 1.",
+            self.0
+        )
+    }
+}
+impl core::error::Error for InvalidCertainty {}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -802,11 +1742,53 @@ mod test {
                 reason: "lost".to_string(),
                 n: 2,
                 unit: ExtentUnit::Column,
-                cert: Some("high".to_string()),
+                cert: Some(Certainty::High),
             }
         );
     }
 
+    /// cert is parsed into the TEI levels or a probability
+    #[test]
+    fn certainty_parse() {
+        assert_eq!("high".parse(), Ok(Certainty::High));
+        assert_eq!("medium".parse(), Ok(Certainty::Medium));
+        assert_eq!("low".parse(), Ok(Certainty::Low));
+        assert_eq!("unknown".parse(), Ok(Certainty::Unknown));
+        assert_eq!(
+            "0.10".parse(),
+            Ok(Certainty::Probability(Probability::new(0.1).unwrap()))
+        );
+        assert_eq!(
+            "1".parse(),
+            Ok(Certainty::Probability(Probability::new(1.0).unwrap()))
+        );
+        assert!("1.5".parse::<Certainty>().is_err());
+        assert!("-0.1".parse::<Certainty>().is_err());
+        assert!("NaN".parse::<Certainty>().is_err());
+        assert!("High".parse::<Certainty>().is_err());
+        assert!("".parse::<Certainty>().is_err());
+    }
+
+    /// numeric certainties are written out in canonical form
+    #[test]
+    fn certainty_canonical() {
+        let xml = r#"<gap reason="lost" unit="line" n="2" cert="0.10"/>"#;
+        let deser: Gap = quick_xml::de::from_str(xml).unwrap();
+        let sr = quick_xml::se::to_string_with_root("gap", &deser).unwrap();
+        assert_eq!(sr, r#"<gap reason="lost" unit="line" n="2" cert="0.1"/>"#);
+    }
+
+    /// invalid certainties are rejected while deserializing
+    #[test]
+    fn certainty_invalid() {
+        let xml = r#"<damage cert="very high" agent="water">damaged</damage>"#;
+        let result: Result<Damage, _> = quick_xml::de::from_str(xml);
+        assert!(result.is_err());
+        let xml = r#"<gap reason="lost" unit="line" n="2" cert="10"/>"#;
+        let result: Result<Gap, _> = quick_xml::de::from_str(xml);
+        assert!(result.is_err());
+    }
+
     /// cert is optional
     #[test]
     fn gap_no_cert() {
@@ -959,7 +1941,7 @@ mod test {
             result.unwrap(),
             Damage {
                 lang: None,
-                cert: Some("low".to_string()),
+                cert: Some(Certainty::Low),
                 agent: "water".to_string(),
                 content: "damaged".to_string()
             }
@@ -976,7 +1958,7 @@ mod test {
             result.unwrap(),
             Damage {
                 lang: Some("en".to_string()),
-                cert: Some("low".to_string()),
+                cert: Some(Certainty::Low),
                 agent: "water".to_string(),
                 content: "damaged".to_string()
             }
@@ -1020,7 +2002,7 @@ mod test {
             result.unwrap(),
             TextDamageOrChoice::Damage(Damage {
                 lang: None,
-                cert: Some("low".to_string()),
+                cert: Some(Certainty::Low),
                 agent: "water".to_string(),
                 content: "damaged".to_string()
             })
@@ -1086,7 +2068,7 @@ mod test {
                 lang: None,
                 value: TextDamageOrChoice::Damage(Damage {
                     lang: None,
-                    cert: Some("low".to_string()),
+                    cert: Some(Certainty::Low),
                     agent: "water".to_string(),
                     content: "damaged".to_string()
                 })
@@ -1214,6 +2196,7 @@ mod test {
         assert_eq!(
             result.unwrap(),
             Line {
+                facs: None,
                 lang: None,
                 div_type: "line".to_string(),
                 n: Some(3),
@@ -1236,6 +2219,7 @@ mod test {
         assert_eq!(
             result.unwrap(),
             Line {
+                facs: None,
                 lang: Some("grc".to_string()),
                 div_type: "line".to_string(),
                 n: Some(3),
@@ -1280,6 +2264,7 @@ mod test {
         assert_eq!(
             result.unwrap(),
             Line {
+                facs: None,
                 lang: None,
                 div_type: "line".to_string(),
                 n: None,
@@ -1298,7 +2283,7 @@ mod test {
                         lang: None,
                         value: TextDamageOrChoice::Damage(Damage {
                             lang: None,
-                            cert: Some("low".to_string()),
+                            cert: Some(Certainty::Low),
                             agent: "water".to_string(),
                             content: "damaged".to_string()
                         })
@@ -1326,11 +2311,13 @@ mod test {
         assert_eq!(
             result.unwrap(),
             Column {
+                facs: None,
                 lang: Some("hbo-Hebr-x-babli".to_string()),
                 n: Some(1),
                 div_type: "column".to_string(),
                 lines: vec![
                     Line {
+                        facs: None,
                         lang: Some("hbo-Hebr".to_string()),
                         div_type: "line".to_string(),
                         n: None,
@@ -1349,7 +2336,7 @@ mod test {
                                 lang: None,
                                 value: TextDamageOrChoice::Damage(Damage {
                                     lang: None,
-                                    cert: Some("low".to_string()),
+                                    cert: Some(Certainty::Low),
                                     agent: "water".to_string(),
                                     content: "damaged".to_string()
                                 })
@@ -1357,6 +2344,7 @@ mod test {
                         ]
                     },
                     Line {
+                        facs: None,
                         lang: None,
                         div_type: "line".to_string(),
                         n: Some(3),
@@ -1392,10 +2380,12 @@ mod test {
         assert_eq!(
             result.unwrap(),
             Column {
+                facs: None,
                 lang: Some("hbo-Hebr-x-babli".to_string()),
                 n: Some(1),
                 div_type: "column".to_string(),
                 lines: vec![Line {
+                    facs: None,
                     lang: None,
                     div_type: "line".to_string(),
                     n: Some(3),
@@ -1408,6 +2398,24 @@ mod test {
         );
     }
 
+    /// Pages without columns are missing pages and are allowed
+    #[test]
+    fn page_missing() {
+        let xml = r#"<div type="page" n="page1"/>"#;
+        let result: Result<Page, _> = quick_xml::de::from_str(xml);
+        assert_eq!(
+            result.unwrap(),
+            Page {
+                facs: None,
+                lang: None,
+                div_type: "page".to_string(),
+                n: "page1".to_string(),
+                status: None,
+                columns: vec![],
+            }
+        );
+    }
+
     /// Body with a single column
     #[test]
     fn body_1_by_1() {
@@ -1428,14 +2436,18 @@ mod test {
             Body {
                 lang: Some("grc".to_string()),
                 pages: vec![Page {
+                    facs: None,
                     lang: None,
                     div_type: "page".to_string(),
                     n: "page1".to_string(),
+                    status: None,
                     columns: vec![Column {
+                        facs: None,
                         lang: Some("hbo-Hebr-x-babli".to_string()),
                         n: Some(1),
                         div_type: "column".to_string(),
                         lines: vec![Line {
+                            facs: None,
                             lang: None,
                             div_type: "line".to_string(),
                             n: Some(3),
@@ -1477,15 +2489,19 @@ mod test {
             Body {
                 lang: None,
                 pages: vec![Page {
+                    facs: None,
                     lang: Some("grc".to_string()),
                     n: "page2".to_string(),
                     div_type: "page".to_string(),
+                    status: None,
                     columns: vec![
                         Column {
+                            facs: None,
                             lang: Some("hbo-Hebr-x-babli".to_string()),
                             n: Some(1),
                             div_type: "column".to_string(),
                             lines: vec![Line {
+                                facs: None,
                                 lang: None,
                                 div_type: "line".to_string(),
                                 n: Some(3),
@@ -1496,10 +2512,12 @@ mod test {
                             }]
                         },
                         Column {
+                            facs: None,
                             lang: Some("hbo-Hebr".to_string()),
                             n: Some(2),
                             div_type: "column".to_string(),
                             lines: vec![Line {
+                                facs: None,
                                 lang: None,
                                 div_type: "line".to_string(),
                                 n: Some(1),
@@ -1545,15 +2563,19 @@ mod test {
                 body: Body {
                     lang: Some("grc".to_string()),
                     pages: vec![Page {
+                        facs: None,
                         lang: None,
                         n: "page1".to_string(),
                         div_type: "page".to_string(),
+                        status: None,
                         columns: vec![
                             Column {
+                                facs: None,
                                 lang: Some("hbo-Hebr-x-babli".to_string()),
                                 n: Some(1),
                                 div_type: "column".to_string(),
                                 lines: vec![Line {
+                                    facs: None,
                                     lang: None,
                                     div_type: "line".to_string(),
                                     n: Some(3),
@@ -1564,10 +2586,12 @@ mod test {
                                 }]
                             },
                             Column {
+                                facs: None,
                                 lang: Some("hbo-Hebr".to_string()),
                                 n: Some(2),
                                 div_type: "column".to_string(),
                                 lines: vec![Line {
+                                    facs: None,
                                     lang: None,
                                     div_type: "line".to_string(),
                                     n: Some(1),
@@ -1607,6 +2631,10 @@ mod test {
                     ms_desc: MsDesc {
                         ms_identifier: MsIdentifier {
                             alt_identifier: vec![],
+                            country: None,
+                            settlement: None,
+                            repository: None,
+                            idno: None,
                             institution: Some(
                                 "University of does-not-exist".to_string(),
                             ),
@@ -1614,18 +2642,24 @@ mod test {
                                 "Collectors Edition 2 electric boogaloo".to_string(),
                             ),
                         },
+                        ms_contents: None,
                         phys_desc: PhysDesc {
+                            object_desc: None,
                             hand_desc: Some(HandDesc {
-                                summary: "There are two recognizable Hands: hand1 and hand2.".to_string(),
+                                summary: Some("There are two recognizable Hands: hand1 and hand2.".to_string()),
+                                hand_notes: vec![],
                             }),
                             script_desc: Some(ScriptDesc {
                                 summary: "Die Schrift in diesem Manuskript gibt es.".to_string(),
                             }),
                         },
+                        history: None,
                     },
                 },
             },
+            revision_desc: None,
         },
+        facsimile: None,
         text: Text {
             body: Body {
                 lang: Some(
@@ -1633,11 +2667,14 @@ mod test {
                 ),
                 pages: vec![
                     Page {
+                        facs: None,
                         lang: None,
                         div_type: "page".to_string(),
                         n: "34_v".to_string(),
+                        status: None,
                 columns: vec![
                     Column {
+                        facs: None,
                         lang: None,
                         div_type: "column".to_string(),
                         n: Some(
@@ -1645,6 +2682,7 @@ mod test {
                         ),
                         lines: vec![
                             Line {
+                                facs: None,
                                 lang: None,
                                 div_type: "line".to_string(),
                                 n: Some(
@@ -1682,6 +2720,7 @@ mod test {
                                 ],
                             },
                             Line {
+                                facs: None,
                                 lang: Some(
                                     "hbo-Hebr-x-babli".to_string(),
                                 ),
@@ -1701,11 +2740,13 @@ mod test {
                         ],
                     },
                     Column {
+                        facs: None,
                         lang: None,
                         div_type: "column".to_string(),
                         n: None,
                         lines: vec![
                             Line {
+                                facs: None,
                                 lang: None,
                                 div_type: "line".to_string(),
                                 n: Some(
@@ -1726,7 +2767,7 @@ mod test {
                                             value: TextDamageOrChoice::Damage(
                                                 Damage {
                                                     lang: None,
-                                                    cert: Some("high".to_string()),
+                                                    cert: Some(Certainty::High),
                                                     agent: "water".to_string(),
                                                     content: "d".to_string(),
                                                 },
@@ -1746,9 +2787,9 @@ mod test {
                                             reason: "lost".to_string(),
                                             n: 12,
                                             unit: ExtentUnit::Character,
-                                            cert: Some(
-                                                "0.10".to_string(),
-                                            ),
+                                            cert: Some(Certainty::Probability(
+                                                Probability::new(0.1).unwrap(),
+                                            )),
                                         },
                                     ),
                                     InlineBlock::P(
@@ -1811,7 +2852,7 @@ mod test {
     fn lang_attribute_serialized_with_xml() {
         let dmg = Damage {
             lang: Some("language".to_string()),
-            cert: Some("high".to_string()),
+            cert: Some(Certainty::High),
             agent: "agent".to_string(),
             content: "text".to_string(),
         };
@@ -1828,7 +2869,7 @@ mod test {
     fn none_language_ser_deser() {
         let block = Damage {
             lang: None,
-            cert: Some("high".to_string()),
+            cert: Some(Certainty::High),
             agent: "agent".to_string(),
             content: "text".to_string(),
         };
@@ -1872,9 +2913,9 @@ mod test {
             reason: "lost".to_string(),
             n: 2,
             unit: ExtentUnit::Column,
-            cert: Some("high".to_string()),
+            cert: Some(Certainty::High),
         };
-        let deser: Gap = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Gap = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(deser, expected);
         let sr = quick_xml::se::to_string_with_root("gap", &expected).unwrap();
         assert_eq!(sr, xml);
@@ -1889,7 +2930,7 @@ mod test {
             unit: ExtentUnit::Column,
             cert: None,
         };
-        let deser: Gap = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Gap = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(deser, expected);
         let sr = quick_xml::se::to_string_with_root("gap", &expected).unwrap();
         assert_eq!(sr, xml);
@@ -1904,7 +2945,7 @@ mod test {
             agent: "water".to_string(),
             content: "content".to_string(),
         };
-        let deser: Damage = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Damage = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(expected, deser);
         let ser = quick_xml::se::to_string_with_root("damage", &deser).unwrap();
         assert_eq!(ser, xml);
@@ -1924,7 +2965,7 @@ mod test {
                 content: "יהוה".to_string(),
             },
         };
-        let deser: Choice = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Choice = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(expected, deser);
         let ser = quick_xml::se::to_string_with_root("choice", &deser).unwrap();
         assert_eq!(ser, xml);
@@ -1941,7 +2982,7 @@ mod test {
                 content: "יהוה".to_string(),
             },
         };
-        let deser: Choice = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Choice = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(expected, deser);
         let ser = quick_xml::se::to_string_with_root("choice", &deser).unwrap();
         assert_eq!(ser, xml);
@@ -1954,12 +2995,193 @@ mod test {
             quantity: 7,
             unit: ExtentUnit::Character,
         };
-        let deser: Space = quick_xml::de::from_str(&xml).unwrap();
+        let deser: Space = quick_xml::de::from_str(xml).unwrap();
         assert_eq!(expected, deser);
         let ser = quick_xml::se::to_string_with_root("space", &deser).unwrap();
         assert_eq!(ser, xml);
     }
 
+    /// handDesc with individual hands
+    #[test]
+    fn hand_desc_hand_notes() {
+        let xml = r#"<handDesc><summary>Two hands.</summary><handNote xml:id="hand1" scribe="Scribe A" script="square" notBefore="0900" notAfter="1000">The main hand</handNote><handNote xml:id="hand2"/></handDesc>"#;
+        let expected = HandDesc {
+            summary: Some("Two hands.".to_string()),
+            hand_notes: vec![
+                HandNote {
+                    xml_id: "hand1".to_string(),
+                    scribe: Some("Scribe A".to_string()),
+                    script: Some("square".to_string()),
+                    not_before: Some("0900".to_string()),
+                    not_after: Some("1000".to_string()),
+                    description: Some("The main hand".to_string()),
+                },
+                HandNote {
+                    xml_id: "hand2".to_string(),
+                    scribe: None,
+                    script: None,
+                    not_before: None,
+                    not_after: None,
+                    description: None,
+                },
+            ],
+        };
+        let deser: HandDesc = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(deser, expected);
+        let ser = quick_xml::se::to_string_with_root("handDesc", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
+    #[test]
+    fn history_origin_provenance() {
+        let xml = r#"<history><origin><origDate notBefore="0900" notAfter="0950">first half of the 10th century</origDate><origPlace>Tiberias</origPlace></origin><provenance>Cairo Genizah</provenance><provenance>Firkovich collection</provenance></history>"#;
+        let expected = History {
+            origin: Some(Origin {
+                orig_date: Some(OrigDate {
+                    when: None,
+                    not_before: Some("0900".to_string()),
+                    not_after: Some("0950".to_string()),
+                    description: Some("first half of the 10th century".to_string()),
+                }),
+                orig_place: Some("Tiberias".to_string()),
+            }),
+            provenance: vec![
+                "Cairo Genizah".to_string(),
+                "Firkovich collection".to_string(),
+            ],
+        };
+        let deser: History = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(deser, expected);
+        let ser = quick_xml::se::to_string_with_root("history", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
+    #[test]
+    fn object_desc() {
+        let xml = r#"<objectDesc><supportDesc material="parchment"><support>Fine parchment</support><extent><measure unit="leaf" quantity="491">491 leaves</measure><dimensions type="leaf" unit="mm"><height>340</height><width>295</width></dimensions></extent></supportDesc><layoutDesc><layout columns="3" writtenLines="27 29"/></layoutDesc></objectDesc>"#;
+        let expected = ObjectDesc {
+            support_desc: Some(SupportDesc {
+                material: Some(Material::Parchment),
+                support: Some("Fine parchment".to_string()),
+                extent: Some(Extent {
+                    measure: vec![Measure {
+                        unit: "leaf".to_string(),
+                        quantity: 491,
+                        description: Some("491 leaves".to_string()),
+                    }],
+                    dimensions: vec![Dimensions {
+                        dim_type: Some("leaf".to_string()),
+                        unit: Some("mm".to_string()),
+                        height: Some(340),
+                        width: Some(295),
+                    }],
+                }),
+            }),
+            layout_desc: Some(LayoutDesc {
+                layout: vec![Layout {
+                    columns: Some(Count { min: 3, max: 3 }),
+                    written_lines: Some(Count { min: 27, max: 29 }),
+                    description: None,
+                }],
+            }),
+        };
+        let deser: ObjectDesc = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(deser, expected);
+        assert_eq!(
+            deser
+                .support_desc
+                .as_ref()
+                .unwrap()
+                .extent
+                .as_ref()
+                .unwrap()
+                .leaves(),
+            Some(491)
+        );
+        let ser = quick_xml::se::to_string_with_root("objectDesc", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
+    #[test]
+    fn count_parse() {
+        assert_eq!("3".parse(), Ok(Count { min: 3, max: 3 }));
+        assert_eq!(" 27  29 ".parse(), Ok(Count { min: 27, max: 29 }));
+        assert_eq!(
+            "29 27".parse::<Count>(),
+            Err(InvalidCount("29 27".to_string()))
+        );
+        assert!("".parse::<Count>().is_err());
+        assert!("1 2 3".parse::<Count>().is_err());
+        assert!("two".parse::<Count>().is_err());
+        assert!(Count { min: 27, max: 29 }.contains(28));
+        assert!(!Count { min: 27, max: 29 }.contains(30));
+    }
+
+    #[test]
+    fn revision_desc() {
+        let xml = r#"<revisionDesc><change who="jdoe" when="2025-03-01" status="transcribed" target="34_v">First transcription</change><change who="mmuster" when="2025-04-12" status="reviewed" target="34_v"/></revisionDesc>"#;
+        let expected = RevisionDesc {
+            change: vec![
+                Change {
+                    who: Some("jdoe".to_string()),
+                    when: Some("2025-03-01".to_string()),
+                    status: Some(PageStatus::Transcribed),
+                    target: Some("34_v".to_string()),
+                    description: Some("First transcription".to_string()),
+                },
+                Change {
+                    who: Some("mmuster".to_string()),
+                    when: Some("2025-04-12".to_string()),
+                    status: Some(PageStatus::Reviewed),
+                    target: Some("34_v".to_string()),
+                    description: None,
+                },
+            ],
+        };
+        let deser: RevisionDesc = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(deser, expected);
+        let ser = quick_xml::se::to_string_with_root("revisionDesc", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
+    #[test]
+    fn facsimile() {
+        let xml = r#"<facsimile><surface xml:id="s1"><graphic url="https://example.org/1.jpg" width="2000" height="3000"/><zone xml:id="z1" ulx="100" uly="200" lrx="900" lry="300"/><zone xml:id="z2" points="100,500 900,480 910,590 100,600"/></surface></facsimile>"#;
+        let deser: Facsimile = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(
+            deser.surface[0].graphic,
+            Some(Graphic {
+                url: "https://example.org/1.jpg".to_string(),
+                width: Some(2000),
+                height: Some(3000),
+            })
+        );
+        assert_eq!(deser.surface("#s1").map(|s| s.zone.len()), Some(2));
+        let (surface, zone) = deser.zone("#z1").unwrap();
+        assert_eq!(surface.xml_id, "s1");
+        assert_eq!(zone.bounding_box(), Some((100, 200, 900, 300)));
+        assert_eq!(
+            deser.zone("z2").unwrap().1.bounding_box(),
+            Some((100, 480, 910, 600))
+        );
+        assert!(!deser.contains("#z3"));
+        let ser = quick_xml::se::to_string_with_root("facsimile", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
+    #[test]
+    fn points_parse() {
+        assert_eq!(
+            " 1,2   -3,4 ".parse(),
+            Ok(Points(vec![Point { x: 1, y: 2 }, Point { x: -3, y: 4 }]))
+        );
+        assert_eq!(
+            "1,2 3".parse::<Points>(),
+            Err(InvalidPoints("1,2 3".to_string()))
+        );
+        assert!("1;2".parse::<Points>().is_err());
+    }
+
     /// We should be able to parse files without HandDesc
     #[test]
     fn empty_hand_desc() {
@@ -1982,17 +3204,84 @@ mod test {
         assert_eq!(
             res.unwrap(),
             MsIdentifier {
+                country: None,
+                settlement: None,
                 institution: Some("NLR".to_string()),
-                alt_identifier: vec![],
+                repository: None,
                 collection: Some("EVRII".to_string()),
+                idno: None,
+                alt_identifier: vec![],
             }
         );
     }
 
+    /// A complete msIdentifier with typed alternative identifiers
+    #[test]
+    fn full_ms_identifier() {
+        let xml = r#"<msIdentifier><country>Russia</country><settlement>St. Petersburg</settlement><institution>NLR</institution><repository>Manuscript Department</repository><collection>EVR I</collection><idno>B 19 A</idno><altIdentifier><idno type="Kennicott">1000</idno></altIdentifier><altIdentifier><idno type="de-Rossi">1</idno></altIdentifier><altIdentifier><idno type="Codex">L</idno></altIdentifier><altIdentifier><idno>Leningradensis</idno></altIdentifier></msIdentifier>"#;
+        let expected = MsIdentifier {
+            country: Some("Russia".to_string()),
+            settlement: Some("St. Petersburg".to_string()),
+            institution: Some("NLR".to_string()),
+            repository: Some("Manuscript Department".to_string()),
+            collection: Some("EVR I".to_string()),
+            idno: Some(IdNo {
+                id_type: None,
+                name: "B 19 A".to_string(),
+            }),
+            alt_identifier: vec![
+                AltIdentifier {
+                    idno: IdNo {
+                        id_type: Some(IdNoType::Kennicott),
+                        name: "1000".to_string(),
+                    },
+                },
+                AltIdentifier {
+                    idno: IdNo {
+                        id_type: Some(IdNoType::DeRossi),
+                        name: "1".to_string(),
+                    },
+                },
+                AltIdentifier {
+                    idno: IdNo {
+                        id_type: Some(IdNoType::Other("Codex".to_string())),
+                        name: "L".to_string(),
+                    },
+                },
+                AltIdentifier {
+                    idno: IdNo {
+                        id_type: None,
+                        name: "Leningradensis".to_string(),
+                    },
+                },
+            ],
+        };
+        let deser: MsIdentifier = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(deser, expected);
+        let ser = quick_xml::se::to_string_with_root("msIdentifier", &deser).unwrap();
+        assert_eq!(ser, xml);
+    }
+
     #[test]
     fn empty_text() {
         let xml = "<p/>";
         let res: Result<TDOCWrapper, _> = quick_xml::de::from_str(xml);
         assert!(dbg!(res).is_ok());
     }
+
+    #[test]
+    fn page_status_names() {
+        for status in [
+            PageStatus::Draft,
+            PageStatus::Transcribed,
+            PageStatus::Reviewed,
+            PageStatus::Approved,
+        ] {
+            assert_eq!(status.name().parse(), Ok(status));
+        }
+        assert_eq!(
+            "done".parse::<PageStatus>(),
+            Err(UnknownPageStatus("done".to_string()))
+        );
+    }
 }
@@ -10,7 +10,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::normalized;
+use crate::{
+    contents::{ContentRange, content_ranges},
+    destream::StreamError,
+    normalized,
+};
 
 /// An entire manuscript with its content streamed
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -19,9 +23,343 @@ pub struct Manuscript {
     pub meta: Meta,
     /// the actual text in individual blocks
     pub content: Vec<Block>,
+    /// The images the text is linked to with [`facs`](Manuscript::facs)
+    pub facsimile: Facsimile,
+    /// The links from pages, columns and lines to the [`facsimile`](Manuscript::facsimile)
+    ///
+    /// There are no page, column or line elements in the stream to put these on, so they are kept
+    /// here and addressed by [`Position`].
+    pub facs: Vec<FacsLink>,
+}
+impl Manuscript {
+    /// The names of all pages that are missing from this manuscript.
+    ///
+    /// See [`missing_pages`].
+    #[must_use]
+    pub fn missing_pages(&self) -> Vec<&str> {
+        missing_pages(&self.content)
+    }
+
+    /// Replace the page named [`page.name`](Page::name) with `page` and record `change` for it.
+    ///
+    /// The content, status and [`facs`](Manuscript::facs) links of the page are replaced. The page of
+    /// `change` and of the new links is set to the name of the page. Returns the previous page.
+    ///
+    /// # Errors
+    /// Without changing anything, if there is no such page, the new content contains a page break,
+    /// or it does not form a valid page with its links.
+    pub fn replace_page(
+        &mut self,
+        mut page: Page,
+        mut change: Change,
+    ) -> Result<Page, ReplacePageError> {
+        if let Some(idx) = page
+            .blocks
+            .iter()
+            .position(|b| matches!(b, Block::Break(BreakType::Page(..))))
+        {
+            return Err(ReplacePageError::PageBreak(idx));
+        }
+        for link in &mut page.facs {
+            link.page.clone_from(&page.name);
+        }
+        normalized::Page::try_from(page.clone()).map_err(ReplacePageError::Invalid)?;
+        let start = self
+            .content
+            .iter()
+            .position(|b| matches!(b, Block::Break(BreakType::Page(n, _)) if *n == page.name))
+            .ok_or_else(|| ReplacePageError::NoSuchPage(page.name.clone()))?;
+        let end = self.content[start + 1..]
+            .iter()
+            .position(|b| matches!(b, Block::Break(BreakType::Page(..))))
+            .map_or(self.content.len(), |len| start + 1 + len);
+        let old_blocks = self.content.splice(start + 1..end, page.blocks).collect();
+        let old_status = match &mut self.content[start] {
+            Block::Break(BreakType::Page(_, status)) => core::mem::replace(status, page.status),
+            _ => None,
+        };
+        let (old_facs, facs) = core::mem::take(&mut self.facs)
+            .into_iter()
+            .partition(|link| link.page == page.name);
+        self.facs = facs;
+        self.facs.extend(page.facs);
+        // keep the links in document order, as `from_xml` returns them
+        let pages = self
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                Block::Break(BreakType::Page(n, _)) => Some(n.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.facs
+            .sort_by_key(|link| pages.iter().position(|n| *n == link.page));
+        change.page = Some(page.name.clone());
+        self.meta.changes.push(change);
+        Ok(Page {
+            name: page.name,
+            status: old_status,
+            blocks: old_blocks,
+            facs: old_facs,
+        })
+    }
+
+    /// Set the workflow status of the page `page`.
+    ///
+    /// Returns false without changing anything if there is no such page.
+    pub fn set_page_status(&mut self, page: &str, status: Option<PageStatus>) -> bool {
+        for block in &mut self.content {
+            if let Block::Break(BreakType::Page(n, s)) = block
+                && n == page
+            {
+                *s = status;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The number of pages in each [`PageStatus`].
+    #[must_use]
+    pub fn status_counts(&self) -> normalized::StatusCounts {
+        let mut res = normalized::StatusCounts::default();
+        for block in &self.content {
+            if let Block::Break(BreakType::Page(_, status)) = block {
+                res.add(*status);
+            }
+        }
+        res
+    }
+
+    /// The contiguous passages in this manuscript, derived from the verse anchors.
+    ///
+    /// See [`content_ranges`](crate::contents::content_ranges).
+    #[must_use]
+    pub fn content_ranges(&self) -> Vec<ContentRange> {
+        content_ranges(&self.content)
+    }
+
+    /// Replace the [`contents`](Meta::contents) in the header with the passages derived from the
+    /// verse anchors.
+    ///
+    /// Verses lost at the end of a chapter are not detected, see [`contents`](crate::contents).
+    pub fn derive_contents(&mut self) {
+        self.meta.contents = self.content_ranges().iter().map(Into::into).collect();
+    }
+
+    /// The `facs` of the block at `idx` in [`content`](Manuscript::content).
+    ///
+    /// This is the `facs` of its line if there is one, else that of its column, else that of its
+    /// page. Use [`Facsimile::zone`] or [`Facsimile::surface`] to find the image region.
+    #[must_use]
+    pub fn facs_at(&self, idx: usize) -> Option<&str> {
+        let pos = position_of(&self.content, idx)?;
+        let find = |column, line| {
+            self.facs
+                .iter()
+                .find(|f| f.page == pos.page && f.column == column && f.line == line)
+                .map(|f| f.facs.as_str())
+        };
+        find(Some(pos.column), Some(pos.line))
+            .or_else(|| find(Some(pos.column), None))
+            .or_else(|| find(None, None))
+    }
+
+    /// The aggregated certainty of all [`Uncertain`] and [`Lacuna`] blocks on each page, in
+    /// order.
+    #[must_use]
+    pub fn certainty_by_page(&self) -> Vec<(&str, normalized::CertaintySummary)> {
+        let mut res = Vec::new();
+        for block in &self.content {
+            match block {
+                Block::Break(BreakType::Page(n, _)) => {
+                    res.push((n.as_str(), normalized::CertaintySummary::default()));
+                }
+                Block::Uncertain(Uncertain { cert, .. }) | Block::Lacuna(Lacuna { cert, .. }) => {
+                    if let Some((_, summary)) = res.last_mut() {
+                        summary.add(*cert);
+                    }
+                }
+                _ => {}
+            }
+        }
+        res
+    }
+}
+
+/// A single page, e.g. read with [`page_from_xml_with_options`](crate::page_from_xml_with_options)
+/// to replace a page with [`Manuscript::replace_page`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Page {
+    /// The name of the page
+    pub name: String,
+    /// How far the transcription of this page has progressed
+    pub status: Option<PageStatus>,
+    /// The content of the page, without a leading page break
+    pub blocks: Vec<Block>,
+    /// The links from the page, its columns and its lines to the [`Facsimile`]
+    pub facs: Vec<FacsLink>,
+}
+
+/// The reasons [`Manuscript::replace_page`] can fail.
+#[derive(Debug, PartialEq)]
+pub enum ReplacePageError {
+    /// There is no page with this name
+    NoSuchPage(String),
+    /// The new content contains a page break at this index
+    PageBreak(usize),
+    /// The new content does not form a valid page, or a link points to a column or line that is
+    /// not on it
+    Invalid(StreamError),
+}
+impl core::fmt::Display for ReplacePageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::NoSuchPage(x) => {
+                write!(f, "There is no page \"{x}\".")
+            }
+            Self::PageBreak(x) => {
+                write!(
+                    f,
+                    "The new content of the page contains a page break at index {x}."
+                )
+            }
+            Self::Invalid(e) => {
+                write!(f, "The new content is not a valid page: {e}")
+            }
+        }
+    }
+}
+impl core::error::Error for ReplacePageError {}
+
+/// The logical place of a block, numbered as in [`normalized`].
+///
+/// Column- and line-spanning [`Lacuna`]s and [`Space`]s skip as many columns or lines as they
+/// span.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Position {
+    /// The name of the page
+    pub page: String,
+    pub column: i32,
+    pub line: i32,
+}
+
+impl Position {
+    /// The position of `block` if it starts a page, i.e. is a page break.
+    #[must_use]
+    pub fn start_of(block: &Block) -> Option<Self> {
+        match block {
+            Block::Break(BreakType::Page(n, _)) => Some(Self {
+                page: n.clone(),
+                column: 1,
+                line: 1,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The position of the block following `block`, if `block` is at this position.
+    ///
+    /// Returns `None` if the number of the column or line does not fit into an [`i32`].
+    #[must_use]
+    pub fn after(&self, block: &Block) -> Option<Self> {
+        if let Some(start) = Self::start_of(block) {
+            return Some(start);
+        }
+        let mut pos = self.clone();
+        match block {
+            Block::Break(BreakType::Line) => pos.line = pos.line.checked_add(1)?,
+            Block::Break(BreakType::Column) => {
+                pos.column = pos.column.checked_add(1)?;
+                pos.line = 1;
+            }
+            Block::Lacuna(Lacuna {
+                unit: ExtentUnit::Line,
+                n: extent,
+                ..
+            })
+            | Block::Space(Space {
+                unit: ExtentUnit::Line,
+                quantity: extent,
+            }) => pos.line = pos.line.checked_add(1)?.checked_add(*extent)?,
+            Block::Lacuna(Lacuna {
+                unit: ExtentUnit::Column,
+                n: extent,
+                ..
+            })
+            | Block::Space(Space {
+                unit: ExtentUnit::Column,
+                quantity: extent,
+            }) => {
+                pos.column = pos.column.checked_add(1)?.checked_add(*extent)?;
+                pos.line = 1;
+            }
+            _ => {}
+        }
+        Some(pos)
+    }
+}
+
+/// The [`Position`] of the block at `idx` in `blocks`.
+///
+/// A page break is at the first line of the first column of its page. Returns `None` if `idx` is
+/// out of bounds, before the first page break, or its column or line number does not fit into an
+/// [`i32`].
+#[must_use]
+pub fn position_of(blocks: &[Block], idx: usize) -> Option<Position> {
+    let mut pos: Option<Position> = None;
+    for (i, block) in blocks.get(..=idx)?.iter().enumerate() {
+        // the block at `idx` is still on the position before any break it causes
+        if i == idx {
+            return Position::start_of(block).or(pos);
+        }
+        pos = match pos {
+            Some(p) => Some(p.after(block)?),
+            None => Position::start_of(block),
+        };
+    }
+    pos
+}
+
+/// A link from a page, column or line to the [`Facsimile`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct FacsLink {
+    /// The name of the page
+    pub page: String,
+    /// The column, or `None` for the page itself
+    pub column: Option<i32>,
+    /// The line, or `None` for the column (or page) itself
+    pub line: Option<i32>,
+    /// The pointer to the [`Surface`] or [`Zone`]
+    pub facs: String,
+}
+
+/// The names of all pages in `blocks` that are missing from the manuscript.
+///
+/// In the streamed form, a missing page is a [`BreakType::Page`] that is immediately followed by
+/// another [`BreakType::Page`] or by the end of the stream.
+#[must_use]
+pub fn missing_pages(blocks: &[Block]) -> Vec<&str> {
+    blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, block)| match block {
+            Block::Break(BreakType::Page(n, _)) => match blocks.get(idx + 1) {
+                None | Some(Block::Break(BreakType::Page(..))) => Some(n.as_str()),
+                Some(_) => None,
+            },
+            _ => None,
+        })
+        .collect()
 }
 
 pub type Meta = normalized::Meta;
+pub type Hand = normalized::Hand;
+pub type Change = normalized::Change;
+pub type PageStatus = normalized::PageStatus;
+pub type Facsimile = normalized::Facsimile;
+pub type Surface = normalized::Surface;
+pub type Zone = normalized::Zone;
 
 /// A block in the editor, without the associated language
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -202,8 +540,8 @@ pub enum BreakType {
     /// Columnbreak
     Column,
     /// Pagebreak
-    /// contains the name of the next page
-    Page(String),
+    /// contains the name and workflow status of the next page
+    Page(String, Option<PageStatus>),
 }
 /// Default for user facing code
 impl Default for BreakType {
@@ -221,7 +559,7 @@ impl core::str::FromStr for BreakType {
             "Column" => Ok(Self::Column),
             // TODO
             // I do not think that this case will ever be necessary, but am unsure
-            "Page" => Ok(Self::Page(String::default())),
+            "Page" => Ok(Self::Page(String::default(), None)),
             _ => Err(()),
         }
     }
@@ -234,7 +572,7 @@ impl BreakType {
             Self::Column => "Column",
             // TODO
             // I do not think that this case will ever be necessary, but am unsure
-            Self::Page(_e) => "Page",
+            Self::Page(..) => "Page",
         }
     }
 }
@@ -344,7 +682,7 @@ pub struct Version {
     pub lang: String,
     /// The scribal hand responsible for this reading
     ///
-    /// The different hands should be explained in the [`<handDesc>`](crate::schema::HandDesc) in the header.
+    /// This should refer to one of the [`hands`](Meta::hands) in the header.
     pub hand: Option<String>,
     /// The actual text of this reading
     pub content: String,
@@ -368,7 +706,7 @@ pub struct Uncertain {
     /// The language of this uncertain passage
     pub lang: String,
     /// The certainty the transcriber assigns to the reconstruction of the damaged text
-    pub cert: Option<String>,
+    pub cert: Option<Certainty>,
     /// The cause of damage
     pub agent: String,
     /// The reproduction of the damaged text
@@ -382,7 +720,7 @@ impl Uncertain {
     ) -> Self {
         Self {
             lang: self.lang.clone(),
-            cert: self.cert.clone(),
+            cert: self.cert,
             agent: self.agent.clone(),
             content: truncated_to(&self.content, max_content_length, equality_alphabet),
         }
@@ -423,3 +761,4 @@ impl Abbreviation {
 
 pub type Space = crate::normalized::Space;
 pub type ExtentUnit = normalized::ExtentUnit;
+pub type Certainty = normalized::Certainty;
@@ -159,7 +159,7 @@ impl SurfaceBaseText {
     /// use critic_format::surface_form::SurfaceBaseText;
     ///
     /// let streamed = vec![
-    ///     Block::Break(BreakType::Page("page1".to_string())),
+    ///     Block::Break(BreakType::Page("page1".to_string(), None)),
     ///     Block::Text(Paragraph {
     ///         lang: "de".to_string(),
     ///         content: "Und also sprach Zarathustra:".to_string()
@@ -196,7 +196,7 @@ impl SurfaceBaseText {
     /// use critic_format::surface_form::SurfaceBaseText;
     ///
     /// let streamed = vec![
-    ///     Block::Break(BreakType::Page("page1".to_string())),
+    ///     Block::Break(BreakType::Page("page1".to_string(), None)),
     ///     Block::Text(Paragraph {
     ///         lang: "grc".to_string(),
     ///         content: "και ειπεν αυτου".to_string()
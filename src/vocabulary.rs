@@ -0,0 +1,127 @@
+//! Controlled vocabularies for attribute values that are free text in the xml.
+//!
+//! The `@agent` of [`<damage>`](crate::schema::Damage) and the `@reason` of
+//! [`<gap>`](crate::schema::Gap) are strings in TEI. Normalization always brings them into the form
+//! required by the spec (see [`normalize_term`]). To keep these values comparable across
+//! manuscripts, it also checks them against a [`Vocabulary`], given in
+//! [`Options::vocabulary`](crate::Options::vocabulary). This is the [`Vocabulary::default`] unless
+//! the caller opts out by setting it to `None`; then any term is accepted.
+
+use serde::{Deserialize, Serialize};
+
+/// The agents recommended by the spec for [`<damage>`](crate::schema::Damage).
+pub const DEFAULT_DAMAGE_AGENTS: [&str; 4] = ["smeared", "smeared-diacritica", "burned", "water"];
+
+/// The reasons recommended by the spec for [`<gap>`](crate::schema::Gap).
+pub const DEFAULT_GAP_REASONS: [&str; 4] = ["lost", "smeared", "burned", "water"];
+
+/// The values allowed for damage agents and gap reasons.
+///
+/// The empty string is always allowed and stands for a value that was not (yet) given.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Vocabulary {
+    /// The values allowed in `<damage agent="…">`
+    pub damage_agents: Vec<String>,
+    /// The values allowed in `<gap reason="…">`
+    pub gap_reasons: Vec<String>,
+}
+/// The vocabulary recommended by the spec.
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self {
+            damage_agents: DEFAULT_DAMAGE_AGENTS
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+            gap_reasons: DEFAULT_GAP_REASONS
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+        }
+    }
+}
+impl Vocabulary {
+    /// Normalize `agent` and return it if it is an allowed damage agent.
+    ///
+    /// See [`normalize_term`].
+    #[must_use]
+    pub fn damage_agent(&self, agent: &str) -> Option<String> {
+        allowed(&self.damage_agents, agent)
+    }
+
+    /// Normalize `reason` and return it if it is an allowed gap reason.
+    ///
+    /// See [`normalize_term`].
+    #[must_use]
+    pub fn gap_reason(&self, reason: &str) -> Option<String> {
+        allowed(&self.gap_reasons, reason)
+    }
+}
+
+/// Normalize `agent` and return it if it is allowed by `vocabulary`, or if there is none.
+pub(crate) fn checked_damage_agent(vocabulary: Option<&Vocabulary>, agent: &str) -> Option<String> {
+    vocabulary.map_or_else(|| Some(normalize_term(agent)), |v| v.damage_agent(agent))
+}
+
+/// Normalize `reason` and return it if it is allowed by `vocabulary`, or if there is none.
+pub(crate) fn checked_gap_reason(vocabulary: Option<&Vocabulary>, reason: &str) -> Option<String> {
+    vocabulary.map_or_else(|| Some(normalize_term(reason)), |v| v.gap_reason(reason))
+}
+
+/// Normalize `term` and return it if it is in `values`.
+fn allowed(values: &[String], term: &str) -> Option<String> {
+    let normalized = normalize_term(term);
+    (normalized.is_empty() || values.contains(&normalized)).then_some(normalized)
+}
+
+/// Bring a term into the form required by the spec.
+///
+/// Terms MAY NOT contain whitespace. Words are joined with `-` instead, so `smeared diacritica`
+/// becomes `smeared-diacritica`.
+#[must_use]
+pub fn normalize_term(term: &str) -> String {
+    term.split_whitespace().collect::<Vec<_>>().join("-")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn term_whitespace() {
+        assert_eq!(normalize_term("smeared diacritica"), "smeared-diacritica");
+        assert_eq!(
+            normalize_term(" smeared \t diacritica "),
+            "smeared-diacritica"
+        );
+        assert_eq!(normalize_term("water"), "water");
+        assert_eq!(normalize_term(""), "");
+    }
+
+    #[test]
+    fn default_vocabulary() {
+        let vocab = Vocabulary::default();
+        assert_eq!(
+            vocab.damage_agent("smeared diacritica"),
+            Some("smeared-diacritica".to_string())
+        );
+        assert_eq!(vocab.damage_agent("lost"), None);
+        assert_eq!(vocab.gap_reason("lost"), Some("lost".to_string()));
+        assert_eq!(vocab.gap_reason("eaten by dog"), None);
+        assert_eq!(vocab.gap_reason(""), Some(String::new()));
+    }
+
+    #[test]
+    fn custom_vocabulary() {
+        let vocab = Vocabulary {
+            damage_agents: vec!["mould".to_string()],
+            gap_reasons: vec!["eaten-by-dog".to_string()],
+        };
+        assert_eq!(vocab.damage_agent("mould"), Some("mould".to_string()));
+        assert_eq!(vocab.damage_agent("water"), None);
+        assert_eq!(
+            vocab.gap_reason("eaten by dog"),
+            Some("eaten-by-dog".to_string())
+        );
+    }
+}
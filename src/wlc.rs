@@ -0,0 +1,395 @@
+//! Import of the [Westminster Leningrad Codex](https://tanach.us) in its XML edition.
+//!
+//! The WLC is split into books, chapters, verses and words (`<book>`, `<c>`, `<v>`, `<w>`). It is
+//! not a transcription of pages, so every chapter becomes a page and every verse a line of that
+//! page, starting with a verse anchor in the
+//! [`Masoretic`](MASORETIC_SCHEME) scheme. Books are named by their `<abbrev>`, which is the OSIS
+//! abbreviation. Pages are named `{book index}-{book}-{chapter}`, with the index of the book in
+//! the input and the chapter zero-padded (`01-Gen-001`), so that they sort in reading order.
+//!
+//! A ketiv (`<k>`) and the qere (`<q>`) following it become an
+//! [`Abbreviation`](streamed::Abbreviation): the ketiv is written, the qere is read. A ketiv
+//! without qere has an empty expansion, a qere without ketiv an empty surface. The morpheme
+//! separators `/` in words and the notes (`<x>`) are dropped.
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::streamed;
+
+/// The language of the WLC.
+pub const LANGUAGE: &str = "hbo-Hebr";
+/// The long form of the versification scheme of the anchors.
+pub const MASORETIC_SCHEME: &str = "Masoretic";
+/// The shorthand of the versification scheme of the anchors.
+pub const MASORETIC_SHORTHAND: &str = "MT";
+/// The title of the imported manuscript.
+pub const TITLE: &str = "Westminster Leningrad Codex";
+
+/// The problems that can occur when importing the WLC.
+#[derive(Debug)]
+pub enum WlcError {
+    /// The input is not well-formed XML
+    Xml(quick_xml::Error),
+    /// A chapter starts before the `<abbrev>` of its book
+    MissingBookAbbreviation,
+    /// This element has no `@n`
+    MissingNumber(String),
+    /// A verse is not inside a chapter
+    VerseOutsideChapter(String),
+}
+impl core::fmt::Display for WlcError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::Xml(e) => {
+                write!(f, "Unable to read the WLC: {e}")
+            }
+            Self::MissingBookAbbreviation => {
+                write!(f, "A book has chapters before its abbreviation.")
+            }
+            Self::MissingNumber(x) => {
+                write!(f, "An element <{x}> has no number in @n.")
+            }
+            Self::VerseOutsideChapter(x) => {
+                write!(f, "The verse {x} is not inside a chapter.")
+            }
+        }
+    }
+}
+impl core::error::Error for WlcError {}
+
+/// Elements whose content is not part of the text.
+const SKIPPED_ELEMENTS: [&[u8]; 4] = [b"teiHeader", b"x", b"vs", b"notes"];
+
+/// The `@n` of the chapter or verse `e`.
+fn number(e: &BytesStart) -> Result<String, WlcError> {
+    e.try_get_attribute("n")
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| {
+            WlcError::MissingNumber(String::from_utf8_lossy(e.local_name().as_ref()).into_owned())
+        })
+}
+
+/// Append `word` to `text`, separated by a space unless `text` ends in a maqaf.
+fn append_word(text: &mut String, word: &str) {
+    if !text.is_empty() && !text.ends_with('\u{05BE}') {
+        text.push(' ');
+    }
+    text.push_str(word);
+}
+
+/// The state while reading the WLC.
+#[derive(Default)]
+struct Importer {
+    blocks: Vec<streamed::Block>,
+    /// The number of books read so far, counting the current one
+    books: usize,
+    /// The `<abbrev>` of the current book
+    book: Option<String>,
+    chapter: Option<String>,
+    /// Whether the current chapter already has a verse
+    chapter_started: bool,
+    /// The local names of all open elements
+    open: Vec<Vec<u8>>,
+    /// The depth of the outermost skipped element, while in one
+    skipping: Option<usize>,
+    /// The text of the current `<abbrev>`, `<w>`, `<k>` or `<q>`
+    word: Option<String>,
+    /// The words of the current verse not yet pushed
+    text: String,
+    /// The ketiv and qere not yet pushed
+    ketiv: String,
+    qere: String,
+}
+impl Importer {
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            self.blocks.push(streamed::Block::Text(streamed::Paragraph {
+                lang: LANGUAGE.to_string(),
+                content: core::mem::take(&mut self.text),
+            }));
+        }
+    }
+
+    fn flush_ketiv_qere(&mut self) {
+        if self.ketiv.is_empty() && self.qere.is_empty() {
+            return;
+        }
+        self.flush_text();
+        self.blocks
+            .push(streamed::Block::Abbreviation(streamed::Abbreviation {
+                surface_lang: LANGUAGE.to_string(),
+                surface: core::mem::take(&mut self.ketiv),
+                expansion_lang: LANGUAGE.to_string(),
+                expansion: core::mem::take(&mut self.qere),
+            }));
+    }
+
+    fn start(&mut self, e: &BytesStart) -> Result<(), WlcError> {
+        let name = e.local_name().as_ref().to_vec();
+        self.open.push(name.clone());
+        if self.skipping.is_some() {
+            return Ok(());
+        }
+        match name.as_slice() {
+            n if SKIPPED_ELEMENTS.contains(&n) => self.skipping = Some(self.open.len()),
+            b"book" => {
+                self.books += 1;
+                self.book = None;
+            }
+            b"c" => {
+                if self.book.is_none() {
+                    return Err(WlcError::MissingBookAbbreviation);
+                }
+                self.chapter = Some(number(e)?);
+                self.chapter_started = false;
+            }
+            b"v" => {
+                let verse = number(e)?;
+                let (Some(book), Some(chapter)) = (self.book.as_ref(), self.chapter.as_ref())
+                else {
+                    return Err(WlcError::VerseOutsideChapter(verse));
+                };
+                let anchor = streamed::Block::Anchor(streamed::Anchor {
+                    anchor_id: format!("A_V_{MASORETIC_SHORTHAND}_{book}-{chapter}-{verse}"),
+                    anchor_type: MASORETIC_SCHEME.to_string(),
+                });
+                let separator = if self.chapter_started {
+                    streamed::BreakType::Line
+                } else {
+                    streamed::BreakType::Page(
+                        format!("{:02}-{book}-{chapter:0>3}", self.books),
+                        None,
+                    )
+                };
+                self.blocks.push(streamed::Block::Break(separator));
+                self.blocks.push(anchor);
+                self.chapter_started = true;
+            }
+            b"w" | b"k" | b"q" => self.word = Some(String::new()),
+            b"abbrev" if self.open.iter().any(|n| n == b"names") => {
+                self.word = Some(String::new());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) {
+        let depth = self.open.len();
+        let Some(name) = self.open.pop() else {
+            return;
+        };
+        if let Some(d) = self.skipping {
+            if d == depth {
+                self.skipping = None;
+            }
+            return;
+        }
+        match name.as_slice() {
+            b"abbrev" => self.book = self.word.take().map(|b| b.trim().to_string()),
+            b"w" | b"k" | b"q" => {
+                let word = self.word.take().unwrap_or_default().replace('/', "");
+                let word = word.trim();
+                if word.is_empty() {
+                    return;
+                }
+                match name.as_slice() {
+                    b"w" => {
+                        self.flush_ketiv_qere();
+                        append_word(&mut self.text, word);
+                    }
+                    b"k" => {
+                        // a ketiv after a qere starts the next pair
+                        if !self.qere.is_empty() {
+                            self.flush_ketiv_qere();
+                        }
+                        append_word(&mut self.ketiv, word);
+                    }
+                    _ => append_word(&mut self.qere, word),
+                }
+            }
+            b"v" => {
+                self.flush_ketiv_qere();
+                self.flush_text();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Read the WLC in the tanach.us XML format from `buf_reader`.
+///
+/// The input may contain any number of books. The [`contents`](streamed::Meta::contents) of the
+/// manuscript are derived from the verse anchors.
+///
+/// # Errors
+/// If the input is not well-formed or chapters and verses are not numbered.
+pub fn from_wlc(buf_reader: impl std::io::BufRead) -> Result<streamed::Manuscript, WlcError> {
+    let mut reader = quick_xml::Reader::from_reader(buf_reader);
+    reader.config_mut().expand_empty_elements = true;
+    let mut importer = Importer::default();
+    let mut buf = vec![];
+    loop {
+        match reader.read_event_into(&mut buf).map_err(WlcError::Xml)? {
+            Event::Start(e) => importer.start(&e)?,
+            Event::End(_) => importer.end(),
+            Event::Text(e) if importer.skipping.is_none() => {
+                if let Some(word) = importer.word.as_mut() {
+                    word.push_str(&e.decode().map_err(|e| WlcError::Xml(e.into()))?);
+                }
+            }
+            Event::GeneralRef(e) if importer.skipping.is_none() => {
+                if let Some(word) = importer.word.as_mut()
+                    && let Some(c) = e.resolve_char_ref().map_err(WlcError::Xml)?
+                {
+                    word.push(c);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    let mut ms = streamed::Manuscript {
+        meta: streamed::Meta {
+            title: TITLE.to_string(),
+            country: None,
+            settlement: None,
+            institution: None,
+            repository: None,
+            collection: None,
+            shelfmark: None,
+            hand_desc: None,
+            hands: vec![],
+            script_desc: None,
+            alt_identifier: vec![],
+            orig_date: None,
+            orig_date_desc: None,
+            orig_place: None,
+            provenance: vec![],
+            material: None,
+            support: None,
+            extent: None,
+            layouts: vec![],
+            contents: vec![],
+            changes: vec![],
+        },
+        content: importer.blocks,
+        facsimile: streamed::Facsimile::default(),
+        facs: vec![],
+    };
+    ms.derive_contents();
+    Ok(ms)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::streamed::{Block, BreakType};
+
+    fn sample() -> streamed::Manuscript {
+        let xml = std::fs::File::open("examples/wlc_sample.xml").unwrap();
+        from_wlc(std::io::BufReader::new(xml)).unwrap()
+    }
+
+    fn anchor(id: &str) -> Block {
+        Block::Anchor(streamed::Anchor {
+            anchor_id: id.to_string(),
+            anchor_type: MASORETIC_SCHEME.to_string(),
+        })
+    }
+
+    fn text(content: &str) -> Block {
+        Block::Text(streamed::Paragraph {
+            lang: LANGUAGE.to_string(),
+            content: content.to_string(),
+        })
+    }
+
+    #[test]
+    fn sample_blocks() {
+        let ms = sample();
+        assert_eq!(
+            ms.content,
+            vec![
+                Block::Break(BreakType::Page("01-Gen-001".to_string(), None)),
+                anchor("A_V_MT_Gen-1-1"),
+                text("בְּרֵאשִׁ֖ית בָּרָ֣א אֱלֹהִ֑ים אֵ֥ת הַשָּׁמַ֖יִם וְאֵ֥ת הָאָֽרֶץ׃"),
+                Block::Break(BreakType::Line),
+                anchor("A_V_MT_Gen-1-2"),
+                text("וְהָאָ֗רֶץ הָיְתָ֥ה תֹ֨הוּ֙ וָבֹ֔הוּ וְחֹ֖שֶׁךְ עַל־פְּנֵ֣י תְה֑וֹם וְר֣וּחַ אֱלֹהִ֔ים מְרַחֶ֖פֶת עַל־פְּנֵ֥י הַמָּֽיִם׃"),
+                Block::Break(BreakType::Page("01-Gen-008".to_string(), None)),
+                anchor("A_V_MT_Gen-8-17"),
+                text("כָּל־הַחַיָּ֨ה אֲשֶֽׁר־אִתְּךָ֜"),
+                Block::Abbreviation(streamed::Abbreviation {
+                    surface_lang: LANGUAGE.to_string(),
+                    surface: "הוצא".to_string(),
+                    expansion_lang: LANGUAGE.to_string(),
+                    expansion: "הַיְצֵ֣א".to_string(),
+                }),
+                text("אִתָּ֑ךְ"),
+            ]
+        );
+        assert_eq!(
+            ms.content_ranges()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["Gen 1:1–2", "Gen 8:17"]
+        );
+        assert_eq!(ms.meta.contents.len(), 2);
+    }
+
+    /// The imported text is a valid manuscript in our format.
+    #[test]
+    fn sample_to_xml() {
+        let ms = sample();
+        let xml = crate::to_xml(ms.clone()).unwrap();
+        assert_eq!(crate::from_xml(xml.as_bytes()).unwrap(), ms);
+    }
+
+    /// Chapters and books beyond the ninth still come out in reading order.
+    #[test]
+    fn page_order() {
+        let chapters = |book: &str, n: usize| {
+            let chapters: String = (1..=n)
+                .map(|c| format!("<c n=\"{c}\"><v n=\"1\"><w>א</w></v></c>"))
+                .collect();
+            format!("<book><names><abbrev>{book}</abbrev></names>{chapters}</book>")
+        };
+        let xml = format!(
+            "<Tanach><tanach>{}{}</tanach></Tanach>",
+            chapters("Gen", 12),
+            chapters("Exod", 2)
+        );
+        let ms = from_wlc(xml.as_bytes()).unwrap();
+        let pages: Vec<_> = ms
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                Block::Break(BreakType::Page(n, _)) => Some(n.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(pages.len(), 14);
+        assert_eq!(pages[9], "01-Gen-010");
+        assert_eq!(pages[12], "02-Exod-001");
+        assert!(pages.is_sorted());
+        let xml = crate::to_xml(ms.clone()).unwrap();
+        assert_eq!(crate::from_xml(xml.as_bytes()).unwrap(), ms);
+    }
+
+    #[test]
+    fn verse_outside_chapter() {
+        let xml = "<Tanach><book><names><abbrev>Gen</abbrev></names><v n=\"1\"/></book></Tanach>";
+        assert!(matches!(
+            from_wlc(xml.as_bytes()),
+            Err(WlcError::VerseOutsideChapter(v)) if v == "1"
+        ));
+    }
+}
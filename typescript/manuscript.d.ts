@@ -0,0 +1,212 @@
+// Generated from the JSON format of critic-format. Do not edit by hand.
+
+/**
+ * A single block of the text.
+ */
+export type Block = { "type": "Text", lang: string, content: string, } | { "type": "Uncertain", lang: string, 
+/**
+ * `high`, `medium`, `low`, `unknown` or a probability such as `0.75`
+ */
+cert?: string, 
+/**
+ * The cause of the uncertainty
+ */
+agent: string, content: string, } | { "type": "Abbreviation", surface_lang: string, 
+/**
+ * The abbreviation as written
+ */
+surface: string, expansion_lang: string, 
+/**
+ * The expansion supplied by the transcriber
+ */
+expansion: string, } | { "type": "Correction", versions: Array<Version>, } | { "type": "Lacuna", reason: string, unit: ExtentUnit, 
+/**
+ * The number of units lost
+ */
+n: number, 
+/**
+ * `high`, `medium`, `low`, `unknown` or a probability such as `0.75`
+ */
+cert?: string, } | { "type": "Space", quantity: number, unit: ExtentUnit, } | { "type": "Anchor", 
+/**
+ * `A_V_{scheme-shorthand}_{book}-{chapter}-{verse}`
+ */
+anchor_id: string, 
+/**
+ * The long form of the versification scheme
+ */
+anchor_type: string, } | { "type": "LineBreak" } | { "type": "ColumnBreak" } | { "type": "PageBreak", 
+/**
+ * The name of the page
+ */
+n: string, status?: PageStatus, };
+
+/**
+ * A change made to the file.
+ */
+export type Change = { who?: string, 
+/**
+ * As `YYYY[-MM[-DD]]`
+ */
+when?: string, status?: PageStatus, page?: string, description?: string, };
+
+/**
+ * A passage contained in the manuscript.
+ */
+export type ContentItem = { 
+/**
+ * The first page of the passage, given together with `to_page`
+ */
+from_page?: string, 
+/**
+ * The last page of the passage, given together with `from_page`
+ */
+to_page?: string, 
+/**
+ * The versification scheme of `passage`
+ */
+scheme?: string, 
+/**
+ * The passage, e.g. `Ps 26:10–31:4`
+ */
+passage: string, };
+
+/**
+ * A number or range of numbers.
+ */
+export type Count = { min: number, max: number, };
+
+/**
+ * The size of a part of the manuscript.
+ */
+export type Dimensions = { dim_type?: string, unit?: string, height?: number, width?: number, };
+
+/**
+ * The extent of the manuscript.
+ */
+export type Extent = { measures?: Array<Measure>, dimensions?: Array<Dimensions>, };
+
+/**
+ * The unit of a [`Block::Lacuna`] or [`Block::Space`].
+ */
+export type ExtentUnit = "character" | "line" | "column";
+
+/**
+ * A link from a page, column or line to a [`Surface`] or [`Zone`].
+ */
+export type FacsLink = { page: string, 
+/**
+ * The column, if the link is not for the page itself
+ */
+column?: number, 
+/**
+ * The line, if the link is not for the column or page itself
+ */
+line?: number, 
+/**
+ * The pointer to the surface or zone, e.g. `#z_01_r_c1`
+ */
+facs: string, };
+
+/**
+ * The image file of a [`Surface`].
+ */
+export type Graphic = { url: string, width?: number, height?: number, };
+
+/**
+ * A scribal hand.
+ */
+export type Hand = { id: string, description?: string, scribe?: string, script?: string, 
+/**
+ * As `YYYY[-MM[-DD]]`
+ */
+not_before?: string, 
+/**
+ * As `YYYY[-MM[-DD]]`
+ */
+not_after?: string, };
+
+/**
+ * An alternative identifier of the manuscript.
+ */
+export type Identifier = { 
+/**
+ * The catalogue, e.g. `Gregory-Aland` or `Rahlfs`
+ */
+id_type?: string, idno: string, };
+
+/**
+ * A layout used in the manuscript.
+ */
+export type Layout = { columns?: Count, written_lines?: Count, description?: string, };
+
+/**
+ * A complete manuscript.
+ */
+export type Manuscript = { 
+/**
+ * The version of this format, currently always 1
+ */
+version: number, meta: Meta, 
+/**
+ * The text, starting with a page break
+ */
+content: Array<Block>, 
+/**
+ * The images the text is linked to
+ */
+surfaces?: Array<Surface>, 
+/**
+ * The links from pages, columns and lines to the `surfaces`
+ */
+facs?: Array<FacsLink>, };
+
+/**
+ * A countable part of the manuscript, e.g. its leaves.
+ */
+export type Measure = { unit: string, quantity: number, description?: string, };
+
+/**
+ * The header with any meta-information.
+ */
+export type Meta = { title: string, country?: string, settlement?: string, institution?: string, repository?: string, collection?: string, shelfmark?: string, hand_desc?: string, hands?: Array<Hand>, script_desc?: string, alt_identifiers?: Array<Identifier>, 
+/**
+ * The earliest date this manuscript may have been written, as `YYYY[-MM[-DD]]`
+ */
+orig_not_before?: string, 
+/**
+ * The latest date this manuscript may have been written, as `YYYY[-MM[-DD]]`
+ */
+orig_not_after?: string, orig_date_desc?: string, orig_place?: string, provenance?: Array<string>, 
+/**
+ * `parchment`, `papyrus`, `paper` or any other material
+ */
+material?: string, support?: string, extent?: Extent, layouts?: Array<Layout>, contents?: Array<ContentItem>, changes?: Array<Change>, };
+
+/**
+ * The stage of the transcription workflow a page is in.
+ */
+export type PageStatus = "draft" | "transcribed" | "reviewed" | "approved";
+
+/**
+ * An image of a page.
+ */
+export type Surface = { id: string, graphic?: Graphic, zones?: Array<Zone>, };
+
+/**
+ * A single version of a [`Block::Correction`].
+ */
+export type Version = { lang: string, 
+/**
+ * The scribal hand responsible for this version, one of the `hands` in the `meta`
+ */
+hand?: string, content: string, };
+
+/**
+ * A region of a [`Surface`].
+ */
+export type Zone = { id: string, ulx?: number, uly?: number, lrx?: number, lry?: number, 
+/**
+ * A polygon as `x,y x,y …`
+ */
+points?: string, };